@@ -392,7 +392,7 @@ where
     type Digest = C::Digest;
 }
 
-#[cfg(all(test, feature = "arithmetic"))]
+#[cfg(all(test, feature = "dev"))]
 mod tests {
     use elliptic_curve::dev::MockCurve;
 
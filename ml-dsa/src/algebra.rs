@@ -0,0 +1,161 @@
+//! Arithmetic in the ring `R_q = Z_q[X]/(X^256 + 1)` used throughout ML-DSA
+//!
+//! Coefficients are always stored as canonical representatives in `[0, q)`. Signed quantities
+//! (such as the secret vectors or the signature response `z`) are mapped into this range and
+//! recovered with [`centered`] where the spec calls for `mod±`.
+use hybrid_array::{Array, ArraySize};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The ML-DSA modulus `q = 2^23 - 2^13 + 1`
+pub(crate) const Q: u32 = 8_380_417;
+
+/// The number of coefficients in a polynomial
+pub(crate) const N: usize = 256;
+
+/// The number of dropped bits from `t` (`d` in FIPS-204)
+pub(crate) const D: u32 = 13;
+
+/// Reduce a 64-bit value modulo `q`
+#[inline]
+#[allow(clippy::cast_possible_truncation)] // The result is always less than q
+pub(crate) const fn reduce(x: u64) -> u32 {
+    (x % Q as u64) as u32
+}
+
+/// Modular addition of two canonical field elements
+#[inline]
+pub(crate) const fn add(a: u32, b: u32) -> u32 {
+    let sum = a + b;
+    if sum >= Q {
+        sum - Q
+    } else {
+        sum
+    }
+}
+
+/// Modular subtraction of two canonical field elements
+#[inline]
+pub(crate) const fn sub(a: u32, b: u32) -> u32 {
+    add(a, Q - b)
+}
+
+/// Modular multiplication of two canonical field elements
+#[inline]
+pub(crate) const fn mul(a: u32, b: u32) -> u32 {
+    reduce(a as u64 * b as u64)
+}
+
+/// Map a canonical field element to its centered representative in `[-(q-1)/2, (q-1)/2]`
+#[inline]
+#[allow(clippy::cast_possible_wrap)] // Both operands are less than 2^23
+pub(crate) const fn centered(x: u32) -> i32 {
+    if x > (Q - 1) / 2 {
+        x as i32 - Q as i32
+    } else {
+        x as i32
+    }
+}
+
+/// Map a small signed integer to its canonical field element
+#[inline]
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)] // |x| < q < 2^31
+pub(crate) const fn from_signed(x: i32) -> u32 {
+    debug_assert!(x.unsigned_abs() < Q);
+    if x < 0 {
+        (x + Q as i32) as u32
+    } else {
+        x as u32
+    }
+}
+
+/// A polynomial in `R_q` (or `T_q` when in NTT form)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Poly(pub(crate) [u32; N]);
+
+impl Default for Poly {
+    fn default() -> Self {
+        Poly([0; N])
+    }
+}
+
+impl ConstantTimeEq for Poly {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl Poly {
+    /// Coefficient-wise addition
+    pub(crate) fn add(&self, other: &Poly) -> Poly {
+        Poly(core::array::from_fn(|i| add(self.0[i], other.0[i])))
+    }
+
+    /// Coefficient-wise subtraction
+    pub(crate) fn sub(&self, other: &Poly) -> Poly {
+        Poly(core::array::from_fn(|i| sub(self.0[i], other.0[i])))
+    }
+
+    /// Negation of every coefficient
+    pub(crate) fn neg(&self) -> Poly {
+        Poly(core::array::from_fn(|i| sub(0, self.0[i])))
+    }
+
+    /// Multiply every coefficient by `2^d`
+    pub(crate) fn shift_left_d(&self) -> Poly {
+        Poly(core::array::from_fn(|i| mul(self.0[i], 1 << D)))
+    }
+
+    /// The infinity norm `||p||∞` of the centered representatives
+    pub(crate) fn infinity_norm(&self) -> u32 {
+        self.0
+            .iter()
+            .map(|&x| centered(x).unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// A vector of polynomials of length `K`
+pub(crate) type Vector<K> = Array<Poly, K>;
+
+/// A `K x L` matrix of polynomials
+pub(crate) type Matrix<K, L> = Array<Vector<L>, K>;
+
+/// Coefficient-wise addition of two vectors
+pub(crate) fn vec_add<K: ArraySize>(a: &Vector<K>, b: &Vector<K>) -> Vector<K> {
+    Array::from_fn(|i| a[i].add(&b[i]))
+}
+
+/// Coefficient-wise subtraction of two vectors
+pub(crate) fn vec_sub<K: ArraySize>(a: &Vector<K>, b: &Vector<K>) -> Vector<K> {
+    Array::from_fn(|i| a[i].sub(&b[i]))
+}
+
+/// The infinity norm of a vector of polynomials
+pub(crate) fn vec_infinity_norm<K: ArraySize>(v: &Vector<K>) -> u32 {
+    v.iter().map(Poly::infinity_norm).max().unwrap_or(0)
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_possible_wrap)] // q < 2^31
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_add_sub_inverse(a in 0..Q, b in 0..Q) {
+            prop_assert_eq!(sub(add(a, b), b), a);
+        }
+
+        #[test]
+        fn test_mul_matches_wide(a in 0..Q, b in 0..Q) {
+            prop_assert_eq!(u64::from(mul(a, b)), (u64::from(a) * u64::from(b)) % u64::from(Q));
+        }
+
+        #[test]
+        fn test_centered_roundtrip(x in -((Q as i32 - 1) / 2)..=((Q as i32 - 1) / 2)) {
+            prop_assert_eq!(centered(from_signed(x)), x);
+        }
+    }
+}
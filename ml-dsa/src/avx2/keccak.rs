@@ -0,0 +1,219 @@
+//! Four SHAKE instances computed in parallel, with each 256-bit vector holding the same lane of
+//! four Keccak states
+#![allow(clippy::cast_possible_wrap)] // Intrinsics take signed integers
+
+use super::arch::{
+    __m256i, _mm256_andnot_si256, _mm256_or_si256, _mm256_set1_epi64x, _mm256_set_epi64x,
+    _mm256_setzero_si256, _mm256_sll_epi64, _mm256_srl_epi64, _mm256_storeu_si256,
+    _mm256_xor_si256, _mm_cvtsi32_si128,
+};
+
+/// Round constants of Keccak-f[1600]
+const RC: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// Rotation offsets of the ρ step, indexed by `x + 5 * y`
+const RHO: [i32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+type State = [__m256i; 25];
+
+#[target_feature(enable = "avx2")]
+unsafe fn rotl(x: __m256i, n: i32) -> __m256i {
+    _mm256_or_si256(
+        _mm256_sll_epi64(x, _mm_cvtsi32_si128(n)),
+        _mm256_srl_epi64(x, _mm_cvtsi32_si128(64 - n)),
+    )
+}
+
+/// Keccak-f[1600], applied to the four states
+#[target_feature(enable = "avx2")]
+unsafe fn keccak_f1600(a: &mut State) {
+    for rc in RC {
+        // θ
+        let mut c = [_mm256_setzero_si256(); 5];
+        for (x, c) in c.iter_mut().enumerate() {
+            for y in 0..5 {
+                *c = _mm256_xor_si256(*c, a[x + 5 * y]);
+            }
+        }
+        for x in 0..5 {
+            let d = _mm256_xor_si256(c[(x + 4) % 5], rotl(c[(x + 1) % 5], 1));
+            for y in 0..5 {
+                a[x + 5 * y] = _mm256_xor_si256(a[x + 5 * y], d);
+            }
+        }
+
+        // ρ and π
+        let mut b = [_mm256_setzero_si256(); 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = rotl(a[x + 5 * y], RHO[x + 5 * y]);
+            }
+        }
+
+        // χ
+        for x in 0..5 {
+            for y in 0..5 {
+                let not_b1_and_b2 =
+                    _mm256_andnot_si256(b[(x + 1) % 5 + 5 * y], b[(x + 2) % 5 + 5 * y]);
+                a[x + 5 * y] = _mm256_xor_si256(b[x + 5 * y], not_b1_and_b2);
+            }
+        }
+
+        // ι
+        a[0] = _mm256_xor_si256(a[0], _mm256_set1_epi64x(rc as i64));
+    }
+}
+
+/// Four SHAKE instances with a rate of `RATE` bytes, absorbing messages of equal length and
+/// squeezed in lockstep
+pub(crate) struct ShakeX4<const RATE: usize> {
+    state: State,
+    squeezed: bool,
+}
+
+/// Four SHAKE128 instances
+pub(crate) type Shake128x4 = ShakeX4<168>;
+
+/// Four SHAKE256 instances
+pub(crate) type Shake256x4 = ShakeX4<136>;
+
+impl<const RATE: usize> ShakeX4<RATE> {
+    /// Absorb one message into each instance.
+    ///
+    /// # Safety
+    /// The CPU must support AVX2.
+    ///
+    /// # Panics
+    /// Panics if the messages don't all have the same length.
+    #[target_feature(enable = "avx2")]
+    pub(crate) unsafe fn new(msgs: [&[u8]; 4]) -> Self {
+        let len = msgs[0].len();
+        assert!(msgs.iter().all(|msg| msg.len() == len));
+
+        let mut state = [_mm256_setzero_si256(); 25];
+        let mut offset = 0;
+        loop {
+            let n = RATE.min(len - offset);
+            let mut blocks = [[0u8; RATE]; 4];
+            for (block, msg) in blocks.iter_mut().zip(msgs) {
+                block[..n].copy_from_slice(&msg[offset..offset + n]);
+                if n < RATE {
+                    // SHAKE domain separation and pad10*1
+                    block[n] ^= 0x1f;
+                    block[RATE - 1] ^= 0x80;
+                }
+            }
+            Self::xor_blocks(&mut state, &blocks);
+            keccak_f1600(&mut state);
+
+            if n < RATE {
+                break;
+            }
+            offset += RATE;
+        }
+
+        ShakeX4 {
+            state,
+            squeezed: false,
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn xor_blocks(state: &mut State, blocks: &[[u8; RATE]; 4]) {
+        let word = |i: usize, k: usize| {
+            let mut w = [0u8; 8];
+            w.copy_from_slice(&blocks[i][8 * k..8 * (k + 1)]);
+            u64::from_le_bytes(w) as i64
+        };
+        for (k, lane) in state.iter_mut().take(RATE / 8).enumerate() {
+            let words = _mm256_set_epi64x(word(3, k), word(2, k), word(1, k), word(0, k));
+            *lane = _mm256_xor_si256(*lane, words);
+        }
+    }
+
+    /// Read the next block of output of each instance.
+    ///
+    /// # Safety
+    /// The CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub(crate) unsafe fn squeeze(&mut self, out: &mut [[u8; RATE]; 4]) {
+        if self.squeezed {
+            keccak_f1600(&mut self.state);
+        }
+        self.squeezed = true;
+
+        for (k, lane) in self.state.iter().take(RATE / 8).enumerate() {
+            let mut words = [0u64; 4];
+            _mm256_storeu_si256(words.as_mut_ptr().cast(), *lane);
+            for (out, word) in out.iter_mut().zip(words) {
+                out[8 * k..8 * (k + 1)].copy_from_slice(&word.to_le_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_possible_truncation)] // Test inputs are built from truncated indices
+mod tests {
+    use super::*;
+    use digest::{ExtendableOutput, Update, XofReader};
+    use proptest::prelude::*;
+
+    fn check<X: Default + Update + ExtendableOutput, const RATE: usize>(msgs: &[Vec<u8>; 4]) {
+        let mut expected = [[0u8; 3 * 168]; 4];
+        for (expected, msg) in expected.iter_mut().zip(msgs) {
+            let mut xof = X::default();
+            xof.update(msg);
+            xof.finalize_xof().read(&mut expected[..3 * RATE]);
+        }
+
+        // SAFETY: callers check AVX2 support
+        let mut xof = unsafe { ShakeX4::<RATE>::new(msgs.each_ref().map(Vec::as_slice)) };
+        let mut out = [[0u8; RATE]; 4];
+        for block in 0..3 {
+            // SAFETY: callers check AVX2 support
+            unsafe { xof.squeeze(&mut out) };
+            for (out, expected) in out.iter().zip(&expected) {
+                assert_eq!(out[..], expected[block * RATE..(block + 1) * RATE]);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_shake_x4_matches_portable(len in 0usize..400, seed: [u8; 4]) {
+            if super::super::available() {
+                let msgs = seed.map(|s| (0..len).map(|i| s.wrapping_add(i as u8)).collect());
+                check::<sha3::Shake128, 168>(&msgs);
+                check::<sha3::Shake256, 136>(&msgs);
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! AVX2 implementations of the NTT and of 4-way parallel SHAKE
+//!
+//! These are selected at runtime when the CPU supports AVX2, and always produce the same results
+//! as the portable implementations, which are used everywhere else.
+#[cfg(target_arch = "x86")]
+use core::arch::x86 as arch;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64 as arch;
+
+mod keccak;
+mod ntt;
+
+pub(crate) use keccak::{Shake128x4, Shake256x4};
+pub(crate) use ntt::{ntt, ntt_inverse, ntt_mul};
+
+cpufeatures::new!(avx2_cpuid, "avx2");
+
+/// Whether the CPU supports AVX2. The result of the check is cached.
+pub(crate) fn available() -> bool {
+    avx2_cpuid::get()
+}
@@ -0,0 +1,338 @@
+//! The NTT and multiplication in `T_q` on eight coefficients at a time
+//!
+//! Multiplications use Montgomery reduction with `R = 2^32`, with the constants premultiplied by
+//! `R` so that every result is the canonical representative computed by the portable code.
+#![allow(clippy::cast_possible_wrap)] // All constants are less than q < 2^31
+
+use super::arch::{
+    __m256i, _mm256_add_epi32, _mm256_and_si256, _mm256_blend_epi32, _mm256_loadu_si256,
+    _mm256_mul_epu32, _mm256_permute2x128_si256, _mm256_set1_epi32, _mm256_setr_epi32,
+    _mm256_setzero_si256, _mm256_slli_epi64, _mm256_srai_epi32, _mm256_srli_epi64,
+    _mm256_storeu_si256, _mm256_sub_epi32, _mm256_sub_epi64, _mm256_unpackhi_epi64,
+    _mm256_unpacklo_epi64,
+};
+use crate::algebra::{mul, reduce, Poly, N, Q};
+use crate::ntt::{N_INV, ZETAS};
+
+/// `q^-1 mod 2^32`
+const QINV: u32 = 58_728_449;
+const _: () = assert!(Q.wrapping_mul(QINV) == 1);
+
+/// `2^32 mod q`
+const MONT: u32 = reduce(1 << 32);
+
+/// `2^64 mod q`, to undo the factor `2^-32` of a Montgomery multiplication
+const MONT2: u32 = mul(MONT, MONT);
+
+/// `256^-1 * 2^32 mod q`
+const N_INV_MONT: u32 = mul(N_INV, MONT);
+
+/// `ZETAS[m] * 2^32 mod q`
+const ZETAS_MONT: [u32; N] = {
+    let mut zetas = [0; N];
+    let mut m = 0;
+    while m < N {
+        zetas[m] = mul(ZETAS[m], MONT);
+        m += 1;
+    }
+    zetas
+};
+
+/// `-ZETAS[m] * 2^32 mod q`
+const NEG_ZETAS_MONT: [u32; N] = {
+    let mut zetas = [0; N];
+    let mut m = 0;
+    while m < N {
+        zetas[m] = mul(Q - ZETAS[m], MONT);
+        m += 1;
+    }
+    zetas
+};
+
+/// A polynomial as 32 vectors of eight coefficients
+type Vectors = [__m256i; N / 8];
+
+#[target_feature(enable = "avx2")]
+unsafe fn load(p: &Poly) -> Vectors {
+    let mut v = [_mm256_setzero_si256(); N / 8];
+    for (i, v) in v.iter_mut().enumerate() {
+        *v = _mm256_loadu_si256(p.0.as_ptr().add(8 * i).cast());
+    }
+    v
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn store(v: &Vectors) -> Poly {
+    let mut p = Poly::default();
+    for (i, v) in v.iter().enumerate() {
+        _mm256_storeu_si256(p.0.as_mut_ptr().add(8 * i).cast(), *v);
+    }
+    p
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn splat(x: u32) -> __m256i {
+    _mm256_set1_epi32(x as i32)
+}
+
+/// Eight zetas from `table`, at the given indices
+#[target_feature(enable = "avx2")]
+unsafe fn gather(table: &[u32; N], m: [usize; 8]) -> __m256i {
+    _mm256_setr_epi32(
+        table[m[0]] as i32,
+        table[m[1]] as i32,
+        table[m[2]] as i32,
+        table[m[3]] as i32,
+        table[m[4]] as i32,
+        table[m[5]] as i32,
+        table[m[6]] as i32,
+        table[m[7]] as i32,
+    )
+}
+
+/// Map lanes in `(-q, q)` to `[0, q)`
+#[target_feature(enable = "avx2")]
+unsafe fn normalize(r: __m256i) -> __m256i {
+    _mm256_add_epi32(r, _mm256_and_si256(_mm256_srai_epi32(r, 31), splat(Q)))
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn add(a: __m256i, b: __m256i) -> __m256i {
+    normalize(_mm256_sub_epi32(_mm256_add_epi32(a, b), splat(Q)))
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn sub(a: __m256i, b: __m256i) -> __m256i {
+    normalize(_mm256_sub_epi32(a, b))
+}
+
+/// Montgomery multiplication `a * b * 2^-32 mod q` of canonical field elements
+#[target_feature(enable = "avx2")]
+unsafe fn montmul(a: __m256i, b: __m256i) -> __m256i {
+    let q = splat(Q);
+    let qinv = splat(QINV);
+
+    // 64-bit products of the even and odd lanes
+    let t_even = _mm256_mul_epu32(a, b);
+    let t_odd = _mm256_mul_epu32(_mm256_srli_epi64(a, 32), _mm256_srli_epi64(b, 32));
+
+    // m = t * q^-1 mod 2^32, so that t - m * q is divisible by 2^32
+    let m_even = _mm256_mul_epu32(t_even, qinv);
+    let m_odd = _mm256_mul_epu32(t_odd, qinv);
+    let r_even = _mm256_sub_epi64(t_even, _mm256_mul_epu32(m_even, q));
+    let r_odd = _mm256_sub_epi64(t_odd, _mm256_mul_epu32(m_odd, q));
+
+    // The high halves hold (t - m * q) / 2^32, which lies in (-q, q)
+    normalize(_mm256_blend_epi32(
+        _mm256_srli_epi64(r_even, 32),
+        r_odd,
+        0b1010_1010,
+    ))
+}
+
+/// Cooley-Tukey butterfly `(a + z * b, a - z * b)`, with `z` in Montgomery form
+#[target_feature(enable = "avx2")]
+unsafe fn ct_butterfly(a: __m256i, b: __m256i, z: __m256i) -> (__m256i, __m256i) {
+    let t = montmul(b, z);
+    (add(a, t), sub(a, t))
+}
+
+/// Gentleman-Sande butterfly `(a + b, z * (a - b))`, with `z` in Montgomery form
+#[target_feature(enable = "avx2")]
+unsafe fn gs_butterfly(a: __m256i, b: __m256i, z: __m256i) -> (__m256i, __m256i) {
+    (add(a, b), montmul(sub(a, b), z))
+}
+
+/// Split two vectors into the lower and upper 128-bit halves of each
+#[target_feature(enable = "avx2")]
+unsafe fn interleave_128(v0: __m256i, v1: __m256i) -> (__m256i, __m256i) {
+    (
+        _mm256_permute2x128_si256(v0, v1, 0x20),
+        _mm256_permute2x128_si256(v0, v1, 0x31),
+    )
+}
+
+/// Split two vectors into the even and odd 64-bit lanes of each
+#[target_feature(enable = "avx2")]
+unsafe fn interleave_64(v0: __m256i, v1: __m256i) -> (__m256i, __m256i) {
+    (_mm256_unpacklo_epi64(v0, v1), _mm256_unpackhi_epi64(v0, v1))
+}
+
+/// Split two vectors into the even and odd 32-bit lanes of each
+#[target_feature(enable = "avx2")]
+unsafe fn interleave_32(v0: __m256i, v1: __m256i) -> (__m256i, __m256i) {
+    (
+        _mm256_blend_epi32(v0, _mm256_slli_epi64(v1, 32), 0b1010_1010),
+        _mm256_blend_epi32(_mm256_srli_epi64(v0, 32), v1, 0b1010_1010),
+    )
+}
+
+/// The zetas for the butterflies of layer `len = 4`, as laid out by [`interleave_128`]
+fn zetas_len4(first: usize) -> [usize; 8] {
+    let [a, b] = [first, first + 1];
+    [a, a, a, a, b, b, b, b]
+}
+
+/// The zetas for the butterflies of layer `len = 2`, as laid out by [`interleave_64`]
+fn zetas_len2(first: usize) -> [usize; 8] {
+    let [a, b, c, d] = [first, first + 1, first + 2, first + 3];
+    [a, a, c, c, b, b, d, d]
+}
+
+/// The zetas for the butterflies of layer `len = 1`, as laid out by [`interleave_32`]
+fn zetas_len1(first: usize) -> [usize; 8] {
+    [0, 4, 1, 5, 2, 6, 3, 7].map(|i| first + i)
+}
+
+/// Algorithm 41: NTT
+///
+/// # Safety
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn ntt(p: &Poly) -> Poly {
+    let mut v = load(p);
+
+    // Layers with `len >= 8` pair up whole vectors. Block `i` of a layer with `nb` blocks uses
+    // `ZETAS[nb + i]`.
+    let mut len = N / 16;
+    while len >= 1 {
+        let nb = N / 16 / len;
+        for i in 0..nb {
+            let z = splat(ZETAS_MONT[nb + i]);
+            for j in 2 * i * len..(2 * i + 1) * len {
+                (v[j], v[j + len]) = ct_butterfly(v[j], v[j + len], z);
+            }
+        }
+        len /= 2;
+    }
+
+    // Layers with `len < 8` pair up coefficients within two adjacent vectors
+    for i in 0..N / 16 {
+        let (j, k) = (2 * i, 2 * i + 1);
+
+        let (a, b) = interleave_128(v[j], v[k]);
+        let (a, b) = ct_butterfly(a, b, gather(&ZETAS_MONT, zetas_len4(32 + 2 * i)));
+        (v[j], v[k]) = interleave_128(a, b);
+
+        let (a, b) = interleave_64(v[j], v[k]);
+        let (a, b) = ct_butterfly(a, b, gather(&ZETAS_MONT, zetas_len2(64 + 4 * i)));
+        (v[j], v[k]) = interleave_64(a, b);
+
+        let (a, b) = interleave_32(v[j], v[k]);
+        let (a, b) = ct_butterfly(a, b, gather(&ZETAS_MONT, zetas_len1(128 + 8 * i)));
+        (v[j], v[k]) = interleave_32(a, b);
+    }
+
+    store(&v)
+}
+
+/// Algorithm 42: NTT^-1
+///
+/// # Safety
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn ntt_inverse(p: &Poly) -> Poly {
+    let mut v = load(p);
+
+    // Block `i` of a layer with `nb` blocks uses `-ZETAS[2 * nb - 1 - i]`
+    for i in 0..N / 16 {
+        let (j, k) = (2 * i, 2 * i + 1);
+        let rev = |m: [usize; 8]| m.map(|m| N - 1 - m);
+
+        let (a, b) = interleave_32(v[j], v[k]);
+        let (a, b) = gs_butterfly(a, b, gather(&NEG_ZETAS_MONT, rev(zetas_len1(8 * i))));
+        (v[j], v[k]) = interleave_32(a, b);
+
+        let (a, b) = interleave_64(v[j], v[k]);
+        let z = gather(&NEG_ZETAS_MONT, rev(zetas_len2(N / 2 + 4 * i)));
+        let (a, b) = gs_butterfly(a, b, z);
+        (v[j], v[k]) = interleave_64(a, b);
+
+        let (a, b) = interleave_128(v[j], v[k]);
+        let z = gather(&NEG_ZETAS_MONT, rev(zetas_len4(3 * N / 4 + 2 * i)));
+        let (a, b) = gs_butterfly(a, b, z);
+        (v[j], v[k]) = interleave_128(a, b);
+    }
+
+    let mut len = 1;
+    while len < N / 8 {
+        let nb = N / 16 / len;
+        for i in 0..nb {
+            let z = splat(NEG_ZETAS_MONT[2 * nb - 1 - i]);
+            for j in 2 * i * len..(2 * i + 1) * len {
+                (v[j], v[j + len]) = gs_butterfly(v[j], v[j + len], z);
+            }
+        }
+        len *= 2;
+    }
+
+    let n_inv = splat(N_INV_MONT);
+    for v in &mut v {
+        *v = montmul(*v, n_inv);
+    }
+    store(&v)
+}
+
+/// Algorithm 45: `MultiplyNTT`
+///
+/// # Safety
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn ntt_mul(a: &Poly, b: &Poly) -> Poly {
+    let (a, mut b) = (load(a), load(b));
+    let mont2 = splat(MONT2);
+    for (a, b) in a.iter().zip(b.iter_mut()) {
+        *b = montmul(montmul(*a, *b), mont2);
+    }
+    store(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ntt::{ntt_inverse_portable, ntt_mul_portable, ntt_portable};
+    use proptest::prelude::*;
+
+    fn arb_poly() -> impl Strategy<Value = Poly> {
+        prop::collection::vec(0..Q, N).prop_map(|v| Poly(v.try_into().unwrap()))
+    }
+
+    proptest! {
+        #[test]
+        fn test_ntt_matches_portable(p in arb_poly()) {
+            if super::super::available() {
+                // SAFETY: AVX2 support was checked above
+                unsafe {
+                    prop_assert_eq!(ntt(&p), ntt_portable(&p));
+                    prop_assert_eq!(ntt_inverse(&p), ntt_inverse_portable(&p));
+                }
+            }
+        }
+
+        #[test]
+        fn test_ntt_mul_matches_portable(a in arb_poly(), b in arb_poly()) {
+            if super::super::available() {
+                // SAFETY: AVX2 support was checked above
+                unsafe {
+                    prop_assert_eq!(ntt_mul(&a, &b), ntt_mul_portable(&a, &b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_extreme_coefficients() {
+        if !super::super::available() {
+            return;
+        }
+        for x in [0, 1, Q - 1] {
+            let p = Poly([x; N]);
+            // SAFETY: AVX2 support was checked above
+            unsafe {
+                assert_eq!(ntt(&p), ntt_portable(&p));
+                assert_eq!(ntt_inverse(&p), ntt_inverse_portable(&p));
+                assert_eq!(ntt_mul(&p, &p), ntt_mul_portable(&p, &p));
+            }
+        }
+    }
+}
@@ -0,0 +1,1100 @@
+//! Composite ML-DSA signatures
+//!
+//! Implements the composite signature algorithms of [draft-ietf-lamps-pq-composite-sigs], which
+//! pair an ML-DSA key with a traditional signature key. A composite signature consists of one
+//! signature from each component, and only verifies if both component signatures verify.
+//!
+//! Both components sign the message representative
+//!
+//! ```text
+//! M' = Prefix || Label || len(ctx) || ctx || SHA-512(M)
+//! ```
+//!
+//! where `Prefix` is the ASCII string `CompositeAlgorithmSignatures2025` and `Label` identifies
+//! the composite algorithm. The ML-DSA component signs `M'` with `Label` as its context string.
+//!
+//! Two algorithms are provided:
+//!
+//! - [`MlDsa65EcdsaP256`] (`id-MLDSA65-ECDSA-P256-SHA512`), with the `ecdsa` feature, combining
+//!   ML-DSA-65 with ECDSA over NIST P-256, e.g. a [`p256::ecdsa::SigningKey`]
+//! - [`MlDsa65Ed25519`] (`id-MLDSA65-Ed25519-SHA512`), with the `ed25519` feature, combining
+//!   ML-DSA-65 with any signer implementing [`Signer<ed25519::Signature>`]
+//!
+//! The traditional component is kept as the signer type `S` of [`CompositeSigningKey`], so that
+//! any implementation of the traditional algorithm can be used.
+//!
+//! Public keys and signatures are serialized as the concatenation of the ML-DSA encoding and the
+//! traditional encoding: an uncompressed SEC1 point and a DER-encoded `Ecdsa-Sig-Value` for
+//! ECDSA, and the raw 32-byte public key and 64-byte signature for Ed25519.
+//!
+//! With the `pkcs8` feature, composite signing keys can be serialized as the 32-byte ML-DSA seed
+//! followed by the traditional private key: a DER-encoded `ECPrivateKey` for ECDSA, and the raw
+//! 32-byte private key for Ed25519. The traditional signer is converted with its own PKCS#8
+//! encoding, so it must implement [`EncodePrivateKey`](crate::pkcs8::EncodePrivateKey) and
+//! [`DecodePrivateKey`](crate::pkcs8::DecodePrivateKey).
+//!
+//! [draft-ietf-lamps-pq-composite-sigs]: https://datatracker.ietf.org/doc/draft-ietf-lamps-pq-composite-sigs/
+
+use crate::{MlDsa65, ParameterSet, Signature, SigningKey, VerifyingKey};
+use const_oid::ObjectIdentifier;
+use core::fmt;
+use core::marker::PhantomData;
+use sha2::{Digest, Sha512};
+use signature::{Error, Keypair, RandomizedSigner, SignatureEncoding, Signer, Verifier};
+use typenum::Unsigned;
+
+#[cfg(feature = "ecdsa")]
+use p256::{ecdsa::DerSignature, EncodedPoint};
+
+#[cfg(feature = "pkcs8")]
+pub use self::pkcs8::TraditionalPrivateKey;
+
+/// The prefix of the message representative `M'`
+const PREFIX: &[u8] = b"CompositeAlgorithmSignatures2025";
+
+/// A composite signature algorithm, combining an ML-DSA parameter set with a traditional
+/// signature algorithm
+pub trait CompositeParams {
+    /// The ML-DSA parameter set of the first component
+    type MlDsa: ParameterSet;
+
+    /// The signature of the traditional component
+    type Signature: SignatureEncoding;
+
+    /// The verifying key of the traditional component
+    type VerifyingKey;
+
+    /// The object identifier of the composite algorithm
+    const OID: ObjectIdentifier;
+
+    /// The label included in the message representative and used as the ML-DSA context string
+    const LABEL: &'static [u8];
+
+    /// Serialize the verifying key of the traditional component
+    fn encode_verifying_key(verifying_key: &Self::VerifyingKey) -> Vec<u8>;
+
+    /// Deserialize the verifying key of the traditional component
+    ///
+    /// # Errors
+    /// Returns an error if the encoding is invalid
+    fn decode_verifying_key(bytes: &[u8]) -> Result<Self::VerifyingKey, Error>;
+}
+
+/// ML-DSA-65 combined with ECDSA over NIST P-256 with SHA-256
+#[cfg(feature = "ecdsa")]
+pub struct MlDsa65EcdsaP256;
+
+/// `id-MLDSA65-ECDSA-P256-SHA512`
+#[cfg(feature = "ecdsa")]
+const ID_MLDSA65_ECDSA_P256_SHA512: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.6.45");
+
+#[cfg(feature = "ecdsa")]
+impl CompositeParams for MlDsa65EcdsaP256 {
+    type MlDsa = MlDsa65;
+    type Signature = DerSignature;
+    type VerifyingKey = p256::ecdsa::VerifyingKey;
+
+    const OID: ObjectIdentifier = ID_MLDSA65_ECDSA_P256_SHA512;
+    const LABEL: &'static [u8] = b"COMPSIG-MLDSA65-ECDSA-P256-SHA512";
+
+    fn encode_verifying_key(verifying_key: &Self::VerifyingKey) -> Vec<u8> {
+        verifying_key.to_encoded_point(false).as_bytes().to_vec()
+    }
+
+    fn decode_verifying_key(bytes: &[u8]) -> Result<Self::VerifyingKey, Error> {
+        let point = EncodedPoint::from_bytes(bytes).map_err(|_| Error::new())?;
+        if point.is_compressed() {
+            return Err(Error::new());
+        }
+        p256::ecdsa::VerifyingKey::from_encoded_point(&point)
+    }
+}
+
+/// ML-DSA-65 combined with Ed25519
+///
+/// `V` is the type of Ed25519 verifying keys, e.g. `ed25519_dalek::VerifyingKey`.
+#[cfg(feature = "ed25519")]
+pub struct MlDsa65Ed25519<V>(PhantomData<V>);
+
+/// `id-MLDSA65-Ed25519-SHA512`
+#[cfg(feature = "ed25519")]
+const ID_MLDSA65_ED25519_SHA512: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.6.48");
+
+#[cfg(feature = "ed25519")]
+impl<V> CompositeParams for MlDsa65Ed25519<V>
+where
+    V: AsRef<[u8]> + for<'a> TryFrom<&'a [u8]>,
+{
+    type MlDsa = MlDsa65;
+    type Signature = ed25519::Signature;
+    type VerifyingKey = V;
+
+    const OID: ObjectIdentifier = ID_MLDSA65_ED25519_SHA512;
+    const LABEL: &'static [u8] = b"COMPSIG-MLDSA65-Ed25519-SHA512";
+
+    fn encode_verifying_key(verifying_key: &V) -> Vec<u8> {
+        verifying_key.as_ref().to_vec()
+    }
+
+    fn decode_verifying_key(bytes: &[u8]) -> Result<V, Error> {
+        if bytes.len() != 32 {
+            return Err(Error::new());
+        }
+        V::try_from(bytes).map_err(|_| Error::new())
+    }
+}
+
+/// Compute the message representative `M'` for the algorithm `A`.
+///
+/// Returns an error if the context string is longer than 255 bytes.
+fn message_representative<A: CompositeParams>(msg: &[u8], ctx: &[u8]) -> Result<Vec<u8>, Error> {
+    let ctx_len = u8::try_from(ctx.len()).map_err(|_| Error::new())?;
+    let mut m_prime = Vec::with_capacity(PREFIX.len() + A::LABEL.len() + 1 + ctx.len() + 64);
+    m_prime.extend_from_slice(PREFIX);
+    m_prime.extend_from_slice(A::LABEL);
+    m_prime.push(ctx_len);
+    m_prime.extend_from_slice(ctx);
+    m_prime.extend_from_slice(&Sha512::digest(msg));
+    Ok(m_prime)
+}
+
+/// A composite signature, made of an ML-DSA signature and a traditional signature
+pub struct CompositeSignature<A: CompositeParams> {
+    ml_dsa: Signature<A::MlDsa>,
+    traditional: A::Signature,
+}
+
+impl<A: CompositeParams> CompositeSignature<A> {
+    /// Combine two component signatures
+    pub fn new(ml_dsa: Signature<A::MlDsa>, traditional: A::Signature) -> Self {
+        CompositeSignature {
+            ml_dsa,
+            traditional,
+        }
+    }
+
+    /// The ML-DSA component
+    pub fn ml_dsa(&self) -> &Signature<A::MlDsa> {
+        &self.ml_dsa
+    }
+
+    /// The traditional component
+    pub fn traditional(&self) -> &A::Signature {
+        &self.traditional
+    }
+
+    /// Serialize the signature as the ML-DSA signature followed by the traditional signature
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let traditional = SignatureEncoding::to_bytes(&self.traditional);
+        let mut bytes = self.ml_dsa.to_vec();
+        bytes.extend_from_slice(traditional.as_ref());
+        bytes
+    }
+}
+
+impl<A: CompositeParams> TryFrom<&[u8]> for CompositeSignature<A> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let ml_dsa_len = <A::MlDsa as crate::SignatureLen>::SigLen::USIZE;
+        if bytes.len() < ml_dsa_len {
+            return Err(Error::new());
+        }
+
+        let (ml_dsa, traditional) = bytes.split_at(ml_dsa_len);
+        Ok(CompositeSignature {
+            ml_dsa: Signature::try_from(ml_dsa)?,
+            traditional: A::Signature::try_from(traditional).map_err(|_| Error::new())?,
+        })
+    }
+}
+
+impl<A: CompositeParams> From<CompositeSignature<A>> for Box<[u8]> {
+    fn from(signature: CompositeSignature<A>) -> Box<[u8]> {
+        signature.to_bytes().into_boxed_slice()
+    }
+}
+
+impl<A: CompositeParams> SignatureEncoding for CompositeSignature<A> {
+    type Repr = Box<[u8]>;
+}
+
+impl<A: CompositeParams> Clone for CompositeSignature<A> {
+    fn clone(&self) -> Self {
+        CompositeSignature {
+            ml_dsa: self.ml_dsa.clone(),
+            traditional: self.traditional.clone(),
+        }
+    }
+}
+
+impl<A: CompositeParams> fmt::Debug for CompositeSignature<A>
+where
+    A::Signature: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompositeSignature")
+            .field("ml_dsa", &self.ml_dsa)
+            .field("traditional", &self.traditional)
+            .finish()
+    }
+}
+
+impl<A: CompositeParams> PartialEq for CompositeSignature<A>
+where
+    A::Signature: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ml_dsa == other.ml_dsa && self.traditional == other.traditional
+    }
+}
+
+impl<A: CompositeParams> Eq for CompositeSignature<A> where A::Signature: Eq {}
+
+/// A composite signing key, made of an ML-DSA signing key and a traditional signer `S`
+pub struct CompositeSigningKey<A: CompositeParams, S> {
+    ml_dsa: SigningKey<A::MlDsa>,
+    traditional: S,
+    algorithm: PhantomData<A>,
+}
+
+impl<A: CompositeParams, S> CompositeSigningKey<A, S> {
+    /// Combine two component signing keys
+    pub fn new(ml_dsa: SigningKey<A::MlDsa>, traditional: S) -> Self {
+        CompositeSigningKey {
+            ml_dsa,
+            traditional,
+            algorithm: PhantomData,
+        }
+    }
+
+    /// The ML-DSA component
+    pub fn ml_dsa(&self) -> &SigningKey<A::MlDsa> {
+        &self.ml_dsa
+    }
+
+    /// The traditional component
+    pub fn traditional(&self) -> &S {
+        &self.traditional
+    }
+
+    /// Sign a message with a context string of at most 255 bytes.
+    ///
+    /// The ML-DSA component is signed deterministically.
+    /// # Errors
+    /// Returns an error if the context string is too long or the traditional signer fails
+    pub fn try_sign_with_context(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+    ) -> Result<CompositeSignature<A>, Error>
+    where
+        S: Signer<A::Signature>,
+    {
+        let m_prime = message_representative::<A>(msg, ctx)?;
+        Ok(CompositeSignature {
+            ml_dsa: self
+                .ml_dsa
+                .try_sign_with_context(&m_prime, A::LABEL, None)?,
+            traditional: self.traditional.try_sign(&m_prime)?,
+        })
+    }
+
+    /// Sign a message with a context string of at most 255 bytes, using randomness from `rng`
+    /// for the ML-DSA component.
+    ///
+    /// The traditional component is signed with its [`Signer`] implementation, which is
+    /// deterministic for both Ed25519 and ECDSA with RFC 6979.
+    /// # Errors
+    /// Returns an error if the context string is too long, `rng` fails or the traditional signer
+    /// fails
+    pub fn try_sign_with_context_and_rng(
+        &self,
+        rng: &mut impl rand_core::CryptoRngCore,
+        msg: &[u8],
+        ctx: &[u8],
+    ) -> Result<CompositeSignature<A>, Error>
+    where
+        S: Signer<A::Signature>,
+    {
+        let m_prime = message_representative::<A>(msg, ctx)?;
+        let mut rnd = [0u8; 32];
+        rng.try_fill_bytes(&mut rnd).map_err(|_| Error::new())?;
+        Ok(CompositeSignature {
+            ml_dsa: self
+                .ml_dsa
+                .try_sign_with_context(&m_prime, A::LABEL, Some(&rnd))?,
+            traditional: self.traditional.try_sign(&m_prime)?,
+        })
+    }
+}
+
+impl<A: CompositeParams, S: Signer<A::Signature>> Signer<CompositeSignature<A>>
+    for CompositeSigningKey<A, S>
+{
+    fn try_sign(&self, msg: &[u8]) -> Result<CompositeSignature<A>, Error> {
+        self.try_sign_with_context(msg, &[])
+    }
+}
+
+impl<A: CompositeParams, S: Signer<A::Signature>> RandomizedSigner<CompositeSignature<A>>
+    for CompositeSigningKey<A, S>
+{
+    fn try_sign_with_rng(
+        &self,
+        rng: &mut impl rand_core::CryptoRngCore,
+        msg: &[u8],
+    ) -> Result<CompositeSignature<A>, Error> {
+        self.try_sign_with_context_and_rng(rng, msg, &[])
+    }
+}
+
+impl<A: CompositeParams, S> Keypair for CompositeSigningKey<A, S>
+where
+    S: Keypair<VerifyingKey = A::VerifyingKey>,
+    A::VerifyingKey: Clone,
+{
+    type VerifyingKey = CompositeVerifyingKey<A>;
+
+    fn verifying_key(&self) -> CompositeVerifyingKey<A> {
+        CompositeVerifyingKey {
+            ml_dsa: self.ml_dsa.verifying_key(),
+            traditional: self.traditional.verifying_key(),
+        }
+    }
+}
+
+impl<A: CompositeParams, S: fmt::Debug> fmt::Debug for CompositeSigningKey<A, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompositeSigningKey")
+            .field("ml_dsa", &self.ml_dsa)
+            .field("traditional", &self.traditional)
+            .finish()
+    }
+}
+
+/// A composite verifying key, made of an ML-DSA verifying key and a traditional verifying key
+pub struct CompositeVerifyingKey<A: CompositeParams> {
+    ml_dsa: VerifyingKey<A::MlDsa>,
+    traditional: A::VerifyingKey,
+}
+
+impl<A: CompositeParams> CompositeVerifyingKey<A> {
+    /// Combine two component verifying keys
+    pub fn new(ml_dsa: VerifyingKey<A::MlDsa>, traditional: A::VerifyingKey) -> Self {
+        CompositeVerifyingKey {
+            ml_dsa,
+            traditional,
+        }
+    }
+
+    /// The ML-DSA component
+    pub fn ml_dsa(&self) -> &VerifyingKey<A::MlDsa> {
+        &self.ml_dsa
+    }
+
+    /// The traditional component
+    pub fn traditional(&self) -> &A::VerifyingKey {
+        &self.traditional
+    }
+
+    /// Serialize the key as the ML-DSA verifying key followed by the traditional verifying key
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.ml_dsa.to_vec();
+        bytes.extend_from_slice(&A::encode_verifying_key(&self.traditional));
+        bytes
+    }
+
+    /// Verify a signature over a message with a context string of at most 255 bytes.
+    /// # Errors
+    /// Returns an error if the context string is too long or if either component signature is
+    /// invalid
+    pub fn try_verify_with_context(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        signature: &CompositeSignature<A>,
+    ) -> Result<(), Error>
+    where
+        A::VerifyingKey: Verifier<A::Signature>,
+    {
+        let m_prime = message_representative::<A>(msg, ctx)?;
+        self.ml_dsa
+            .try_verify_with_context(&m_prime, A::LABEL, &signature.ml_dsa)?;
+        self.traditional.verify(&m_prime, &signature.traditional)
+    }
+}
+
+impl<A: CompositeParams> TryFrom<&[u8]> for CompositeVerifyingKey<A> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let ml_dsa_len = <A::MlDsa as crate::VerifyingKeyLen>::VkLen::USIZE;
+        if bytes.len() < ml_dsa_len {
+            return Err(Error::new());
+        }
+
+        let (ml_dsa, traditional) = bytes.split_at(ml_dsa_len);
+        Ok(CompositeVerifyingKey {
+            ml_dsa: VerifyingKey::try_from(ml_dsa)?,
+            traditional: A::decode_verifying_key(traditional)?,
+        })
+    }
+}
+
+impl<A: CompositeParams> Verifier<CompositeSignature<A>> for CompositeVerifyingKey<A>
+where
+    A::VerifyingKey: Verifier<A::Signature>,
+{
+    fn verify(&self, msg: &[u8], signature: &CompositeSignature<A>) -> Result<(), Error> {
+        self.try_verify_with_context(msg, &[], signature)
+    }
+}
+
+impl<A: CompositeParams> Clone for CompositeVerifyingKey<A>
+where
+    A::VerifyingKey: Clone,
+{
+    fn clone(&self) -> Self {
+        CompositeVerifyingKey {
+            ml_dsa: self.ml_dsa.clone(),
+            traditional: self.traditional.clone(),
+        }
+    }
+}
+
+impl<A: CompositeParams> fmt::Debug for CompositeVerifyingKey<A>
+where
+    A::VerifyingKey: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompositeVerifyingKey")
+            .field("ml_dsa", &self.ml_dsa)
+            .field("traditional", &self.traditional)
+            .finish()
+    }
+}
+
+impl<A: CompositeParams> PartialEq for CompositeVerifyingKey<A>
+where
+    A::VerifyingKey: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ml_dsa == other.ml_dsa && self.traditional == other.traditional
+    }
+}
+
+impl<A: CompositeParams> Eq for CompositeVerifyingKey<A> where A::VerifyingKey: Eq {}
+
+#[cfg(feature = "pkcs8")]
+mod pkcs8 {
+    use super::{CompositeParams, CompositeSignature, CompositeSigningKey, CompositeVerifyingKey};
+    use crate::pkcs8::{
+        spki, AssociatedAlgorithmIdentifier, DecodePrivateKey, Document, EncodePrivateKey,
+        EncodePublicKey, Error, PrivateKeyInfoRef, Result, SecretDocument,
+        SignatureAlgorithmIdentifier, SubjectPublicKeyInfoRef,
+    };
+    use crate::SigningKey;
+    use ::pkcs8::der::asn1::{AnyRef, BitStringRef, OctetStringRef};
+    use ::pkcs8::spki::AlgorithmIdentifier;
+
+    #[cfg(feature = "ecdsa")]
+    use {super::MlDsa65EcdsaP256, const_oid::db::rfc5912};
+
+    #[cfg(feature = "ed25519")]
+    use {
+        super::MlDsa65Ed25519,
+        ::pkcs8::der::{Decode, Encode},
+        const_oid::db::rfc8410,
+    };
+
+    /// The length of the ML-DSA seed at the start of a composite private key
+    const SEED_LEN: usize = 32;
+
+    /// Conversion between the PKCS#8 encoding of the traditional private key of a composite
+    /// algorithm and its encoding in a composite private key
+    pub trait TraditionalPrivateKey: CompositeParams {
+        /// Extract the traditional private key from its PKCS#8 `PrivateKeyInfo`
+        ///
+        /// # Errors
+        /// Returns an error if the algorithm or the key is not the expected one
+        fn from_private_key_info(private_key_info: PrivateKeyInfoRef<'_>) -> Result<Vec<u8>>;
+
+        /// Wrap a traditional private key in a PKCS#8 `PrivateKeyInfo`
+        ///
+        /// # Errors
+        /// Returns an error if the key cannot be encoded
+        fn to_private_key_info(private_key: &[u8]) -> Result<SecretDocument>;
+    }
+
+    /// The traditional private key is an `ECPrivateKey` (RFC 5915), which is also the content of
+    /// the PKCS#8 private key
+    #[cfg(feature = "ecdsa")]
+    impl TraditionalPrivateKey for MlDsa65EcdsaP256 {
+        fn from_private_key_info(private_key_info: PrivateKeyInfoRef<'_>) -> Result<Vec<u8>> {
+            private_key_info
+                .algorithm
+                .assert_oids(rfc5912::ID_EC_PUBLIC_KEY, rfc5912::SECP_256_R_1)?;
+            Ok(private_key_info.private_key.as_bytes().to_vec())
+        }
+
+        fn to_private_key_info(private_key: &[u8]) -> Result<SecretDocument> {
+            let algorithm = AlgorithmIdentifier {
+                oid: rfc5912::ID_EC_PUBLIC_KEY,
+                parameters: Some(AnyRef::from(&rfc5912::SECP_256_R_1)),
+            };
+            let private_key_info =
+                PrivateKeyInfoRef::new(algorithm, OctetStringRef::new(private_key)?);
+            Ok(SecretDocument::encode_msg(&private_key_info)?)
+        }
+    }
+
+    /// The traditional private key is the raw 32-byte key, which PKCS#8 wraps in an OCTET STRING
+    /// (RFC 8410)
+    #[cfg(feature = "ed25519")]
+    impl<V> TraditionalPrivateKey for MlDsa65Ed25519<V>
+    where
+        V: AsRef<[u8]> + for<'a> TryFrom<&'a [u8]>,
+    {
+        fn from_private_key_info(private_key_info: PrivateKeyInfoRef<'_>) -> Result<Vec<u8>> {
+            private_key_info
+                .algorithm
+                .assert_algorithm_oid(rfc8410::ID_ED_25519)?;
+            if private_key_info.algorithm.parameters.is_some() {
+                return Err(Error::KeyMalformed);
+            }
+            let private_key = OctetStringRef::from_der(private_key_info.private_key.as_bytes())?;
+            if private_key.as_bytes().len() != 32 {
+                return Err(Error::KeyMalformed);
+            }
+            Ok(private_key.as_bytes().to_vec())
+        }
+
+        fn to_private_key_info(private_key: &[u8]) -> Result<SecretDocument> {
+            if private_key.len() != 32 {
+                return Err(Error::KeyMalformed);
+            }
+            let algorithm = AlgorithmIdentifier {
+                oid: rfc8410::ID_ED_25519,
+                parameters: None,
+            };
+            let private_key = OctetStringRef::new(private_key)?.to_der()?;
+            let private_key_info =
+                PrivateKeyInfoRef::new(algorithm, OctetStringRef::new(&private_key)?);
+            Ok(SecretDocument::encode_msg(&private_key_info)?)
+        }
+    }
+
+    impl<A, S> CompositeSigningKey<A, S>
+    where
+        A: TraditionalPrivateKey,
+        S: EncodePrivateKey,
+    {
+        /// Serialize the key as the seed of the ML-DSA signing key followed by the traditional
+        /// private key
+        ///
+        /// # Errors
+        /// Returns an error if the ML-DSA signing key was imported without its seed, or if the
+        /// traditional signing key cannot be encoded
+        pub fn to_bytes(&self) -> Result<Vec<u8>> {
+            let seed = self.ml_dsa.to_seed().ok_or(Error::KeyMalformed)?;
+            let traditional = self.traditional.to_pkcs8_der()?;
+            let traditional =
+                A::from_private_key_info(PrivateKeyInfoRef::try_from(traditional.as_bytes())?)?;
+            let mut bytes = seed.to_vec();
+            bytes.extend_from_slice(&traditional);
+            Ok(bytes)
+        }
+    }
+
+    impl<A, S> TryFrom<&[u8]> for CompositeSigningKey<A, S>
+    where
+        A: TraditionalPrivateKey,
+        S: DecodePrivateKey,
+    {
+        type Error = Error;
+
+        fn try_from(bytes: &[u8]) -> Result<Self> {
+            if bytes.len() < SEED_LEN {
+                return Err(Error::KeyMalformed);
+            }
+            let (seed, traditional) = bytes.split_at(SEED_LEN);
+            let seed = seed.try_into().map_err(|_| Error::KeyMalformed)?;
+            let traditional = A::to_private_key_info(traditional)?;
+            Ok(Self::new(
+                SigningKey::from_seed(seed),
+                S::from_pkcs8_der(traditional.as_bytes())?,
+            ))
+        }
+    }
+
+    impl<A, S> TryFrom<PrivateKeyInfoRef<'_>> for CompositeSigningKey<A, S>
+    where
+        A: TraditionalPrivateKey,
+        S: DecodePrivateKey,
+    {
+        type Error = Error;
+
+        fn try_from(private_key_info: PrivateKeyInfoRef<'_>) -> Result<Self> {
+            private_key_info.algorithm.assert_algorithm_oid(A::OID)?;
+            if private_key_info.algorithm.parameters.is_some() {
+                return Err(Error::KeyMalformed);
+            }
+            Self::try_from(private_key_info.private_key.as_bytes())
+        }
+    }
+
+    impl<A, S> EncodePrivateKey for CompositeSigningKey<A, S>
+    where
+        A: TraditionalPrivateKey,
+        S: EncodePrivateKey,
+    {
+        fn to_pkcs8_der(&self) -> Result<SecretDocument> {
+            let private_key = self.to_bytes()?;
+            let private_key_info = PrivateKeyInfoRef::new(
+                CompositeSignature::<A>::ALGORITHM_IDENTIFIER,
+                OctetStringRef::new(&private_key)?,
+            );
+            Ok(SecretDocument::encode_msg(&private_key_info)?)
+        }
+    }
+
+    impl<A: CompositeParams> AssociatedAlgorithmIdentifier for CompositeSignature<A> {
+        type Params = AnyRef<'static>;
+
+        const ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> = AlgorithmIdentifier {
+            oid: A::OID,
+            parameters: None,
+        };
+    }
+
+    impl<A: CompositeParams, S> SignatureAlgorithmIdentifier for CompositeSigningKey<A, S> {
+        type Params = AnyRef<'static>;
+
+        const SIGNATURE_ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> =
+            CompositeSignature::<A>::ALGORITHM_IDENTIFIER;
+    }
+
+    impl<A: CompositeParams> AssociatedAlgorithmIdentifier for CompositeVerifyingKey<A> {
+        type Params = AnyRef<'static>;
+
+        const ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> =
+            CompositeSignature::<A>::ALGORITHM_IDENTIFIER;
+    }
+
+    impl<A: CompositeParams> TryFrom<SubjectPublicKeyInfoRef<'_>> for CompositeVerifyingKey<A> {
+        type Error = spki::Error;
+
+        fn try_from(spki: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+            spki.algorithm.assert_algorithm_oid(A::OID)?;
+            if spki.algorithm.parameters.is_some() {
+                return Err(spki::Error::KeyMalformed);
+            }
+            let bytes = spki
+                .subject_public_key
+                .as_bytes()
+                .ok_or(spki::Error::KeyMalformed)?;
+            Self::try_from(bytes).map_err(|_| spki::Error::KeyMalformed)
+        }
+    }
+
+    impl<A: CompositeParams> EncodePublicKey for CompositeVerifyingKey<A> {
+        fn to_public_key_der(&self) -> spki::Result<Document> {
+            let public_key = self.to_bytes();
+            let spki = SubjectPublicKeyInfoRef {
+                algorithm: Self::ALGORITHM_IDENTIFIER,
+                subject_public_key: BitStringRef::new(0, &public_key)?,
+            };
+            Ok(Document::encode_msg(&spki)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    /// An Ed25519 signing key from `ed25519-compact`. `ed25519-dalek` cannot be used here, since
+    /// no release of it supports the pre-release of `signature` this crate is built with, so the
+    /// key is wrapped to implement the traits of that release.
+    #[cfg(feature = "ed25519")]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct TestEd25519Key(ed25519_compact::KeyPair);
+
+    /// The Ed25519 verifying key of a [`TestEd25519Key`]
+    #[cfg(feature = "ed25519")]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct TestEd25519VerifyingKey(ed25519_compact::PublicKey);
+
+    #[cfg(feature = "ed25519")]
+    mod test_ed25519 {
+        use super::{TestEd25519Key, TestEd25519VerifyingKey};
+        use ed25519_compact::{KeyPair, PublicKey, Seed};
+        use signature::{Error, Keypair, Signer, Verifier};
+
+        impl TestEd25519Key {
+            pub(super) fn from_seed(seed: [u8; 32]) -> Self {
+                TestEd25519Key(KeyPair::from_seed(Seed::new(seed)))
+            }
+        }
+
+        impl Signer<ed25519::Signature> for TestEd25519Key {
+            fn try_sign(&self, msg: &[u8]) -> Result<ed25519::Signature, Error> {
+                Ok(ed25519::Signature::from_bytes(&self.0.sk.sign(msg, None)))
+            }
+        }
+
+        impl Keypair for TestEd25519Key {
+            type VerifyingKey = TestEd25519VerifyingKey;
+
+            fn verifying_key(&self) -> TestEd25519VerifyingKey {
+                TestEd25519VerifyingKey(self.0.pk)
+            }
+        }
+
+        impl Verifier<ed25519::Signature> for TestEd25519VerifyingKey {
+            fn verify(&self, msg: &[u8], signature: &ed25519::Signature) -> Result<(), Error> {
+                let signature = ed25519_compact::Signature::new(signature.to_bytes());
+                self.0.verify(msg, &signature).map_err(|_| Error::new())
+            }
+        }
+
+        impl AsRef<[u8]> for TestEd25519VerifyingKey {
+            fn as_ref(&self) -> &[u8] {
+                self.0.as_slice()
+            }
+        }
+
+        impl TryFrom<&[u8]> for TestEd25519VerifyingKey {
+            type Error = Error;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+                let key = PublicKey::from_slice(bytes).map_err(|_| Error::new())?;
+                Ok(TestEd25519VerifyingKey(key))
+            }
+        }
+
+        /// The PKCS#8 encoding of an Ed25519 key (RFC 8410), up to the private key itself
+        #[cfg(feature = "pkcs8")]
+        const PKCS8_PREFIX: [u8; 16] = hex_literal::hex!("302e020100300506032b657004220420");
+
+        #[cfg(feature = "pkcs8")]
+        impl crate::pkcs8::EncodePrivateKey for TestEd25519Key {
+            fn to_pkcs8_der(&self) -> crate::pkcs8::Result<crate::pkcs8::SecretDocument> {
+                let mut der = PKCS8_PREFIX.to_vec();
+                der.extend_from_slice(self.0.sk.seed().as_slice());
+                Ok(der.try_into()?)
+            }
+        }
+
+        #[cfg(feature = "pkcs8")]
+        impl TryFrom<crate::pkcs8::PrivateKeyInfoRef<'_>> for TestEd25519Key {
+            type Error = crate::pkcs8::Error;
+
+            fn try_from(
+                private_key_info: crate::pkcs8::PrivateKeyInfoRef<'_>,
+            ) -> crate::pkcs8::Result<Self> {
+                private_key_info
+                    .algorithm
+                    .assert_algorithm_oid(const_oid::db::rfc8410::ID_ED_25519)?;
+                let private_key = private_key_info.private_key.as_bytes();
+                match private_key.strip_prefix(&PKCS8_PREFIX[14..]) {
+                    Some(seed) => Ok(TestEd25519Key::from_seed(
+                        seed.try_into()
+                            .map_err(|_| crate::pkcs8::Error::KeyMalformed)?,
+                    )),
+                    None => Err(crate::pkcs8::Error::KeyMalformed),
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "ed25519")]
+    type TestComposite = MlDsa65Ed25519<TestEd25519VerifyingKey>;
+
+    #[cfg(feature = "ed25519")]
+    fn test_key(seed: u8) -> CompositeSigningKey<TestComposite, TestEd25519Key> {
+        CompositeSigningKey::new(
+            SigningKey::from_seed(&[seed; 32].into()),
+            TestEd25519Key::from_seed([seed; 32]),
+        )
+    }
+
+    #[test]
+    fn test_prefix() {
+        // Prefix from the draft, in hexadecimal
+        assert_eq!(
+            PREFIX,
+            hex!("436F6D706F73697465416C676F726974686D5369676E61747572657332303235")
+        );
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_ed25519_key() {
+        // RFC 8032, section 7.1, test 1
+        let sk = TestEd25519Key::from_seed(hex!(
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"
+        ));
+        assert_eq!(
+            sk.verifying_key().as_ref(),
+            hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+        );
+        assert_eq!(
+            sk.try_sign(b"").unwrap().to_bytes(),
+            hex!(
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155"
+                "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+            )
+        );
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_sign_verify() {
+        let sk = test_key(1);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+
+        let sig = sk.try_sign(msg).unwrap();
+        vk.verify(msg, &sig).unwrap();
+        assert!(vk.verify(b"Goodbye, world!", &sig).is_err());
+
+        let sig = sk.try_sign_with_rng(&mut rand::thread_rng(), msg).unwrap();
+        vk.verify(msg, &sig).unwrap();
+
+        let sig = sk.try_sign_with_context(msg, b"context").unwrap();
+        vk.try_verify_with_context(msg, b"context", &sig).unwrap();
+        assert!(vk.verify(msg, &sig).is_err());
+        assert!(sk.try_sign_with_context(msg, &[0; 256]).is_err());
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_message_representative() {
+        let sk = test_key(2);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let ctx = b"context";
+        let sig = sk.try_sign_with_context(msg, ctx).unwrap();
+
+        let mut m_prime = PREFIX.to_vec();
+        m_prime.extend_from_slice(b"COMPSIG-MLDSA65-Ed25519-SHA512");
+        m_prime.push(7);
+        m_prime.extend_from_slice(ctx);
+        m_prime.extend_from_slice(&Sha512::digest(msg));
+
+        // Both components sign M', with the label as the ML-DSA context string
+        vk.ml_dsa()
+            .try_verify_with_context(&m_prime, TestComposite::LABEL, sig.ml_dsa())
+            .unwrap();
+        let ed25519_sig = ed25519_compact::Signature::new(sig.traditional().to_bytes());
+        vk.traditional().0.verify(&m_prime, &ed25519_sig).unwrap();
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_both_components_required() {
+        let sk = test_key(3);
+        let other = test_key(4);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let sig = sk.try_sign(msg).unwrap();
+        let other_sig = other.try_sign(msg).unwrap();
+
+        let mixed = CompositeSignature::new(sig.ml_dsa().clone(), *other_sig.traditional());
+        assert!(vk.verify(msg, &mixed).is_err());
+        let mixed = CompositeSignature::new(other_sig.ml_dsa().clone(), *sig.traditional());
+        assert!(vk.verify(msg, &mixed).is_err());
+
+        // Component signatures made without the composite construction are rejected
+        let ml_dsa_sig = sk.ml_dsa().try_sign(msg).unwrap();
+        let traditional_sig = sk.traditional().try_sign(msg).unwrap();
+        let stripped = CompositeSignature::new(ml_dsa_sig, traditional_sig);
+        assert!(vk.verify(msg, &stripped).is_err());
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_encoding_roundtrip() {
+        let sk = test_key(5);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let sig = sk.try_sign(msg).unwrap();
+
+        let vk_bytes = vk.to_bytes();
+        assert_eq!(vk_bytes.len(), 1952 + 32);
+        assert_eq!(vk_bytes[..1952], vk.ml_dsa().to_bytes()[..]);
+        assert_eq!(vk_bytes[1952..], *sk.traditional().0.pk);
+        let vk2 = CompositeVerifyingKey::<TestComposite>::try_from(vk_bytes.as_slice()).unwrap();
+        assert_eq!(vk, vk2);
+        assert!(CompositeVerifyingKey::<TestComposite>::try_from(&vk_bytes[..1983]).is_err());
+
+        let sig_bytes = sig.to_bytes();
+        assert_eq!(sig_bytes.len(), 3309 + 64);
+        assert_eq!(sig_bytes[..3309], sig.ml_dsa().to_bytes()[..]);
+        assert_eq!(sig_bytes[3309..], sig.traditional().to_bytes());
+        let sig2 = CompositeSignature::<TestComposite>::try_from(sig_bytes.as_slice()).unwrap();
+        assert_eq!(sig, sig2);
+        vk2.verify(msg, &sig2).unwrap();
+        assert!(CompositeSignature::<TestComposite>::try_from(&sig_bytes[..3372]).is_err());
+    }
+
+    #[cfg(all(feature = "ed25519", feature = "pkcs8"))]
+    #[test]
+    fn test_spki_roundtrip() {
+        use crate::pkcs8::{DecodePublicKey, EncodePublicKey};
+
+        let vk = test_key(6).verifying_key();
+        let der = vk.to_public_key_der().unwrap();
+        let vk2 = CompositeVerifyingKey::<TestComposite>::from_public_key_der(der.as_bytes());
+        assert_eq!(vk, vk2.unwrap());
+        assert!(VerifyingKey::<MlDsa65>::from_public_key_der(der.as_bytes()).is_err());
+    }
+
+    #[cfg(all(feature = "ed25519", feature = "pkcs8"))]
+    #[test]
+    fn test_pkcs8_roundtrip() {
+        use crate::pkcs8::{DecodePrivateKey, EncodePrivateKey};
+
+        type TestSigningKey = CompositeSigningKey<TestComposite, TestEd25519Key>;
+
+        // The ML-DSA seed followed by the raw Ed25519 private key
+        let sk = test_key(7);
+        let bytes = sk.to_bytes().unwrap();
+        assert_eq!(bytes, [7; 64]);
+        let sk2 = TestSigningKey::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(sk2.ml_dsa(), sk.ml_dsa());
+        assert_eq!(sk2.traditional(), sk.traditional());
+        assert!(TestSigningKey::try_from(&bytes[..63]).is_err());
+
+        let der = sk.to_pkcs8_der().unwrap();
+        let sk3 = TestSigningKey::from_pkcs8_der(der.as_bytes()).unwrap();
+        assert_eq!(sk3.to_bytes().unwrap(), bytes);
+        assert_eq!(sk3.verifying_key(), sk.verifying_key());
+        assert!(SigningKey::<MlDsa65>::from_pkcs8_der(der.as_bytes()).is_err());
+
+        // The draft format requires the ML-DSA seed
+        let expanded = SigningKey::<MlDsa65>::try_from(sk.ml_dsa().to_bytes().as_slice()).unwrap();
+        let sk4 = TestSigningKey::new(expanded, TestEd25519Key::from_seed([7; 32]));
+        assert!(sk4.to_bytes().is_err());
+        assert!(sk4.to_pkcs8_der().is_err());
+    }
+
+    #[cfg(feature = "ecdsa")]
+    mod ecdsa_p256 {
+        use super::*;
+
+        type TestSigningKey = CompositeSigningKey<MlDsa65EcdsaP256, p256::ecdsa::SigningKey>;
+
+        fn test_key(seed: u8) -> TestSigningKey {
+            CompositeSigningKey::new(
+                SigningKey::from_seed(&[seed; 32].into()),
+                p256::ecdsa::SigningKey::from_slice(&[seed; 32]).unwrap(),
+            )
+        }
+
+        #[test]
+        fn test_sign_verify() {
+            let sk = test_key(1);
+            let vk = sk.verifying_key();
+            let msg = b"Hello, world!";
+
+            let sig = sk.try_sign(msg).unwrap();
+            vk.verify(msg, &sig).unwrap();
+            assert!(vk.verify(b"Goodbye, world!", &sig).is_err());
+
+            let sig = sk.try_sign_with_context(msg, b"context").unwrap();
+            vk.try_verify_with_context(msg, b"context", &sig).unwrap();
+            assert!(vk.verify(msg, &sig).is_err());
+
+            let other = test_key(2).try_sign(msg).unwrap();
+            let mixed = CompositeSignature::new(sig.ml_dsa().clone(), other.traditional().clone());
+            assert!(vk.try_verify_with_context(msg, b"context", &mixed).is_err());
+        }
+
+        #[test]
+        fn test_verifying_key_encoding() {
+            let vk = test_key(3).verifying_key();
+            let bytes = vk.to_bytes();
+
+            // The ML-DSA key followed by the uncompressed SEC1 point
+            assert_eq!(bytes.len(), 1952 + 65);
+            assert_eq!(bytes[..1952], vk.ml_dsa().to_bytes()[..]);
+            assert_eq!(
+                bytes[1952..],
+                *vk.traditional().to_encoded_point(false).as_bytes()
+            );
+            let vk2 = CompositeVerifyingKey::<MlDsa65EcdsaP256>::try_from(bytes.as_slice());
+            assert_eq!(vk2.unwrap(), vk);
+
+            // Compressed points are rejected
+            let mut compressed = vk.ml_dsa().to_vec();
+            compressed.extend_from_slice(vk.traditional().to_encoded_point(true).as_bytes());
+            assert!(
+                CompositeVerifyingKey::<MlDsa65EcdsaP256>::try_from(compressed.as_slice()).is_err()
+            );
+        }
+
+        #[test]
+        fn test_signature_encoding() {
+            let sk = test_key(4);
+            let msg = b"Hello, world!";
+            let sig = sk.try_sign_with_context(msg, b"context").unwrap();
+
+            // Both components sign M', with the label as the ML-DSA context string
+            let m_prime = message_representative::<MlDsa65EcdsaP256>(msg, b"context").unwrap();
+            assert_eq!(&m_prime[32..65], b"COMPSIG-MLDSA65-ECDSA-P256-SHA512");
+            sk.ml_dsa()
+                .verifying_key()
+                .try_verify_with_context(&m_prime, MlDsa65EcdsaP256::LABEL, sig.ml_dsa())
+                .unwrap();
+            sk.traditional()
+                .verifying_key()
+                .verify(&m_prime, sig.traditional())
+                .unwrap();
+
+            // The ECDSA signature is DER-encoded after the ML-DSA signature
+            let bytes = sig.to_bytes();
+            assert_eq!(bytes[3309..], *sig.traditional().as_bytes());
+            assert_eq!(bytes[3309], 0x30);
+            let sig2 = CompositeSignature::<MlDsa65EcdsaP256>::try_from(bytes.as_slice()).unwrap();
+            assert_eq!(sig2.to_bytes(), bytes);
+        }
+
+        #[cfg(feature = "pkcs8")]
+        #[test]
+        fn test_pkcs8_roundtrip() {
+            use crate::pkcs8::{DecodePrivateKey, EncodePrivateKey, PrivateKeyInfoRef};
+            use const_oid::db::rfc5912;
+
+            // The ML-DSA seed followed by the `ECPrivateKey` inside the PKCS#8 encoding
+            let sk = test_key(5);
+            let bytes = sk.to_bytes().unwrap();
+            assert_eq!(bytes[..32], [5; 32]);
+            let ec_der = sk.traditional().to_pkcs8_der().unwrap();
+            let ec_private_key_info = PrivateKeyInfoRef::try_from(ec_der.as_bytes()).unwrap();
+            assert_eq!(bytes[32..], *ec_private_key_info.private_key.as_bytes());
+
+            let sk2 = TestSigningKey::try_from(bytes.as_slice()).unwrap();
+            assert_eq!(sk2.ml_dsa(), sk.ml_dsa());
+            assert_eq!(sk2.traditional(), sk.traditional());
+
+            let der = sk.to_pkcs8_der().unwrap();
+            let sk3 = TestSigningKey::from_pkcs8_der(der.as_bytes()).unwrap();
+            assert_eq!(sk3.verifying_key(), sk.verifying_key());
+
+            // The traditional private key is wrapped with the P-256 curve as its parameters
+            let der = MlDsa65EcdsaP256::to_private_key_info(&bytes[32..]).unwrap();
+            let private_key_info = PrivateKeyInfoRef::try_from(der.as_bytes()).unwrap();
+            assert_eq!(
+                private_key_info.algorithm.parameters_oid().unwrap(),
+                rfc5912::SECP_256_R_1
+            );
+            assert_eq!(der.as_bytes(), ec_der.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! CRYSTALS-Dilithium as submitted to round 3 of the NIST PQC process
+//!
+//! This module implements version 3.1 of the Dilithium specification, as used by deployments
+//! predating FIPS-204, so that legacy keys and signatures can be handled alongside ML-DSA. It
+//! shares the lattice arithmetic, sampling and rounding with ML-DSA, which uses the same
+//! parameters, but follows the round-3 conventions where the two differ:
+//!
+//! - Key generation expands the seed `ξ` without appending `k` and `l`.
+//! - The public key hash `tr` is 32 bytes long instead of 64, which makes signing keys 32 bytes
+//!   shorter.
+//! - The message representative is `μ = H(tr || M)`, without a context string or domain
+//!   separator, so there is no pure or pre-hash variant to choose from.
+//! - The commitment hash `c̃` is 32 bytes long for every parameter set.
+//! - Deterministic signing derives `ρ'' = H(K || μ)`, and randomized signing draws `ρ''` at
+//!   random.
+//!
+//! Signatures use the layout and the strict decoding of ML-DSA signatures: see [`Signature`].
+//!
+//! New keys and signatures should use ML-DSA instead.
+//!
+//! ```
+//! use ml_dsa::dilithium_r3::{Dilithium3, SigningKey, VerifyingKey};
+//! use ml_dsa::Signature;
+//! use signature::{Keypair, Signer, Verifier};
+//!
+//! let sk = SigningKey::<Dilithium3>::new(&mut rand::thread_rng());
+//! let vk = sk.verifying_key();
+//! let sig: Signature<Dilithium3> = sk.sign(b"Hello world");
+//!
+//! let vk = VerifyingKey::<Dilithium3>::try_from(vk.to_bytes().as_slice()).unwrap();
+//! let sig = Signature::<Dilithium3>::try_from(sig.to_bytes().as_slice()).unwrap();
+//! assert!(vk.verify(b"Hello world", &sig).is_ok());
+//! ```
+
+use crate::algebra::{Vector, Q};
+use crate::error::SigningKeyError;
+use crate::hashes::{h, message_representative};
+use crate::params::MlDsaParams;
+use crate::sampling::expand_s;
+use crate::signing_key::{decode_secrets, encode_secrets, power2round_t, sign_with_rho_prime};
+use crate::verifying_key::{pk_decode, pk_encode, verify};
+pub use crate::Signature;
+use crate::{SignatureLen, SignatureParams, VerifyingKeyLen};
+use ::signature::{Error, KeypairRef, RandomizedSigner, Signer, Verifier};
+use core::fmt;
+use hybrid_array::sizes::{U1312, U1952, U2420, U2592, U3293, U4595};
+use hybrid_array::Array;
+use subtle::{Choice, ConstantTimeEq};
+use typenum::{U128, U32, U4, U5, U6, U64, U7, U8};
+
+/// Specific parameters for each of the 3 round-3 Dilithium parameter sets
+#[allow(private_bounds)] // Intentionally un-usable type
+pub trait DilithiumParams: SignatureParams + VerifyingKeyLen {
+    /// Human-readable name for the parameter set, as in the round-3 submission
+    const NAME: &'static str;
+
+    /// The length of an encoded signing key in bytes
+    const SIGNING_KEY_LEN: usize;
+}
+
+/// Dilithium2 (NIST security category 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dilithium2;
+impl MlDsaParams for Dilithium2 {
+    type K = U4;
+    type L = U4;
+    type CTildeLen = U32;
+    const ETA: u32 = 2;
+    const TAU: usize = 39;
+    const GAMMA1: u32 = 1 << 17;
+    const GAMMA2: u32 = (Q - 1) / 88;
+    const OMEGA: usize = 80;
+}
+
+/// Dilithium3 (NIST security category 3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dilithium3;
+impl MlDsaParams for Dilithium3 {
+    type K = U6;
+    type L = U5;
+    type CTildeLen = U32;
+    const ETA: u32 = 4;
+    const TAU: usize = 49;
+    const GAMMA1: u32 = 1 << 19;
+    const GAMMA2: u32 = (Q - 1) / 32;
+    const OMEGA: usize = 55;
+}
+
+/// Dilithium5 (NIST security category 5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dilithium5;
+impl MlDsaParams for Dilithium5 {
+    type K = U8;
+    type L = U7;
+    type CTildeLen = U32;
+    const ETA: u32 = 2;
+    const TAU: usize = 60;
+    const GAMMA1: u32 = 1 << 19;
+    const GAMMA2: u32 = (Q - 1) / 32;
+    const OMEGA: usize = 75;
+}
+
+impl DilithiumParams for Dilithium2 {
+    const NAME: &'static str = "Dilithium2";
+    const SIGNING_KEY_LEN: usize = 2528;
+}
+
+impl DilithiumParams for Dilithium3 {
+    const NAME: &'static str = "Dilithium3";
+    const SIGNING_KEY_LEN: usize = 4000;
+}
+
+impl DilithiumParams for Dilithium5 {
+    const NAME: &'static str = "Dilithium5";
+    const SIGNING_KEY_LEN: usize = 4864;
+}
+
+impl VerifyingKeyLen for Dilithium2 {
+    type VkLen = U1312;
+}
+
+impl VerifyingKeyLen for Dilithium3 {
+    type VkLen = U1952;
+}
+
+impl VerifyingKeyLen for Dilithium5 {
+    type VkLen = U2592;
+}
+
+impl SignatureLen for Dilithium2 {
+    type SigLen = U2420;
+}
+
+impl SignatureLen for Dilithium3 {
+    type SigLen = U3293;
+}
+
+impl SignatureLen for Dilithium5 {
+    type SigLen = U4595;
+}
+
+/// A round-3 Dilithium public key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<P: DilithiumParams> {
+    rho: Array<u8, U32>,
+    t1: Vector<P::K>,
+    // Cached `H(pk, 32)`, used to compute the message representative
+    tr: Array<u8, U32>,
+}
+
+impl<P: DilithiumParams> VerifyingKey<P> {
+    fn new(rho: Array<u8, U32>, t1: Vector<P::K>) -> Self {
+        let tr = h(&[&pk_encode::<P>(&rho, &t1)]);
+        VerifyingKey { rho, t1, tr }
+    }
+
+    /// Serialize the verifying key to a new stack-allocated array
+    pub fn to_bytes(&self) -> Array<u8, P::VkLen> {
+        pk_encode::<P>(&self.rho, &self.t1)
+    }
+
+    /// Serialize the verifying key to a new heap-allocated vector
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl<P: DilithiumParams> TryFrom<&[u8]> for VerifyingKey<P> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let (rho, t1) = pk_decode::<P>(bytes)?;
+        Ok(VerifyingKey::new(rho, t1))
+    }
+}
+
+impl<P: DilithiumParams> Verifier<Signature<P>> for VerifyingKey<P> {
+    fn verify(&self, msg: &[u8], signature: &Signature<P>) -> Result<(), Error> {
+        let mu = message_representative(&self.tr, &[msg]);
+        verify(&self.rho, &self.t1, &mu, signature)
+    }
+}
+
+/// A round-3 Dilithium signing key
+///
+/// The `Debug` output omits the secret values.
+#[derive(Clone)]
+pub struct SigningKey<P: DilithiumParams> {
+    key: Array<u8, U32>,
+    s1: Vector<P::L>,
+    s2: Vector<P::K>,
+    t0: Vector<P::K>,
+    verifying_key: VerifyingKey<P>,
+}
+
+impl<P: DilithiumParams> ConstantTimeEq for SigningKey<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.key.as_slice().ct_eq(other.key.as_slice())
+            & self.s1.as_slice().ct_eq(other.s1.as_slice())
+            & self.s2.as_slice().ct_eq(other.s2.as_slice())
+            & self.t0.as_slice().ct_eq(other.t0.as_slice())
+            & Choice::from(u8::from(self.verifying_key == other.verifying_key))
+    }
+}
+
+/// Constant-time comparison
+impl<P: DilithiumParams> PartialEq for SigningKey<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<P: DilithiumParams> Eq for SigningKey<P> {}
+
+impl<P: DilithiumParams> fmt::Debug for SigningKey<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("verifying_key", &self.verifying_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: DilithiumParams> SigningKey<P> {
+    /// Create a new `SigningKey` from a cryptographic random number generator
+    pub fn new(rng: &mut impl rand_core::CryptoRngCore) -> Self {
+        let mut seed = Array::<u8, U32>::default();
+        rng.fill_bytes(&mut seed);
+        Self::from_seed(&seed)
+    }
+
+    /// Deterministically derive a `SigningKey` from the 32 random bytes of round-3 key
+    /// generation
+    #[must_use]
+    pub fn from_seed(seed: &Array<u8, U32>) -> Self {
+        let seeds = h::<U128>(&[seed]);
+        let (rho, rest) = seeds.split_at(32);
+        let (rho_prime, key) = rest.split_at(64);
+
+        let (s1, s2) = expand_s::<P::K, P::L>(rho_prime, P::ETA);
+        let (t1, t0) = power2round_t::<P>(rho, &s1, &s2);
+
+        #[allow(deprecated)] // clone_from_slice
+        SigningKey {
+            key: Array::clone_from_slice(key),
+            s1,
+            s2,
+            t0,
+            verifying_key: VerifyingKey::new(Array::clone_from_slice(rho), t1),
+        }
+    }
+
+    /// Serialize the signing key into `out`, which must be [`DilithiumParams::SIGNING_KEY_LEN`]
+    /// bytes long
+    /// # Errors
+    /// Returns an error if `out` has the wrong length
+    pub fn write_to(&self, out: &mut [u8]) -> Result<(), Error> {
+        if out.len() != P::SIGNING_KEY_LEN {
+            return Err(Error::new());
+        }
+        let (head, rest) = out.split_at_mut(96);
+        head[..32].copy_from_slice(&self.verifying_key.rho);
+        head[32..64].copy_from_slice(&self.key);
+        head[64..].copy_from_slice(&self.verifying_key.tr);
+        encode_secrets::<P>(&self.s1, &self.s2, &self.t0, rest);
+        Ok(())
+    }
+
+    /// Serialize the signing key to a new heap-allocated vector
+    /// # Panics
+    /// Never panics, as the vector is allocated with the right length
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = vec![0; P::SIGNING_KEY_LEN];
+        self.write_to(&mut bytes).expect("length is correct");
+        bytes
+    }
+
+    /// Sign `msg`, with `ρ''` derived from `rnd` if given and from the key and `μ` otherwise
+    fn sign_internal(&self, msg: &[u8], rnd: Option<&Array<u8, U64>>) -> Signature<P> {
+        let mu = message_representative(&self.verifying_key.tr, &[msg]);
+        let rho_prime = rnd.copied().unwrap_or_else(|| h::<U64>(&[&self.key, &mu]));
+        sign_with_rho_prime(
+            &self.verifying_key.rho,
+            &self.s1,
+            &self.s2,
+            &self.t0,
+            &rho_prime,
+            &mu,
+        )
+    }
+}
+
+impl<P: DilithiumParams> TryFrom<&[u8]> for SigningKey<P> {
+    type Error = SigningKeyError;
+
+    /// Decode a signing key from its round-3 encoding.
+    ///
+    /// As for ML-DSA keys, the coefficients of `s1` and `s2` must lie in `[-η, η]`, and `t0` and
+    /// the public key hash `tr` must match the values recomputed from `s1` and `s2`.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != P::SIGNING_KEY_LEN {
+            return Err(SigningKeyError::InvalidLength);
+        }
+
+        let (head, rest) = bytes.split_at(96);
+        #[allow(deprecated)] // clone_from_slice
+        let rho = Array::clone_from_slice(&head[..32]);
+        let (s1, s2, t0, t1) = decode_secrets::<P>(&rho, rest)?;
+        let verifying_key = VerifyingKey::new(rho, t1);
+        if verifying_key.tr.as_slice() != &head[64..] {
+            return Err(SigningKeyError::TrMismatch);
+        }
+
+        #[allow(deprecated)] // clone_from_slice
+        Ok(SigningKey {
+            key: Array::clone_from_slice(&head[32..64]),
+            s1,
+            s2,
+            t0,
+            verifying_key,
+        })
+    }
+}
+
+impl<P: DilithiumParams> Signer<Signature<P>> for SigningKey<P> {
+    /// Deterministic signing
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature<P>, Error> {
+        Ok(self.sign_internal(msg, None))
+    }
+}
+
+impl<P: DilithiumParams> RandomizedSigner<Signature<P>> for SigningKey<P> {
+    /// Randomized signing, with `ρ''` drawn from `rng`
+    fn try_sign_with_rng(
+        &self,
+        rng: &mut impl signature::rand_core::CryptoRngCore,
+        msg: &[u8],
+    ) -> Result<Signature<P>, Error> {
+        let mut rnd = Array::<u8, U64>::default();
+        rng.try_fill_bytes(&mut rnd)?;
+        Ok(self.sign_internal(msg, Some(&rnd)))
+    }
+}
+
+impl<P: DilithiumParams> AsRef<VerifyingKey<P>> for SigningKey<P> {
+    fn as_ref(&self) -> &VerifyingKey<P> {
+        &self.verifying_key
+    }
+}
+
+impl<P: DilithiumParams> KeypairRef for SigningKey<P> {
+    type VerifyingKey = VerifyingKey<P>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::T0_POLY_LEN;
+    use signature::Keypair;
+    use typenum::Unsigned;
+
+    // Table 2 of the round-3 specification
+    fn test_encoded_sizes<P: DilithiumParams>(vk: usize, sk: usize, sig: usize) {
+        let k = P::K::USIZE;
+        let l = P::L::USIZE;
+        assert_eq!(P::VkLen::USIZE, vk);
+        assert_eq!(P::SIGNING_KEY_LEN, sk);
+        assert_eq!(96 + (k + l) * P::ETA_POLY_LEN + k * T0_POLY_LEN, sk);
+        assert_eq!(P::SigLen::USIZE, sig);
+        assert_eq!(32 + l * P::Z_POLY_LEN + P::OMEGA + k, sig);
+    }
+
+    #[test]
+    fn test_encoded_sizes_dilithium() {
+        test_encoded_sizes::<Dilithium2>(1312, 2528, 2420);
+        test_encoded_sizes::<Dilithium3>(1952, 4000, 3293);
+        test_encoded_sizes::<Dilithium5>(2592, 4864, 4595);
+    }
+
+    fn test_sign_verify<P: DilithiumParams>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+
+        let sig = sk.sign(msg);
+        assert_eq!(sig, sk.sign(msg));
+        assert!(vk.verify(msg, &sig).is_ok());
+        assert!(vk.verify(b"Goodbye, world!", &sig).is_err());
+
+        let sig = sk.sign_with_rng(&mut rng, msg);
+        assert!(vk.verify(msg, &sig).is_ok());
+        assert_ne!(sig, sk.sign_with_rng(&mut rng, msg));
+    }
+
+    fn test_serialize_deserialize<P: DilithiumParams>() {
+        let sk = SigningKey::<P>::new(&mut rand::thread_rng());
+        let mut buf = [0; 4864];
+        let sk_bytes = &mut buf[..P::SIGNING_KEY_LEN];
+        sk.write_to(sk_bytes).unwrap();
+        assert_eq!(SigningKey::<P>::try_from(&*sk_bytes), Ok(sk.clone()));
+        assert_eq!(
+            SigningKey::<P>::try_from(&sk_bytes[1..]),
+            Err(SigningKeyError::InvalidLength)
+        );
+        assert!(sk.write_to(&mut [0; 32]).is_err());
+
+        // Modify the public key hash
+        sk_bytes[70] ^= 1;
+        assert_eq!(
+            SigningKey::<P>::try_from(&*sk_bytes),
+            Err(SigningKeyError::TrMismatch)
+        );
+        sk_bytes[70] ^= 1;
+
+        // Modify t0
+        sk_bytes[P::SIGNING_KEY_LEN - 1] ^= 1;
+        assert_eq!(
+            SigningKey::<P>::try_from(&*sk_bytes),
+            Err(SigningKeyError::T0Mismatch)
+        );
+
+        let vk = sk.verifying_key();
+        assert_eq!(
+            VerifyingKey::<P>::try_from(vk.to_bytes().as_slice()).unwrap(),
+            vk
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let sk = SigningKey::<Dilithium2>::new(&mut rand::thread_rng());
+        let expected_vk = format!("{:?}", sk.verifying_key);
+        assert_eq!(
+            format!("{sk:?}"),
+            format!("SigningKey {{ verifying_key: {expected_vk}, .. }}")
+        );
+    }
+
+    fn test_differs_from_ml_dsa<P: DilithiumParams, M: crate::ParameterSet>() {
+        // The same seed gives different keys, as round 3 does not hash `k` and `l` with it
+        let seed = Array([1; 32]);
+        let sk = SigningKey::<P>::from_seed(&seed);
+        let ml_dsa_sk = crate::SigningKey::<M>::from_seed(&seed);
+        assert_ne!(
+            sk.verifying_key().to_bytes()[..],
+            ml_dsa_sk.verifying_key().to_bytes()[..]
+        );
+    }
+
+    #[test]
+    fn test_dilithium2() {
+        test_sign_verify::<Dilithium2>();
+        test_serialize_deserialize::<Dilithium2>();
+        test_differs_from_ml_dsa::<Dilithium2, crate::MlDsa44>();
+    }
+
+    #[test]
+    fn test_dilithium3() {
+        test_sign_verify::<Dilithium3>();
+        test_serialize_deserialize::<Dilithium3>();
+        test_differs_from_ml_dsa::<Dilithium3, crate::MlDsa65>();
+    }
+
+    #[test]
+    fn test_dilithium5() {
+        test_sign_verify::<Dilithium5>();
+        test_serialize_deserialize::<Dilithium5>();
+        test_differs_from_ml_dsa::<Dilithium5, crate::MlDsa87>();
+    }
+}
@@ -0,0 +1,163 @@
+//! Bit-packing of polynomials and hint vectors
+//!
+//! Follows section 7.1 of FIPS-204. Integers are packed little-endian, least significant bit first.
+use crate::algebra::{sub, Poly, N};
+use crate::error::SignatureError;
+use crate::rounding::Hint;
+use hybrid_array::ArraySize;
+
+/// `bitlen(x)`: the number of bits needed to represent `x`
+pub(crate) const fn bitlen(x: u32) -> usize {
+    (u32::BITS - x.leading_zeros()) as usize
+}
+
+/// Algorithm 16: `SimpleBitPack`, for coefficients in `[0, b]`
+///
+/// `out` must be exactly `32 * bitlen(b)` bytes long
+pub(crate) fn simple_bit_pack(p: &Poly, b: u32, out: &mut [u8]) {
+    pack(p.0.iter().copied(), bitlen(b), out);
+}
+
+/// Algorithm 17: `BitPack`, for coefficients in `[-a, b]`
+///
+/// `out` must be exactly `32 * bitlen(a + b)` bytes long
+pub(crate) fn bit_pack(p: &Poly, a: u32, b: u32, out: &mut [u8]) {
+    pack(p.0.iter().map(|&w| sub(b, w)), bitlen(a + b), out);
+}
+
+/// Algorithm 18: `SimpleBitUnpack`
+pub(crate) fn simple_bit_unpack(v: &[u8], b: u32) -> Poly {
+    unpack(v, bitlen(b))
+}
+
+/// Algorithm 19: `BitUnpack`
+///
+/// Note that coefficients outside of `[-a, b]` can be produced when `a + b + 1` is not a power of two
+pub(crate) fn bit_unpack(v: &[u8], a: u32, b: u32) -> Poly {
+    let mut z = unpack(v, bitlen(a + b));
+    for x in &mut z.0 {
+        *x = sub(b, *x);
+    }
+    z
+}
+
+fn pack(values: impl Iterator<Item = u32>, bits: usize, out: &mut [u8]) {
+    debug_assert_eq!(out.len(), N * bits / 8);
+    let mut acc = 0u64;
+    let mut acc_bits = 0;
+    let mut i = 0;
+    for value in values {
+        acc |= u64::from(value) << acc_bits;
+        acc_bits += bits;
+        while acc_bits >= 8 {
+            out[i] = acc.to_le_bytes()[0];
+            acc >>= 8;
+            acc_bits -= 8;
+            i += 1;
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)] // bits <= 32
+fn unpack(v: &[u8], bits: usize) -> Poly {
+    debug_assert_eq!(v.len(), N * bits / 8);
+    let mask = (1u64 << bits) - 1;
+    let mut p = Poly::default();
+    let mut acc = 0u64;
+    let mut acc_bits = 0;
+    let mut bytes = v.iter();
+    for coeff in &mut p.0 {
+        while acc_bits < bits {
+            acc |= u64::from(*bytes.next().expect("input length checked above")) << acc_bits;
+            acc_bits += 8;
+        }
+        *coeff = (acc & mask) as u32;
+        acc >>= bits;
+        acc_bits -= bits;
+    }
+    p
+}
+
+/// Algorithm 20: `HintBitPack`
+///
+/// `out` must be exactly `omega + K` bytes long
+#[allow(clippy::cast_possible_truncation)] // Indices are less than 256 and counts at most omega
+pub(crate) fn hint_bit_pack<K: ArraySize>(h: &Hint<K>, omega: usize, out: &mut [u8]) {
+    debug_assert_eq!(out.len(), omega + K::USIZE);
+    out.fill(0);
+    let mut index = 0;
+    for (i, poly) in h.iter().enumerate() {
+        for j in poly.ones() {
+            out[index] = j as u8;
+            index += 1;
+        }
+        out[omega + i] = index as u8;
+    }
+}
+
+/// Algorithm 21: `HintBitUnpack`
+///
+/// Rejects every non-canonical encoding, so that `HintBitPack` is the inverse of this function.
+pub(crate) fn hint_bit_unpack<K: ArraySize>(
+    y: &[u8],
+    omega: usize,
+) -> Result<Hint<K>, SignatureError> {
+    debug_assert_eq!(y.len(), omega + K::USIZE);
+    let mut h = Hint::<K>::default();
+    let mut index = 0;
+    for i in 0..K::USIZE {
+        let end = usize::from(y[omega + i]);
+        if end < index {
+            return Err(SignatureError::HintCountDecreasing);
+        }
+        if end > omega {
+            return Err(SignatureError::TooManyHints);
+        }
+        let first = index;
+        while index < end {
+            if index > first && y[index - 1] >= y[index] {
+                return Err(SignatureError::UnsortedHints);
+            }
+            h[i].set(usize::from(y[index]));
+            index += 1;
+        }
+    }
+    if y[index..omega].iter().any(|&b| b != 0) {
+        return Err(SignatureError::NonzeroHintPadding);
+    }
+    Ok(h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::{from_signed, Q};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_simple_bit_pack_roundtrip(v in prop::collection::vec(0u32..1024, N)) {
+            let p = Poly(v.try_into().unwrap());
+            let mut out = [0u8; 320];
+            simple_bit_pack(&p, 1023, &mut out);
+            prop_assert_eq!(simple_bit_unpack(&out, 1023), p);
+        }
+
+        #[test]
+        fn test_bit_pack_roundtrip(v in prop::collection::vec(-(1i32 << 17) + 1..=(1 << 17), N)) {
+            let p = Poly(core::array::from_fn(|i| from_signed(v[i])));
+            let mut out = [0u8; 576];
+            bit_pack(&p, (1 << 17) - 1, 1 << 17, &mut out);
+            prop_assert_eq!(bit_unpack(&out, (1 << 17) - 1, 1 << 17), p);
+        }
+    }
+
+    #[test]
+    fn test_bitlen() {
+        assert_eq!(bitlen(0), 0);
+        assert_eq!(bitlen(1), 1);
+        assert_eq!(bitlen(4), 3);
+        assert_eq!(bitlen(8), 4);
+        assert_eq!(bitlen((Q - 1) >> 13), 10);
+    }
+}
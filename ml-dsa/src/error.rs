@@ -0,0 +1,82 @@
+//! Error types
+
+use core::fmt;
+
+/// The error returned when importing, generating or validating a [`SigningKey`]
+///
+/// [`SigningKey`]: crate::SigningKey
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SigningKeyError {
+    /// The encoding is neither a 32-byte seed nor an expanded key of the expected length
+    InvalidLength,
+    /// A coefficient of the secret vector `s1` is outside of `[-η, η]`
+    S1OutOfRange,
+    /// A coefficient of the secret vector `s2` is outside of `[-η, η]`
+    S2OutOfRange,
+    /// The vector `t0` does not match the one recomputed from `s1` and `s2`
+    T0Mismatch,
+    /// The public key hash `tr` does not match the hash of the recomputed verifying key
+    TrMismatch,
+    /// A signature created with the key failed to verify
+    PairwiseConsistency,
+}
+
+impl fmt::Display for SigningKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SigningKeyError::InvalidLength => "invalid signing key length",
+            SigningKeyError::S1OutOfRange => "coefficient of s1 out of range",
+            SigningKeyError::S2OutOfRange => "coefficient of s2 out of range",
+            SigningKeyError::T0Mismatch => "t0 is inconsistent with s1 and s2",
+            SigningKeyError::TrMismatch => "tr does not match the verifying key",
+            SigningKeyError::PairwiseConsistency => "pairwise consistency test failed",
+        })
+    }
+}
+
+impl core::error::Error for SigningKeyError {}
+
+/// The error returned when decoding a [`Signature`]
+///
+/// Decoding is strict: every signature has a single accepted encoding, so that signatures cannot
+/// be modified without invalidating them.
+///
+/// [`Signature`]: crate::Signature
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SignatureError {
+    /// The encoding does not have the signature length of the parameter set
+    InvalidLength,
+    /// A coefficient of the response vector `z` is outside of `(-(γ1 - β), γ1 - β)`
+    ZOutOfRange,
+    /// The hint vector has more than `ω` set bits
+    TooManyHints,
+    /// The number of hints in a polynomial is negative
+    HintCountDecreasing,
+    /// The hint indices of a polynomial are not strictly increasing
+    UnsortedHints,
+    /// The unused hint index bytes are not all zero
+    NonzeroHintPadding,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SignatureError::InvalidLength => "invalid signature length",
+            SignatureError::ZOutOfRange => "coefficient of z out of range",
+            SignatureError::TooManyHints => "too many hints",
+            SignatureError::HintCountDecreasing => "hint counts are decreasing",
+            SignatureError::UnsortedHints => "hint indices are not sorted",
+            SignatureError::NonzeroHintPadding => "nonzero hint padding",
+        })
+    }
+}
+
+impl core::error::Error for SignatureError {}
+
+impl From<SignatureError> for signature::Error {
+    fn from(_: SignatureError) -> Self {
+        signature::Error::new()
+    }
+}
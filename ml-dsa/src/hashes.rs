@@ -0,0 +1,124 @@
+//! The SHAKE256-based hash function `H` and the values derived from it
+//!
+//! Follows section 3.7 of FIPS-204
+use crate::algebra::{Poly, Vector, Q};
+use crate::encode::simple_bit_pack;
+use crate::params::MlDsaParams;
+use crate::{ParameterSet, VerifyingKey};
+use ::signature::Error;
+use core::marker::PhantomData;
+use digest::{ExtendableOutput, Update, XofReader};
+use hybrid_array::{Array, ArraySize};
+use sha3::Shake256;
+use typenum::U64;
+
+/// `H(parts[0] || parts[1] || ..., Out)`
+pub(crate) fn h<Out: ArraySize>(parts: &[&[u8]]) -> Array<u8, Out> {
+    let mut xof = Shake256::default();
+    for part in parts {
+        xof.update(part);
+    }
+    let mut out = Array::<u8, Out>::default();
+    xof.finalize_xof().read(&mut out);
+    out
+}
+
+/// The message representative `μ = H(tr || M', 64)`
+pub type Mu = Array<u8, U64>;
+
+/// Incrementally computes the message representative `μ` of a pure ML-DSA message.
+///
+/// This allows `μ` to be computed where the message lives, and only `μ` to be sent to the
+/// holder of the signing key for use with [`SigningKey::sign_mu`].
+///
+/// [`SigningKey::sign_mu`]: crate::SigningKey::sign_mu
+#[derive(Clone, Debug)]
+pub struct MuBuilder(Shake256);
+
+impl MuBuilder {
+    /// Start computing `μ` for a message to be verified with `verifying_key`, using a context
+    /// string. Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context string is too long.
+    pub fn new<P: ParameterSet>(
+        verifying_key: &VerifyingKey<P>,
+        ctx: &[u8],
+    ) -> Result<Self, Error> {
+        let ctx_len = u8::try_from(ctx.len()).map_err(|_| Error::new())?;
+        let mut builder = Self::from_tr(&verifying_key.tr);
+        builder.update(&[0, ctx_len]).update(ctx);
+        Ok(builder)
+    }
+
+    pub(crate) fn from_tr(tr: &[u8]) -> Self {
+        let mut xof = Shake256::default();
+        xof.update(tr);
+        MuBuilder(xof)
+    }
+
+    /// Absorb the next chunk of the message
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.0.update(data);
+        self
+    }
+
+    /// Finish absorbing the message and return `μ`
+    #[must_use]
+    pub fn finalize(self) -> Mu {
+        let mut mu = Mu::default();
+        self.0.finalize_xof().read(&mut mu);
+        mu
+    }
+}
+
+/// The message representative `μ = H(tr || M', 64)`, with `M'` given in parts
+pub(crate) fn message_representative(tr: &[u8], msg: &[&[u8]]) -> Mu {
+    let mut builder = MuBuilder::from_tr(tr);
+    for part in msg {
+        builder.update(part);
+    }
+    builder.finalize()
+}
+
+/// The commitment hash `c̃ = H(μ || w1Encode(w1), λ/4)`
+pub(crate) fn commitment_hash<P: MlDsaParams>(
+    mu: &[u8],
+    w1: &Vector<P::K>,
+) -> Array<u8, P::CTildeLen> {
+    let mut hasher = CommitmentHasher::<P>::new(mu);
+    for poly in w1 {
+        hasher.update(poly);
+    }
+    hasher.finalize()
+}
+
+/// Incrementally computes the commitment hash, one polynomial of `w1` at a time
+pub(crate) struct CommitmentHasher<P: MlDsaParams> {
+    xof: Shake256,
+    params: PhantomData<P>,
+}
+
+impl<P: MlDsaParams> CommitmentHasher<P> {
+    pub(crate) fn new(mu: &[u8]) -> Self {
+        let mut xof = Shake256::default();
+        xof.update(mu);
+        CommitmentHasher {
+            xof,
+            params: PhantomData,
+        }
+    }
+
+    /// Absorb the next polynomial of `w1`, encoded as in `w1Encode` (algorithm 28)
+    pub(crate) fn update(&mut self, w1: &Poly) {
+        let mut buf = [0u8; 32 * 6];
+        let buf = &mut buf[..P::W1_POLY_LEN];
+        simple_bit_pack(w1, (Q - 1) / (2 * P::GAMMA2) - 1, buf);
+        self.xof.update(buf);
+    }
+
+    pub(crate) fn finalize(self) -> Array<u8, P::CTildeLen> {
+        let mut c_tilde = Array::<u8, P::CTildeLen>::default();
+        self.xof.finalize_xof().read(&mut c_tilde);
+        c_tilde
+    }
+}
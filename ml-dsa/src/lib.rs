@@ -1,2 +1,416 @@
-#![no_std]
+#![cfg_attr(not(any(feature = "alloc", test)), no_std)]
 #![doc = include_str!("../README.md")]
+#![warn(clippy::pedantic)] // Be pedantic by default
+#![allow(clippy::module_name_repetitions)] // There are many types of signature and otherwise this gets confusing
+#![allow(clippy::similar_names)] // Variable names follow the notation of the spec
+#![allow(clippy::many_single_char_names)] // Variable names follow the notation of the spec
+#![deny(missing_docs)] // Require all public interfaces to be documented
+
+//! # Usage
+//! This crate implements the Module-Lattice-Based Digital Signature Algorithm (ML-DSA) based on the
+//! finalized standard by NIST in FIPS-204. ML-DSA (based on the CRYSTALS-Dilithium submission) is a
+//! signature algorithm designed to be resistant to quantum computers.
+//!
+//! Three parameter sets are provided: [`MlDsa44`], [`MlDsa65`] and [`MlDsa87`], targeting NIST
+//! security categories 2, 3 and 5 respectively.
+//!
+//! The pre-hash variant HashML-DSA is available through the [`signature::DigestSigner`] and
+//! [`signature::DigestVerifier`] traits, or with any [`PreHash`] function via
+//! [`SigningKey::try_sign_prehash_with_context`] and
+//! [`VerifyingKey::try_verify_prehash_with_context`].
+//!
+//! ```
+//! use ml_dsa::*;
+//! use signature::*;
+//!
+//! let mut rng = rand::thread_rng();
+//!
+//! // Generate a signing key using the ML-DSA-65 parameter set
+//! let sk = SigningKey::<MlDsa65>::new(&mut rng);
+//!
+//! // Generate the corresponding public key
+//! let vk = sk.verifying_key();
+//!
+//! // Serialize the verifying key and distribute
+//! let vk_bytes = vk.to_bytes();
+//!
+//! // Sign a message
+//! let message = b"Hello world";
+//! let sig = sk.sign_with_rng(&mut rng, message); // .sign() can be used for deterministic signatures
+//!
+//! // Deserialize a verifying key
+//! let vk_deserialized = VerifyingKey::<MlDsa65>::try_from(vk_bytes.as_slice()).unwrap();
+//! assert_eq!(vk, vk_deserialized);
+//!
+//! assert!(vk_deserialized.verify(message, &sig).is_ok())
+//! ```
+//!
+//! Messages too large to hold in memory can be signed and verified in chunks with a
+//! [`MessageBuilder`], obtained from [`SigningKey::message_builder`] or
+//! [`VerifyingKey::message_builder`].
+//!
+//! # Composite signatures
+//! With the `ecdsa` or `ed25519` feature, the [`composite`] module combines ML-DSA-65 with ECDSA
+//! over P-256 or with Ed25519, following the IETF LAMPS composite signatures draft. A composite
+//! signature is only valid if both of its component signatures are valid.
+//!
+//! # Round-3 Dilithium
+//! With the `dilithium_r3` feature, the [`dilithium_r3`] module provides the Dilithium2,
+//! Dilithium3 and Dilithium5 parameter sets of the round-3 submission, for interoperability with
+//! keys and signatures created before FIPS-204. They are not compatible with ML-DSA.
+//!
+//! # Memory usage
+//! By default, signing and verification expand the whole matrix `Â` and keep every intermediate
+//! vector on the stack, which takes over 100 KiB of stack for ML-DSA-44 and about 250 KiB for
+//! ML-DSA-87. [`PreparedVerifyingKey`] trades more memory for faster verification.
+//!
+//! The `low-memory` feature switches key generation, signing and verification to implementations
+//! that generate `Â` one entry at a time and process one row at a time. The results are
+//! identical, but signing is slower since `Â` is sampled twice for each attempt. In optimized
+//! builds, signing and verifying then use less than 32 KiB of stack for every parameter set,
+//! including the signature, and about 48 KiB in unoptimized builds.
+//! Keys are large (up to about 40 KiB for an ML-DSA-87 [`SigningKey`]) and are best kept in
+//! static memory or on the heap. Key generation and decoding still build the whole key on the
+//! stack.
+//!
+//! # Performance
+//! On x86 and x86-64 CPUs supporting AVX2, and on 64-bit ARM targets with NEON, the NTT, its
+//! inverse, pointwise multiplication and the sampling of `Â`, `s1`, `s2` and `y` (with four SHAKE
+//! instances in parallel) use vectorized implementations. AVX2 support is detected at runtime and
+//! NEON support at compile time, with a fallback to the portable code. All paths produce
+//! identical keys and signatures.
+
+pub use signature;
+
+mod algebra;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
+#[cfg(any(feature = "ecdsa", feature = "ed25519"))]
+pub mod composite;
+#[cfg(feature = "dilithium_r3")]
+pub mod dilithium_r3;
+mod encode;
+pub mod error;
+mod hashes;
+mod message_builder;
+#[cfg(target_arch = "aarch64")]
+mod neon;
+mod ntt;
+mod params;
+#[cfg(feature = "pkcs8")]
+pub mod pkcs8;
+mod prehash;
+mod rounding;
+mod sampling;
+mod signature_encoding;
+mod signing_key;
+mod streaming;
+mod util;
+mod verifying_key;
+
+pub use hashes::{Mu, MuBuilder};
+pub use message_builder::MessageBuilder;
+pub use params::{MlDsa44, MlDsa65, MlDsa87};
+pub use prehash::PreHash;
+pub use signature_encoding::*;
+pub use signing_key::*;
+pub use verifying_key::*;
+
+use params::MlDsaParams;
+
+/// The parameters determining the layout of a [`Signature`], shared by the ML-DSA parameter sets
+/// and the round-3 Dilithium parameter sets of the `dilithium_r3` feature
+#[allow(private_bounds)] // Intentionally un-usable type
+pub trait SignatureParams: MlDsaParams + SignatureLen {}
+
+impl<P: MlDsaParams + SignatureLen> SignatureParams for P {}
+
+/// Specific parameters for each of the 3 FIPS parameter sets
+#[allow(private_bounds)] // Intentionally un-usable type
+pub trait ParameterSet: SignatureParams + SigningKeyLen + VerifyingKeyLen {
+    /// Human-readable name for parameter set, matching the FIPS-204 designations
+    const NAME: &'static str;
+}
+
+impl ParameterSet for MlDsa44 {
+    const NAME: &'static str = "ML-DSA-44";
+}
+
+impl ParameterSet for MlDsa65 {
+    const NAME: &'static str = "ML-DSA-65";
+}
+
+impl ParameterSet for MlDsa87 {
+    const NAME: &'static str = "ML-DSA-87";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use signature::*;
+    use util::macros::test_parameter_sets;
+
+    fn test_sign_verify<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let sig = sk.try_sign(msg).unwrap();
+        vk.verify(msg, &sig).unwrap();
+    }
+    test_parameter_sets!(test_sign_verify);
+
+    fn test_sign_verify_randomized<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let sig = sk.try_sign_with_rng(&mut rng, msg).unwrap();
+        vk.verify(msg, &sig).unwrap();
+    }
+    test_parameter_sets!(test_sign_verify_randomized);
+
+    // Check signature fails on modified message
+    #[test]
+    fn test_sign_verify_fail_on_modified_message() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let msg = b"Hello, world!";
+        let modified_msg = b"Goodbye, world!";
+
+        let sig = sk.try_sign(msg).unwrap();
+        let vk = sk.verifying_key();
+        assert!(vk.verify(msg, &sig).is_ok());
+        assert!(vk.verify(modified_msg, &sig).is_err());
+    }
+
+    #[test]
+    fn test_sign_verify_fail_with_wrong_verifying_key() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let wrong_sk = SigningKey::<MlDsa44>::new(&mut rng); // Generate a different signing key
+        let msg = b"Hello, world!";
+
+        let sig = sk.try_sign(msg).unwrap();
+        let vk = sk.verifying_key();
+        let wrong_vk = wrong_sk.verifying_key(); // Get the verifying key of the wrong signing key
+        assert!(vk.verify(msg, &sig).is_ok());
+        assert!(wrong_vk.verify(msg, &sig).is_err()); // This should fail because the verifying key does not match the signing key used
+    }
+
+    #[test]
+    fn test_sign_verify_fail_on_modified_signature() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let msg = b"Hello, world!";
+
+        let mut sig_bytes = sk.try_sign(msg).unwrap().to_bytes();
+        // Randomly modify one byte of the commitment hash or response
+        let random_byte_index = rng.gen_range(0..sig_bytes.len() - 84);
+        sig_bytes[random_byte_index] ^= 0xff; // Invert one byte to ensure it's different
+
+        let vk = sk.verifying_key();
+        // Modified signatures must either fail to decode or fail to verify
+        if let Ok(sig) = Signature::<MlDsa44>::try_from(sig_bytes.as_slice()) {
+            assert!(
+                vk.verify(msg, &sig).is_err(),
+                "Verification should fail with a modified signature"
+            );
+        }
+    }
+
+    #[test]
+    fn test_deterministic_signatures_equal() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let msg = b"Hello, world!";
+
+        let sig1 = sk.try_sign(msg).unwrap();
+        let sig2 = sk.try_sign(msg).unwrap();
+        assert_eq!(sig1, sig2);
+    }
+
+    #[test]
+    fn test_successive_signatures_not_equal() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let msg = b"Hello, world!";
+
+        let sig1 = sk.try_sign_with_rng(&mut rng, msg).unwrap();
+        let sig2 = sk.try_sign_with_rng(&mut rng, msg).unwrap();
+
+        assert_ne!(
+            sig1, sig2,
+            "Two successive randomized signatures over the same message should not be equal"
+        );
+    }
+
+    #[test]
+    fn test_sign_verify_nonempty_context() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let ctx = b"Test context";
+        let sig = sk.try_sign_with_context(msg, ctx, None).unwrap();
+        vk.try_verify_with_context(msg, ctx, &sig).unwrap();
+    }
+
+    #[test]
+    fn test_sign_verify_wrong_context() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let ctx = b"Test context!";
+        let wrong_ctx = b"Wrong context";
+        let sig = sk.try_sign_with_context(msg, ctx, None).unwrap();
+        assert!(vk.try_verify_with_context(msg, wrong_ctx, &sig).is_err());
+    }
+
+    #[test]
+    fn test_context_too_long() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let max_ctx = [0x42; 255];
+        let long_ctx = [0x42; 256];
+        let sig = sk.try_sign_with_context(msg, &max_ctx, None).unwrap();
+        vk.try_verify_with_context(msg, &max_ctx, &sig).unwrap();
+        assert!(sk.try_sign_with_context(msg, &long_ctx, None).is_err());
+        assert!(vk.try_verify_with_context(msg, &long_ctx, &sig).is_err());
+    }
+
+    #[test]
+    fn test_hedged_context_matches_internal() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::ml_dsa_keygen_internal(&[0x11; 32]);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let ctx = b"Test context";
+        let mut rnd = [0u8; 32];
+        rng.fill(&mut rnd);
+
+        let sig = sk.try_sign_with_context(msg, ctx, Some(&rnd)).unwrap();
+        let ctx_msg: [&[u8]; 4] = [&[0], &[12], ctx, msg];
+        assert_eq!(sig, sk.ml_dsa_sign_internal(&ctx_msg, Some(&rnd)));
+        vk.ml_dsa_verify_internal(&ctx_msg, &sig).unwrap();
+    }
+
+    fn test_sign_verify_external_mu<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let ctx = b"Test context";
+
+        let mut builder = MuBuilder::new(&vk, ctx).unwrap();
+        for chunk in msg.chunks(5) {
+            builder.update(chunk);
+        }
+        let mu = builder.finalize();
+
+        let sig = sk.sign_mu(&mu);
+        assert_eq!(sig, sk.try_sign_with_context(msg, ctx, None).unwrap());
+        vk.try_verify_with_context(msg, ctx, &sig).unwrap();
+
+        let sig = sk.sign_mu_with_rng(&mut rng, &mu);
+        vk.verify_mu(&mu, &sig).unwrap();
+        vk.try_verify_with_context(msg, ctx, &sig).unwrap();
+        assert!(vk.verify(msg, &sig).is_err());
+    }
+    test_parameter_sets!(test_sign_verify_external_mu);
+
+    #[test]
+    fn test_external_mu_context_too_long() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        assert!(MuBuilder::new(&sk.verifying_key(), &[0; 256]).is_err());
+    }
+
+    fn test_sign_verify_digest<P: ParameterSet>() {
+        use sha2::Digest;
+
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+
+        let sig: Signature<P> = sk.sign_digest(sha2::Sha256::new_with_prefix(msg));
+        vk.verify_digest(sha2::Sha256::new_with_prefix(msg), &sig)
+            .unwrap();
+        assert!(vk.verify(msg, &sig).is_err());
+        assert!(vk
+            .verify_digest(sha3::Sha3_256::new_with_prefix(msg), &sig)
+            .is_err());
+
+        let sig: Signature<P> =
+            sk.sign_digest_with_rng(&mut rng, sha3::Sha3_512::new_with_prefix(msg));
+        vk.verify_digest(sha3::Sha3_512::new_with_prefix(msg), &sig)
+            .unwrap();
+    }
+    test_parameter_sets!(test_sign_verify_digest);
+
+    #[test]
+    fn test_sign_verify_prehash() {
+        use sha2::Digest;
+
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+
+        let digest = sha3::Sha3_512::digest(msg);
+        let sig = sk
+            .try_sign_prehash_with_context::<sha3::Sha3_512>(&digest, &[], None)
+            .unwrap();
+        vk.try_verify_prehash_with_context::<sha3::Sha3_512>(&digest, &[], &sig)
+            .unwrap();
+        vk.verify_digest(sha3::Sha3_512::new_with_prefix(msg), &sig)
+            .unwrap();
+
+        // A digest of the same length is not accepted under another hash function
+        assert!(vk
+            .try_verify_prehash_with_context::<sha2::Sha512>(&digest, &[], &sig)
+            .is_err());
+
+        // Digests of the wrong length are rejected
+        assert!(sk
+            .try_sign_prehash_with_context::<sha3::Sha3_512>(&digest[..32], &[], None)
+            .is_err());
+        assert!(vk
+            .try_verify_prehash_with_context::<sha3::Sha3_512>(&digest[..32], &[], &sig)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sign_verify_shake_prehash() {
+        use sha3::digest::{ExtendableOutput, Update};
+
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let ctx = b"Test context";
+
+        let mut digest = [0u8; 64];
+        sha3::Shake256::default()
+            .chain(b"Hello, world!")
+            .finalize_xof_into(&mut digest);
+        let sig = sk
+            .try_sign_prehash_with_context::<sha3::Shake256>(&digest, ctx, None)
+            .unwrap();
+        vk.try_verify_prehash_with_context::<sha3::Shake256>(&digest, ctx, &sig)
+            .unwrap();
+
+        // The OID of the pre-hash function is bound to the signature
+        assert!(vk
+            .try_verify_prehash_with_context::<sha2::Sha512>(&digest, ctx, &sig)
+            .is_err());
+        // SHAKE128 digests must be 256 bits long
+        assert!(sk
+            .try_sign_prehash_with_context::<sha3::Shake128>(&digest, ctx, None)
+            .is_err());
+    }
+}
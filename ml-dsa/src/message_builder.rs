@@ -0,0 +1,176 @@
+//! Incremental signing and verification of pure ML-DSA messages
+//!
+//! The message representative `μ = H(tr || 0 || |ctx| || ctx || M, 64)` is the only part of
+//! `ML-DSA.Sign` and `ML-DSA.Verify` that depends on the message, so the message can be absorbed
+//! in chunks of any size and never needs to be held in memory as a whole.
+use crate::hashes::MuBuilder;
+use crate::{ParameterSet, PreparedVerifyingKey, Signature, SigningKey, VerifyingKey};
+use ::signature::Error;
+
+/// Signs or verifies a message supplied in chunks, producing the same results as the one-shot
+/// functions with the same context string.
+///
+/// A `MessageBuilder` is created by the `message_builder` method of a [`SigningKey`],
+/// [`VerifyingKey`] or [`PreparedVerifyingKey`], and consumed by `finalize` once the whole
+/// message has been passed to [`MessageBuilder::update`].
+///
+/// ```
+/// use ml_dsa::*;
+/// use signature::Keypair;
+///
+/// let sk = SigningKey::<MlDsa65>::new(&mut rand::thread_rng());
+/// let vk = sk.verifying_key();
+/// let chunks: [&[u8]; 3] = [b"Hello", b", ", b"world"];
+///
+/// let mut builder = sk.message_builder(b"context").unwrap();
+/// for chunk in chunks {
+///     builder.update(chunk);
+/// }
+/// let sig = builder.finalize();
+/// assert_eq!(sig, sk.try_sign_with_context(b"Hello, world", b"context", None).unwrap());
+///
+/// let mut builder = vk.message_builder(b"context").unwrap();
+/// for chunk in chunks {
+///     builder.update(chunk);
+/// }
+/// assert!(builder.finalize(&sig).is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageBuilder<'k, K> {
+    key: &'k K,
+    mu: MuBuilder,
+}
+
+impl<'k, K> MessageBuilder<'k, K> {
+    fn new<P: ParameterSet>(
+        key: &'k K,
+        verifying_key: &VerifyingKey<P>,
+        ctx: &[u8],
+    ) -> Result<Self, Error> {
+        Ok(MessageBuilder {
+            key,
+            mu: MuBuilder::new(verifying_key, ctx)?,
+        })
+    }
+
+    /// Absorb the next chunk of the message
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.mu.update(data);
+        self
+    }
+}
+
+impl<P: ParameterSet> MessageBuilder<'_, SigningKey<P>> {
+    /// Deterministically sign the message
+    #[must_use]
+    pub fn finalize(self) -> Signature<P> {
+        self.key.sign_mu(&self.mu.finalize())
+    }
+
+    /// Sign the message using hedged randomness
+    #[must_use]
+    pub fn finalize_with_rng(self, rng: &mut impl rand_core::CryptoRngCore) -> Signature<P> {
+        self.key.sign_mu_with_rng(rng, &self.mu.finalize())
+    }
+}
+
+impl<P: ParameterSet> MessageBuilder<'_, VerifyingKey<P>> {
+    /// Verify a signature over the message
+    /// # Errors
+    /// Returns an error if the signature is invalid
+    pub fn finalize(self, signature: &Signature<P>) -> Result<(), Error> {
+        self.key.verify_mu(&self.mu.finalize(), signature)
+    }
+}
+
+impl<P: ParameterSet> MessageBuilder<'_, PreparedVerifyingKey<P>> {
+    /// Verify a signature over the message
+    /// # Errors
+    /// Returns an error if the signature is invalid
+    pub fn finalize(self, signature: &Signature<P>) -> Result<(), Error> {
+        self.key.verify_mu(&self.mu.finalize(), signature)
+    }
+}
+
+impl<P: ParameterSet> SigningKey<P> {
+    /// Start signing a message supplied in chunks, using a context string.
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context string is too long.
+    pub fn message_builder(&self, ctx: &[u8]) -> Result<MessageBuilder<'_, Self>, Error> {
+        MessageBuilder::new(self, self.as_ref(), ctx)
+    }
+}
+
+impl<P: ParameterSet> VerifyingKey<P> {
+    /// Start verifying a signature over a message supplied in chunks, using a context string.
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context string is too long.
+    pub fn message_builder(&self, ctx: &[u8]) -> Result<MessageBuilder<'_, Self>, Error> {
+        MessageBuilder::new(self, self, ctx)
+    }
+}
+
+impl<P: ParameterSet> PreparedVerifyingKey<P> {
+    /// Start verifying a signature over a message supplied in chunks, using a context string.
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context string is too long.
+    pub fn message_builder(&self, ctx: &[u8]) -> Result<MessageBuilder<'_, Self>, Error> {
+        MessageBuilder::new(self, self.verifying_key(), ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::macros::test_parameter_sets;
+    use signature::{Keypair, Verifier};
+
+    fn test_message_builder<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let prepared = vk.prepare();
+        let msg: Vec<u8> = (0..=u8::MAX).cycle().take(10_000).collect();
+        let ctx = b"Test context";
+
+        // Chunks not aligned to the SHAKE256 rate
+        let mut builder = sk.message_builder(ctx).unwrap();
+        for chunk in msg.chunks(1000) {
+            builder.update(chunk);
+        }
+        let sig = builder.finalize();
+        assert_eq!(sig, sk.try_sign_with_context(&msg, ctx, None).unwrap());
+
+        let mut builder = sk.message_builder(&[]).unwrap();
+        builder.update(&msg[..1]).update(&msg[1..]);
+        let sig = builder.finalize_with_rng(&mut rng);
+        vk.verify(&msg, &sig).unwrap();
+
+        let mut builder = vk.message_builder(&[]).unwrap();
+        builder.update(&msg[..5000]).update(&msg[5000..]);
+        builder.finalize(&sig).unwrap();
+
+        let mut builder = prepared.message_builder(&[]).unwrap();
+        builder.update(&msg);
+        builder.finalize(&sig).unwrap();
+
+        // A different context or truncated message
+        let mut builder = vk.message_builder(ctx).unwrap();
+        builder.update(&msg);
+        assert!(builder.finalize(&sig).is_err());
+        let mut builder = prepared.message_builder(&[]).unwrap();
+        builder.update(&msg[1..]);
+        assert!(builder.finalize(&sig).is_err());
+    }
+    test_parameter_sets!(test_message_builder);
+
+    #[test]
+    fn test_message_builder_context_too_long() {
+        let sk = SigningKey::<crate::MlDsa44>::new(&mut rand::thread_rng());
+        assert!(sk.message_builder(&[0; 256]).is_err());
+        assert!(sk.verifying_key().message_builder(&[0; 256]).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Four SHAKE instances computed in parallel, as two pairs of Keccak states with each 128-bit
+//! vector holding the same lane of two states
+
+use super::arch::{
+    uint64x2_t, vbicq_u64, vdupq_n_s64, vdupq_n_u64, veorq_u64, vld1q_u64, vorrq_u64, vshlq_u64,
+    vst1q_u64,
+};
+
+/// Round constants of Keccak-f[1600]
+const RC: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// Rotation offsets of the ρ step, indexed by `x + 5 * y`
+const RHO: [i64; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Two Keccak states
+type State = [uint64x2_t; 25];
+
+/// Rotate left by `n`, which must be in `1..64`. A negative shift is a right shift.
+#[target_feature(enable = "neon")]
+unsafe fn rotl(x: uint64x2_t, n: i64) -> uint64x2_t {
+    vorrq_u64(
+        vshlq_u64(x, vdupq_n_s64(n)),
+        vshlq_u64(x, vdupq_n_s64(n - 64)),
+    )
+}
+
+/// Keccak-f[1600], applied to the two states
+#[target_feature(enable = "neon")]
+unsafe fn keccak_f1600(a: &mut State) {
+    for rc in RC {
+        // θ
+        let mut c = [vdupq_n_u64(0); 5];
+        for (x, c) in c.iter_mut().enumerate() {
+            for y in 0..5 {
+                *c = veorq_u64(*c, a[x + 5 * y]);
+            }
+        }
+        for x in 0..5 {
+            let d = veorq_u64(c[(x + 4) % 5], rotl(c[(x + 1) % 5], 1));
+            for y in 0..5 {
+                a[x + 5 * y] = veorq_u64(a[x + 5 * y], d);
+            }
+        }
+
+        // ρ and π
+        let mut b = [vdupq_n_u64(0); 25];
+        b[0] = a[0];
+        for x in 0..5 {
+            for y in 0..5 {
+                if x + y > 0 {
+                    b[y + 5 * ((2 * x + 3 * y) % 5)] = rotl(a[x + 5 * y], RHO[x + 5 * y]);
+                }
+            }
+        }
+
+        // χ
+        for x in 0..5 {
+            for y in 0..5 {
+                let not_b1_and_b2 = vbicq_u64(b[(x + 2) % 5 + 5 * y], b[(x + 1) % 5 + 5 * y]);
+                a[x + 5 * y] = veorq_u64(b[x + 5 * y], not_b1_and_b2);
+            }
+        }
+
+        // ι
+        a[0] = veorq_u64(a[0], vdupq_n_u64(rc));
+    }
+}
+
+/// Four SHAKE instances with a rate of `RATE` bytes, absorbing messages of equal length and
+/// squeezed in lockstep. Instances `2 * i` and `2 * i + 1` share the vectors of `states[i]`.
+pub(crate) struct ShakeX4<const RATE: usize> {
+    states: [State; 2],
+    squeezed: bool,
+}
+
+/// Four SHAKE128 instances
+pub(crate) type Shake128x4 = ShakeX4<168>;
+
+/// Four SHAKE256 instances
+pub(crate) type Shake256x4 = ShakeX4<136>;
+
+impl<const RATE: usize> ShakeX4<RATE> {
+    /// Absorb one message into each instance.
+    ///
+    /// # Safety
+    /// The CPU must support NEON.
+    ///
+    /// # Panics
+    /// Panics if the messages don't all have the same length.
+    #[target_feature(enable = "neon")]
+    pub(crate) unsafe fn new(msgs: [&[u8]; 4]) -> Self {
+        let len = msgs[0].len();
+        assert!(msgs.iter().all(|msg| msg.len() == len));
+
+        let mut states = [[vdupq_n_u64(0); 25]; 2];
+        let mut offset = 0;
+        loop {
+            let n = RATE.min(len - offset);
+            let mut blocks = [[0u8; RATE]; 4];
+            for (block, msg) in blocks.iter_mut().zip(msgs) {
+                block[..n].copy_from_slice(&msg[offset..offset + n]);
+                if n < RATE {
+                    // SHAKE domain separation and pad10*1
+                    block[n] ^= 0x1f;
+                    block[RATE - 1] ^= 0x80;
+                }
+            }
+            for (state, blocks) in states.iter_mut().zip(blocks.chunks_exact(2)) {
+                Self::xor_blocks(state, [&blocks[0], &blocks[1]]);
+                keccak_f1600(state);
+            }
+
+            if n < RATE {
+                break;
+            }
+            offset += RATE;
+        }
+
+        ShakeX4 {
+            states,
+            squeezed: false,
+        }
+    }
+
+    #[target_feature(enable = "neon")]
+    unsafe fn xor_blocks(state: &mut State, blocks: [&[u8; RATE]; 2]) {
+        let word = |i: usize, k: usize| {
+            let mut w = [0u8; 8];
+            w.copy_from_slice(&blocks[i][8 * k..8 * (k + 1)]);
+            u64::from_le_bytes(w)
+        };
+        for (k, lane) in state.iter_mut().take(RATE / 8).enumerate() {
+            let words = [word(0, k), word(1, k)];
+            *lane = veorq_u64(*lane, vld1q_u64(words.as_ptr()));
+        }
+    }
+
+    /// Read the next block of output of each instance.
+    ///
+    /// # Safety
+    /// The CPU must support NEON.
+    #[target_feature(enable = "neon")]
+    pub(crate) unsafe fn squeeze(&mut self, out: &mut [[u8; RATE]; 4]) {
+        if self.squeezed {
+            for state in &mut self.states {
+                keccak_f1600(state);
+            }
+        }
+        self.squeezed = true;
+
+        for (state, out) in self.states.iter().zip(out.chunks_exact_mut(2)) {
+            for (k, lane) in state.iter().take(RATE / 8).enumerate() {
+                let mut words = [0u64; 2];
+                vst1q_u64(words.as_mut_ptr(), *lane);
+                for (out, word) in out.iter_mut().zip(words) {
+                    out[8 * k..8 * (k + 1)].copy_from_slice(&word.to_le_bytes());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_possible_truncation)] // Test inputs are built from truncated indices
+mod tests {
+    use super::*;
+    use digest::{ExtendableOutput, Update, XofReader};
+    use proptest::prelude::*;
+
+    fn check<X: Default + Update + ExtendableOutput, const RATE: usize>(msgs: &[Vec<u8>; 4]) {
+        let mut expected = [[0u8; 3 * 168]; 4];
+        for (expected, msg) in expected.iter_mut().zip(msgs) {
+            let mut xof = X::default();
+            xof.update(msg);
+            xof.finalize_xof().read(&mut expected[..3 * RATE]);
+        }
+
+        // SAFETY: callers check NEON support
+        let mut xof = unsafe { ShakeX4::<RATE>::new(msgs.each_ref().map(Vec::as_slice)) };
+        let mut out = [[0u8; RATE]; 4];
+        for block in 0..3 {
+            // SAFETY: callers check NEON support
+            unsafe { xof.squeeze(&mut out) };
+            for (out, expected) in out.iter().zip(&expected) {
+                assert_eq!(out[..], expected[block * RATE..(block + 1) * RATE]);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_shake_x4_matches_portable(len in 0usize..400, seed: [u8; 4]) {
+            if super::super::available() {
+                let msgs = seed.map(|s| (0..len).map(|i| s.wrapping_add(i as u8)).collect());
+                check::<sha3::Shake128, 168>(&msgs);
+                check::<sha3::Shake256, 136>(&msgs);
+            }
+        }
+    }
+}
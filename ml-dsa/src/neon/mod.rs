@@ -0,0 +1,17 @@
+//! NEON implementations of the NTT and of 4-way parallel SHAKE
+//!
+//! These are used on 64-bit ARM targets which enable NEON, as all the standard ones do,
+//! and always produce the same results as the portable implementations.
+use core::arch::aarch64 as arch;
+
+mod keccak;
+mod ntt;
+
+pub(crate) use keccak::{Shake128x4, Shake256x4};
+pub(crate) use ntt::{ntt, ntt_inverse, ntt_mul};
+
+/// Whether the target supports NEON. There is no runtime detection, as NEON is part of the
+/// baseline of the targets which enable it.
+pub(crate) fn available() -> bool {
+    cfg!(target_feature = "neon")
+}
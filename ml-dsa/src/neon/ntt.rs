@@ -0,0 +1,297 @@
+//! The NTT and multiplication in `T_q` on four coefficients at a time
+//!
+//! Multiplications use Montgomery reduction with `R = 2^32`, with the constants premultiplied by
+//! `R` so that every result is the canonical representative computed by the portable code.
+
+use super::arch::{
+    uint32x4_t, vaddq_u32, vdupq_n_u32, vget_low_u32, vld1q_u32, vminq_u32, vmlsl_u32, vmovn_u64,
+    vmul_u32, vmull_high_u32, vmull_u32, vreinterpretq_u32_u64, vreinterpretq_u64_u32,
+    vshrn_high_n_u64, vshrn_n_u64, vst1q_u32, vsubq_u32, vtrn1q_u32, vtrn1q_u64, vtrn2q_u32,
+    vtrn2q_u64,
+};
+use crate::algebra::{mul, reduce, Poly, N, Q};
+use crate::ntt::{N_INV, ZETAS};
+
+/// `q^-1 mod 2^32`
+const QINV: u32 = 58_728_449;
+const _: () = assert!(Q.wrapping_mul(QINV) == 1);
+
+/// `2^32 mod q`
+const MONT: u32 = reduce(1 << 32);
+
+/// `2^64 mod q`, to undo the factor `2^-32` of a Montgomery multiplication
+const MONT2: u32 = mul(MONT, MONT);
+
+/// `256^-1 * 2^32 mod q`
+const N_INV_MONT: u32 = mul(N_INV, MONT);
+
+/// `ZETAS[m] * 2^32 mod q`
+const ZETAS_MONT: [u32; N] = {
+    let mut zetas = [0; N];
+    let mut m = 0;
+    while m < N {
+        zetas[m] = mul(ZETAS[m], MONT);
+        m += 1;
+    }
+    zetas
+};
+
+/// `-ZETAS[m] * 2^32 mod q`
+const NEG_ZETAS_MONT: [u32; N] = {
+    let mut zetas = [0; N];
+    let mut m = 0;
+    while m < N {
+        zetas[m] = mul(Q - ZETAS[m], MONT);
+        m += 1;
+    }
+    zetas
+};
+
+/// A polynomial as 64 vectors of four coefficients
+type Vectors = [uint32x4_t; N / 4];
+
+#[target_feature(enable = "neon")]
+unsafe fn load(p: &Poly) -> Vectors {
+    let mut v = [vdupq_n_u32(0); N / 4];
+    for (i, v) in v.iter_mut().enumerate() {
+        *v = vld1q_u32(p.0.as_ptr().add(4 * i));
+    }
+    v
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn store(v: &Vectors) -> Poly {
+    let mut p = Poly::default();
+    for (i, v) in v.iter().enumerate() {
+        vst1q_u32(p.0.as_mut_ptr().add(4 * i), *v);
+    }
+    p
+}
+
+/// Four zetas from `table`, at the given indices
+#[target_feature(enable = "neon")]
+unsafe fn gather(table: &[u32; N], m: [usize; 4]) -> uint32x4_t {
+    let zetas = m.map(|m| table[m]);
+    vld1q_u32(zetas.as_ptr())
+}
+
+/// Map lanes in `(-q, q)` to `[0, q)`. Negative lanes wrap around when `q` is added, which makes
+/// them the smaller of the two.
+#[target_feature(enable = "neon")]
+unsafe fn normalize(r: uint32x4_t) -> uint32x4_t {
+    vminq_u32(r, vaddq_u32(r, vdupq_n_u32(Q)))
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn add(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+    normalize(vsubq_u32(vaddq_u32(a, b), vdupq_n_u32(Q)))
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn sub(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+    normalize(vsubq_u32(a, b))
+}
+
+/// Montgomery multiplication `a * b * 2^-32 mod q` of canonical field elements
+#[target_feature(enable = "neon")]
+unsafe fn montmul(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+    let q = vdupq_n_u32(Q);
+    let qinv = vget_low_u32(vdupq_n_u32(QINV));
+
+    // 64-bit products of the low and high pairs of lanes
+    let t_low = vmull_u32(vget_low_u32(a), vget_low_u32(b));
+    let t_high = vmull_high_u32(a, b);
+
+    // m = t * q^-1 mod 2^32, so that t - m * q is divisible by 2^32
+    let m_low = vmul_u32(vmovn_u64(t_low), qinv);
+    let m_high = vmul_u32(vmovn_u64(t_high), qinv);
+    let r_low = vmlsl_u32(t_low, m_low, vget_low_u32(q));
+    let r_high = vmlsl_u32(t_high, m_high, vget_low_u32(q));
+
+    // The high halves hold (t - m * q) / 2^32, which lies in (-q, q)
+    normalize(vshrn_high_n_u64::<32>(vshrn_n_u64::<32>(r_low), r_high))
+}
+
+/// Cooley-Tukey butterfly `(a + z * b, a - z * b)`, with `z` in Montgomery form
+#[target_feature(enable = "neon")]
+unsafe fn ct_butterfly(a: uint32x4_t, b: uint32x4_t, z: uint32x4_t) -> (uint32x4_t, uint32x4_t) {
+    let t = montmul(b, z);
+    (add(a, t), sub(a, t))
+}
+
+/// Gentleman-Sande butterfly `(a + b, z * (a - b))`, with `z` in Montgomery form
+#[target_feature(enable = "neon")]
+unsafe fn gs_butterfly(a: uint32x4_t, b: uint32x4_t, z: uint32x4_t) -> (uint32x4_t, uint32x4_t) {
+    (add(a, b), montmul(sub(a, b), z))
+}
+
+/// Split two vectors into the even and odd 64-bit lanes of each
+#[target_feature(enable = "neon")]
+unsafe fn interleave_64(v0: uint32x4_t, v1: uint32x4_t) -> (uint32x4_t, uint32x4_t) {
+    let (v0, v1) = (vreinterpretq_u64_u32(v0), vreinterpretq_u64_u32(v1));
+    (
+        vreinterpretq_u32_u64(vtrn1q_u64(v0, v1)),
+        vreinterpretq_u32_u64(vtrn2q_u64(v0, v1)),
+    )
+}
+
+/// Split two vectors into the even and odd 32-bit lanes of each
+#[target_feature(enable = "neon")]
+unsafe fn interleave_32(v0: uint32x4_t, v1: uint32x4_t) -> (uint32x4_t, uint32x4_t) {
+    (vtrn1q_u32(v0, v1), vtrn2q_u32(v0, v1))
+}
+
+/// The zetas for the butterflies of layer `len = 2`, as laid out by [`interleave_64`]
+fn zetas_len2(first: usize) -> [usize; 4] {
+    let [a, b] = [first, first + 1];
+    [a, a, b, b]
+}
+
+/// The zetas for the butterflies of layer `len = 1`, as laid out by [`interleave_32`]
+fn zetas_len1(first: usize) -> [usize; 4] {
+    [0, 2, 1, 3].map(|i| first + i)
+}
+
+/// Algorithm 41: NTT
+///
+/// # Safety
+/// The CPU must support NEON.
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn ntt(p: &Poly) -> Poly {
+    let mut v = load(p);
+
+    // Layers with `len >= 4` pair up whole vectors. Block `i` of a layer with `nb` blocks uses
+    // `ZETAS[nb + i]`.
+    let mut len = N / 8;
+    while len >= 1 {
+        let nb = N / 8 / len;
+        for i in 0..nb {
+            let z = vdupq_n_u32(ZETAS_MONT[nb + i]);
+            for j in 2 * i * len..(2 * i + 1) * len {
+                (v[j], v[j + len]) = ct_butterfly(v[j], v[j + len], z);
+            }
+        }
+        len /= 2;
+    }
+
+    // Layers with `len < 4` pair up coefficients within two adjacent vectors
+    for i in 0..N / 8 {
+        let (j, k) = (2 * i, 2 * i + 1);
+
+        let (a, b) = interleave_64(v[j], v[k]);
+        let (a, b) = ct_butterfly(a, b, gather(&ZETAS_MONT, zetas_len2(64 + 2 * i)));
+        (v[j], v[k]) = interleave_64(a, b);
+
+        let (a, b) = interleave_32(v[j], v[k]);
+        let (a, b) = ct_butterfly(a, b, gather(&ZETAS_MONT, zetas_len1(128 + 4 * i)));
+        (v[j], v[k]) = interleave_32(a, b);
+    }
+
+    store(&v)
+}
+
+/// Algorithm 42: NTT^-1
+///
+/// # Safety
+/// The CPU must support NEON.
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn ntt_inverse(p: &Poly) -> Poly {
+    let mut v = load(p);
+
+    // Block `i` of a layer with `nb` blocks uses `-ZETAS[2 * nb - 1 - i]`
+    for i in 0..N / 8 {
+        let (j, k) = (2 * i, 2 * i + 1);
+        let rev = |m: [usize; 4]| m.map(|m| N - 1 - m);
+
+        let (a, b) = interleave_32(v[j], v[k]);
+        let (a, b) = gs_butterfly(a, b, gather(&NEG_ZETAS_MONT, rev(zetas_len1(4 * i))));
+        (v[j], v[k]) = interleave_32(a, b);
+
+        let (a, b) = interleave_64(v[j], v[k]);
+        let z = gather(&NEG_ZETAS_MONT, rev(zetas_len2(N / 2 + 2 * i)));
+        let (a, b) = gs_butterfly(a, b, z);
+        (v[j], v[k]) = interleave_64(a, b);
+    }
+
+    let mut len = 1;
+    while len < N / 4 {
+        let nb = N / 8 / len;
+        for i in 0..nb {
+            let z = vdupq_n_u32(NEG_ZETAS_MONT[2 * nb - 1 - i]);
+            for j in 2 * i * len..(2 * i + 1) * len {
+                (v[j], v[j + len]) = gs_butterfly(v[j], v[j + len], z);
+            }
+        }
+        len *= 2;
+    }
+
+    let n_inv = vdupq_n_u32(N_INV_MONT);
+    for v in &mut v {
+        *v = montmul(*v, n_inv);
+    }
+    store(&v)
+}
+
+/// Algorithm 45: `MultiplyNTT`
+///
+/// # Safety
+/// The CPU must support NEON.
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn ntt_mul(a: &Poly, b: &Poly) -> Poly {
+    let (a, mut b) = (load(a), load(b));
+    let mont2 = vdupq_n_u32(MONT2);
+    for (a, b) in a.iter().zip(b.iter_mut()) {
+        *b = montmul(montmul(*a, *b), mont2);
+    }
+    store(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ntt::{ntt_inverse_portable, ntt_mul_portable, ntt_portable};
+    use proptest::prelude::*;
+
+    fn arb_poly() -> impl Strategy<Value = Poly> {
+        prop::collection::vec(0..Q, N).prop_map(|v| Poly(v.try_into().unwrap()))
+    }
+
+    proptest! {
+        #[test]
+        fn test_ntt_matches_portable(p in arb_poly()) {
+            if super::super::available() {
+                // SAFETY: NEON support was checked above
+                unsafe {
+                    prop_assert_eq!(ntt(&p), ntt_portable(&p));
+                    prop_assert_eq!(ntt_inverse(&p), ntt_inverse_portable(&p));
+                }
+            }
+        }
+
+        #[test]
+        fn test_ntt_mul_matches_portable(a in arb_poly(), b in arb_poly()) {
+            if super::super::available() {
+                // SAFETY: NEON support was checked above
+                unsafe {
+                    prop_assert_eq!(ntt_mul(&a, &b), ntt_mul_portable(&a, &b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_extreme_coefficients() {
+        if !super::super::available() {
+            return;
+        }
+        for x in [0, 1, Q - 1] {
+            let p = Poly([x; N]);
+            // SAFETY: NEON support was checked above
+            unsafe {
+                assert_eq!(ntt(&p), ntt_portable(&p));
+                assert_eq!(ntt_inverse(&p), ntt_inverse_portable(&p));
+                assert_eq!(ntt_mul(&p, &p), ntt_mul_portable(&p, &p));
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! The Number-Theoretic Transform and multiplication in `T_q`
+//!
+//! Follows algorithms 41 to 46 of FIPS-204. The NTT and multiplication in `T_q` use AVX2 or NEON
+//! when available.
+use crate::algebra::{add, mul, sub, Matrix, Poly, Vector, N};
+use hybrid_array::{Array, ArraySize};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::avx2;
+#[cfg(target_arch = "aarch64")]
+use crate::neon;
+
+/// A primitive 512th root of unity modulo q
+const ZETA: u32 = 1753;
+
+/// `256^-1 mod q`, used to scale the output of the inverse NTT
+pub(crate) const N_INV: u32 = 8_347_681;
+
+const fn pow(base: u32, mut exp: usize) -> u32 {
+    let mut acc = 1;
+    let mut b = base;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul(acc, b);
+        }
+        b = mul(b, b);
+        exp >>= 1;
+    }
+    acc
+}
+
+/// `ZETA^BitRev8(k) mod q` for `k` in `0..256` (Appendix B of FIPS-204)
+#[allow(clippy::cast_possible_truncation)] // k < 256
+pub(crate) const ZETAS: [u32; N] = {
+    let mut zetas = [0; N];
+    let mut k = 0;
+    while k < N {
+        zetas[k] = pow(ZETA, (k as u8).reverse_bits() as usize);
+        k += 1;
+    }
+    zetas
+};
+
+/// Algorithm 41: NTT
+pub(crate) fn ntt(p: &Poly) -> Poly {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if avx2::available() {
+        // SAFETY: AVX2 support was checked above
+        return unsafe { avx2::ntt(p) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if neon::available() {
+        // SAFETY: NEON support was checked above
+        return unsafe { neon::ntt(p) };
+    }
+    ntt_portable(p)
+}
+
+/// Portable implementation of [`ntt`]
+pub(crate) fn ntt_portable(p: &Poly) -> Poly {
+    let mut w = p.0;
+    let mut m = 0;
+    let mut len = 128;
+    while len >= 1 {
+        let mut start = 0;
+        while start < N {
+            m += 1;
+            let z = ZETAS[m];
+            for j in start..start + len {
+                let t = mul(z, w[j + len]);
+                w[j + len] = sub(w[j], t);
+                w[j] = add(w[j], t);
+            }
+            start += 2 * len;
+        }
+        len /= 2;
+    }
+    Poly(w)
+}
+
+/// Algorithm 42: NTT^-1
+pub(crate) fn ntt_inverse(p: &Poly) -> Poly {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if avx2::available() {
+        // SAFETY: AVX2 support was checked above
+        return unsafe { avx2::ntt_inverse(p) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if neon::available() {
+        // SAFETY: NEON support was checked above
+        return unsafe { neon::ntt_inverse(p) };
+    }
+    ntt_inverse_portable(p)
+}
+
+/// Portable implementation of [`ntt_inverse`]
+pub(crate) fn ntt_inverse_portable(p: &Poly) -> Poly {
+    let mut w = p.0;
+    let mut m = N;
+    let mut len = 1;
+    while len < N {
+        let mut start = 0;
+        while start < N {
+            m -= 1;
+            let z = sub(0, ZETAS[m]);
+            for j in start..start + len {
+                let t = w[j];
+                w[j] = add(t, w[j + len]);
+                w[j + len] = mul(z, sub(t, w[j + len]));
+            }
+            start += 2 * len;
+        }
+        len *= 2;
+    }
+    for x in &mut w {
+        *x = mul(*x, N_INV);
+    }
+    Poly(w)
+}
+
+/// Algorithm 45: `MultiplyNTT`, coefficient-wise multiplication in `T_q`
+pub(crate) fn ntt_mul(a: &Poly, b: &Poly) -> Poly {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if avx2::available() {
+        // SAFETY: AVX2 support was checked above
+        return unsafe { avx2::ntt_mul(a, b) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if neon::available() {
+        // SAFETY: NEON support was checked above
+        return unsafe { neon::ntt_mul(a, b) };
+    }
+    ntt_mul_portable(a, b)
+}
+
+/// Portable implementation of [`ntt_mul`]
+pub(crate) fn ntt_mul_portable(a: &Poly, b: &Poly) -> Poly {
+    Poly(core::array::from_fn(|i| mul(a.0[i], b.0[i])))
+}
+
+/// Apply the NTT to every entry of a vector
+pub(crate) fn ntt_vec<K: ArraySize>(v: &Vector<K>) -> Vector<K> {
+    Array::from_fn(|i| ntt(&v[i]))
+}
+
+/// Apply the inverse NTT to every entry of a vector
+pub(crate) fn ntt_inverse_vec<K: ArraySize>(v: &Vector<K>) -> Vector<K> {
+    Array::from_fn(|i| ntt_inverse(&v[i]))
+}
+
+/// Multiply every entry of an NTT-domain vector by an NTT-domain scalar
+pub(crate) fn scalar_vec_ntt_mul<K: ArraySize>(c: &Poly, v: &Vector<K>) -> Vector<K> {
+    Array::from_fn(|i| ntt_mul(c, &v[i]))
+}
+
+/// Algorithm 48: `MatrixVectorNTT`, computing `Â ∘ v̂`
+pub(crate) fn matrix_vec_ntt_mul<K: ArraySize, L: ArraySize>(
+    a_hat: &Matrix<K, L>,
+    v_hat: &Vector<L>,
+) -> Vector<K> {
+    Array::from_fn(|i| {
+        a_hat[i]
+            .iter()
+            .zip(v_hat.iter())
+            .fold(Poly::default(), |acc, (a, v)| acc.add(&ntt_mul(a, v)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::Q;
+    use proptest::prelude::*;
+
+    /// Schoolbook negacyclic multiplication in `R_q`
+    fn schoolbook_mul(a: &Poly, b: &Poly) -> Poly {
+        let mut out = Poly::default();
+        for i in 0..N {
+            for j in 0..N {
+                let prod = mul(a.0[i], b.0[j]);
+                if i + j < N {
+                    out.0[i + j] = add(out.0[i + j], prod);
+                } else {
+                    out.0[i + j - N] = sub(out.0[i + j - N], prod);
+                }
+            }
+        }
+        out
+    }
+
+    fn arb_poly() -> impl Strategy<Value = Poly> {
+        prop::collection::vec(0..Q, N).prop_map(|v| Poly(v.try_into().unwrap()))
+    }
+
+    #[test]
+    fn test_zetas_table() {
+        // Spot-check against Appendix B of FIPS-204
+        assert_eq!(ZETAS[0], 1);
+        assert_eq!(ZETAS[1], 4_808_194);
+        assert_eq!(ZETAS[2], 3_765_607);
+        assert_eq!(ZETAS[255], 7_648_983);
+    }
+
+    proptest! {
+        #[test]
+        fn test_ntt_roundtrip(p in arb_poly()) {
+            prop_assert_eq!(ntt_inverse(&ntt(&p)), p);
+        }
+
+        #[test]
+        fn test_ntt_mul_matches_schoolbook(a in arb_poly(), b in arb_poly()) {
+            let c = ntt_inverse(&ntt_mul(&ntt(&a), &ntt(&b)));
+            prop_assert_eq!(c, schoolbook_mul(&a, &b));
+        }
+    }
+}
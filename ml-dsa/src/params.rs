@@ -0,0 +1,119 @@
+//! The ML-DSA parameter sets of FIPS-204 section 4
+use crate::algebra::{D, Q};
+use crate::encode::bitlen;
+use core::fmt::Debug;
+use hybrid_array::ArraySize;
+use typenum::{U32, U4, U48, U5, U6, U64, U7, U8};
+
+/// The lattice parameters of an ML-DSA parameter set (table 1 of FIPS-204)
+pub(crate) trait MlDsaParams: Sized + Clone + Debug + PartialEq + Eq {
+    /// Number of rows of the matrix `A` (`k`)
+    type K: ArraySize + Debug + Eq;
+    /// Number of columns of the matrix `A` (`l`)
+    type L: ArraySize + Debug + Eq;
+    /// Length of the commitment hash `c̃` in bytes (`λ/4`)
+    type CTildeLen: ArraySize + Debug + Eq;
+
+    /// Private key range
+    const ETA: u32;
+    /// Number of ±1's in the challenge polynomial
+    const TAU: usize;
+    /// Coefficient range of `y`
+    const GAMMA1: u32;
+    /// Low-order rounding range
+    const GAMMA2: u32;
+    /// Max number of 1's in the hint
+    const OMEGA: usize;
+    /// `τ * η`
+    #[allow(clippy::cast_possible_truncation)] // tau < 256
+    const BETA: u32 = Self::TAU as u32 * Self::ETA;
+
+    /// Length in bytes of a bit-packed polynomial with coefficients in `[-η, η]`
+    const ETA_POLY_LEN: usize = 32 * bitlen(2 * Self::ETA);
+    /// Length in bytes of a bit-packed polynomial with coefficients in `[-γ1 + 1, γ1]`
+    const Z_POLY_LEN: usize = 32 * bitlen(2 * Self::GAMMA1 - 1);
+    /// Length in bytes of a bit-packed polynomial of `w1`
+    const W1_POLY_LEN: usize = 32 * bitlen((Q - 1) / (2 * Self::GAMMA2) - 1);
+}
+
+/// Length in bytes of a bit-packed polynomial of `t1`
+pub(crate) const T1_POLY_LEN: usize = 32 * bitlen((Q - 1) >> D);
+
+/// Length in bytes of a bit-packed polynomial of `t0`
+pub(crate) const T0_POLY_LEN: usize = 32 * D as usize;
+
+/// ML-DSA-44 (NIST security category 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlDsa44;
+impl MlDsaParams for MlDsa44 {
+    type K = U4;
+    type L = U4;
+    type CTildeLen = U32;
+    const ETA: u32 = 2;
+    const TAU: usize = 39;
+    const GAMMA1: u32 = 1 << 17;
+    const GAMMA2: u32 = (Q - 1) / 88;
+    const OMEGA: usize = 80;
+}
+
+/// ML-DSA-65 (NIST security category 3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlDsa65;
+impl MlDsaParams for MlDsa65 {
+    type K = U6;
+    type L = U5;
+    type CTildeLen = U48;
+    const ETA: u32 = 4;
+    const TAU: usize = 49;
+    const GAMMA1: u32 = 1 << 19;
+    const GAMMA2: u32 = (Q - 1) / 32;
+    const OMEGA: usize = 55;
+}
+
+/// ML-DSA-87 (NIST security category 5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlDsa87;
+impl MlDsaParams for MlDsa87 {
+    type K = U8;
+    type L = U7;
+    type CTildeLen = U64;
+    const ETA: u32 = 2;
+    const TAU: usize = 60;
+    const GAMMA1: u32 = 1 << 19;
+    const GAMMA2: u32 = (Q - 1) / 32;
+    const OMEGA: usize = 75;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParameterSet;
+    use typenum::Unsigned;
+
+    // Table 2 of FIPS-204
+    fn test_encoded_sizes<P: ParameterSet>(vk: usize, sk: usize, sig: usize) {
+        let k = P::K::USIZE;
+        let l = P::L::USIZE;
+        assert_eq!(P::VkLen::USIZE, vk);
+        assert_eq!(32 + k * T1_POLY_LEN, vk);
+        assert_eq!(P::SkLen::USIZE, sk);
+        assert_eq!(128 + (k + l) * P::ETA_POLY_LEN + k * T0_POLY_LEN, sk);
+        assert_eq!(P::SigLen::USIZE, sig);
+        assert_eq!(P::CTildeLen::USIZE + l * P::Z_POLY_LEN + P::OMEGA + k, sig);
+    }
+
+    #[test]
+    fn test_encoded_sizes_ml_dsa_44() {
+        test_encoded_sizes::<MlDsa44>(1312, 2560, 2420);
+    }
+
+    #[test]
+    fn test_encoded_sizes_ml_dsa_65() {
+        test_encoded_sizes::<MlDsa65>(1952, 4032, 3309);
+    }
+
+    #[test]
+    fn test_encoded_sizes_ml_dsa_87() {
+        test_encoded_sizes::<MlDsa87>(2592, 4896, 4627);
+    }
+}
@@ -0,0 +1,187 @@
+//! High-order / low-order bit decomposition and hints
+//!
+//! Follows section 7.4 of FIPS-204. All inputs are canonical field elements in `[0, q)`.
+use crate::algebra::{add, from_signed, Poly, Vector, D, N, Q};
+use hybrid_array::{Array, ArraySize};
+
+/// Compute `r mod± alpha` for a canonical `r`, returning a value in `(-alpha/2, alpha/2]`
+#[inline]
+#[allow(clippy::cast_possible_wrap)] // alpha < 2^23
+fn mod_plus_minus(r: u32, alpha: u32) -> i32 {
+    let r0 = (r % alpha) as i32;
+    if r0 > (alpha / 2) as i32 {
+        r0 - alpha as i32
+    } else {
+        r0
+    }
+}
+
+/// Algorithm 35: `Power2Round`, returning `(r1, r0)` with `r = r1 * 2^d + r0`
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)] // 0 <= r - r0 < q < 2^31
+pub(crate) fn power2round(r: u32) -> (u32, i32) {
+    let r0 = mod_plus_minus(r, 1 << D);
+    let r1 = ((r as i32 - r0) as u32) >> D;
+    (r1, r0)
+}
+
+/// Algorithm 36: `Decompose`, returning `(r1, r0)` with `r = r1 * 2 * gamma2 + r0`
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)] // 0 <= r - r0 < q < 2^31
+pub(crate) fn decompose(r: u32, gamma2: u32) -> (u32, i32) {
+    let r0 = mod_plus_minus(r, 2 * gamma2);
+    let diff = (r as i32 - r0) as u32;
+    if diff == Q - 1 {
+        (0, r0 - 1)
+    } else {
+        (diff / (2 * gamma2), r0)
+    }
+}
+
+/// Algorithm 37: `HighBits`
+pub(crate) fn high_bits(r: u32, gamma2: u32) -> u32 {
+    decompose(r, gamma2).0
+}
+
+/// Algorithm 38: `LowBits`
+pub(crate) fn low_bits(r: u32, gamma2: u32) -> i32 {
+    decompose(r, gamma2).1
+}
+
+/// Algorithm 39: `MakeHint`
+pub(crate) fn make_hint(z: u32, r: u32, gamma2: u32) -> bool {
+    high_bits(r, gamma2) != high_bits(add(r, z), gamma2)
+}
+
+/// Algorithm 40: `UseHint`
+pub(crate) fn use_hint(h: bool, r: u32, gamma2: u32) -> u32 {
+    let m = (Q - 1) / (2 * gamma2);
+    let (r1, r0) = decompose(r, gamma2);
+    match (h, r0 > 0) {
+        (false, _) => r1,
+        (true, true) => (r1 + 1) % m,
+        (true, false) => (r1 + m - 1) % m,
+    }
+}
+
+/// Apply `Power2Round` to every coefficient, returning `(t1, t0)`
+pub(crate) fn power2round_vec<K: ArraySize>(t: &Vector<K>) -> (Vector<K>, Vector<K>) {
+    let mut t1 = Vector::<K>::default();
+    let mut t0 = Vector::<K>::default();
+    for i in 0..K::USIZE {
+        for j in 0..t[i].0.len() {
+            let (hi, lo) = power2round(t[i].0[j]);
+            t1[i].0[j] = hi;
+            t0[i].0[j] = from_signed(lo);
+        }
+    }
+    (t1, t0)
+}
+
+/// Apply `HighBits` to every coefficient
+pub(crate) fn high_bits_vec<K: ArraySize>(v: &Vector<K>, gamma2: u32) -> Vector<K> {
+    Array::from_fn(|i| Poly(v[i].0.map(|x| high_bits(x, gamma2))))
+}
+
+/// Apply `LowBits` to every coefficient
+pub(crate) fn low_bits_vec<K: ArraySize>(v: &Vector<K>, gamma2: u32) -> Vector<K> {
+    Array::from_fn(|i| Poly(v[i].0.map(|x| from_signed(low_bits(x, gamma2)))))
+}
+
+/// The hints of one polynomial, with the hint of coefficient `j` in bit `j % 64` of word `j / 64`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct HintPoly([u64; 4]);
+
+impl HintPoly {
+    /// Collect the hint `f(j)` of every coefficient `j`
+    pub(crate) fn from_fn(mut f: impl FnMut(usize) -> bool) -> Self {
+        let mut h = Self::default();
+        for j in 0..N {
+            if f(j) {
+                h.set(j);
+            }
+        }
+        h
+    }
+
+    /// The hint of coefficient `j`
+    pub(crate) fn get(&self, j: usize) -> bool {
+        (self.0[j / 64] >> (j % 64)) & 1 == 1
+    }
+
+    /// Set the hint of coefficient `j`
+    pub(crate) fn set(&mut self, j: usize) {
+        self.0[j / 64] |= 1 << (j % 64);
+    }
+
+    /// The indices of the coefficients with a hint, in increasing order
+    pub(crate) fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..N).filter(|&j| self.get(j))
+    }
+
+    /// The number of coefficients with a hint
+    pub(crate) fn weight(&self) -> usize {
+        self.0.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+/// A hint vector, with one bit per coefficient
+pub(crate) type Hint<K> = Array<HintPoly, K>;
+
+/// Apply `MakeHint` to every coefficient
+pub(crate) fn make_hint_vec<K: ArraySize>(z: &Vector<K>, r: &Vector<K>, gamma2: u32) -> Hint<K> {
+    Array::from_fn(|i| HintPoly::from_fn(|j| make_hint(z[i].0[j], r[i].0[j], gamma2)))
+}
+
+/// Apply `UseHint` to every coefficient
+pub(crate) fn use_hint_vec<K: ArraySize>(h: &Hint<K>, r: &Vector<K>, gamma2: u32) -> Vector<K> {
+    Array::from_fn(|i| {
+        Poly(core::array::from_fn(|j| {
+            use_hint(h[i].get(j), r[i].0[j], gamma2)
+        }))
+    })
+}
+
+/// The number of set bits in a hint vector
+pub(crate) fn hint_weight<K: ArraySize>(h: &Hint<K>) -> usize {
+    h.iter().map(HintPoly::weight).sum()
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_possible_wrap)] // q < 2^31
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const GAMMA2_44: u32 = (Q - 1) / 88;
+    const GAMMA2_65: u32 = (Q - 1) / 32;
+
+    proptest! {
+        #[test]
+        fn test_power2round_recombines(r in 0..Q) {
+            let (r1, r0) = power2round(r);
+            prop_assert!(r0 > -(1 << (D - 1)) && r0 <= 1 << (D - 1));
+            prop_assert_eq!(add(r1 << D, from_signed(r0)), r);
+        }
+
+        #[test]
+        fn test_decompose_recombines(r in 0..Q) {
+            for gamma2 in [GAMMA2_44, GAMMA2_65] {
+                let (r1, r0) = decompose(r, gamma2);
+                prop_assert!(r1 < (Q - 1) / (2 * gamma2));
+                prop_assert_eq!(add(r1 * 2 * gamma2, from_signed(r0)), r);
+            }
+        }
+
+        // Lemma 1 of the Dilithium specification: UseHint recovers the high bits of r + z
+        #[test]
+        fn test_use_hint_recovers_high_bits(r in 0..Q, z in -(GAMMA2_65 as i32)..=(GAMMA2_65 as i32)) {
+            for gamma2 in [GAMMA2_44, GAMMA2_65] {
+                if z.unsigned_abs() > gamma2 {
+                    continue;
+                }
+                let z = from_signed(z);
+                let h = make_hint(z, r, gamma2);
+                prop_assert_eq!(use_hint(h, r, gamma2), high_bits(add(r, z), gamma2));
+            }
+        }
+    }
+}
@@ -0,0 +1,418 @@
+//! Pseudorandom sampling of polynomials, vectors and matrices
+//!
+//! Follows section 7.3 of FIPS-204
+use crate::algebra::{from_signed, Matrix, Poly, Vector, N, Q};
+use crate::encode::bit_unpack;
+use digest::{ExtendableOutput, Update, XofReader};
+use hybrid_array::{Array, ArraySize};
+use sha3::{Shake128, Shake256};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::avx2;
+#[cfg(target_arch = "aarch64")]
+use crate::neon;
+
+/// Algorithm 14: `CoeffFromThreeBytes`
+fn coeff_from_three_bytes(b: &[u8]) -> Option<u32> {
+    let z = u32::from(b[0]) | (u32::from(b[1]) << 8) | (u32::from(b[2] & 0x7f) << 16);
+    (z < Q).then_some(z)
+}
+
+/// Algorithm 15: `CoeffFromHalfByte`
+fn coeff_from_half_byte(b: u8, eta: u32) -> Option<u32> {
+    match eta {
+        2 if b < 15 => Some(from_signed(2 - i32::from(b % 5))),
+        4 if b < 9 => Some(from_signed(4 - i32::from(b))),
+        _ => None,
+    }
+}
+
+/// Algorithm 29: `SampleInBall`
+pub(crate) fn sample_in_ball(rho: &[u8], tau: usize) -> Poly {
+    let mut xof = Shake256::default();
+    xof.update(rho);
+    let mut reader = xof.finalize_xof();
+
+    let mut s = [0u8; 8];
+    reader.read(&mut s);
+    let signs = u64::from_le_bytes(s);
+
+    let mut c = Poly::default();
+    let mut j = [0u8];
+    for (k, i) in (N - tau..N).enumerate() {
+        loop {
+            reader.read(&mut j);
+            if usize::from(j[0]) <= i {
+                break;
+            }
+        }
+        let j = usize::from(j[0]);
+        c.0[i] = c.0[j];
+        c.0[j] = if (signs >> k) & 1 == 1 { Q - 1 } else { 1 };
+    }
+    c
+}
+
+/// Algorithm 30: `RejNTTPoly`
+pub(crate) fn rej_ntt_poly(seed: &[&[u8]]) -> Poly {
+    let mut xof = Shake128::default();
+    for part in seed {
+        xof.update(part);
+    }
+    let mut reader = xof.finalize_xof();
+
+    let mut a = Poly::default();
+    let mut j = 0;
+    let mut block = [0u8; 168];
+    while j < N {
+        reader.read(&mut block);
+        rej_ntt_block(&block, &mut a, &mut j);
+    }
+    a
+}
+
+/// Fill `a` from index `j` onwards with the coefficients accepted from a block of `RejNTTPoly`
+fn rej_ntt_block(block: &[u8], a: &mut Poly, j: &mut usize) {
+    for b in block.chunks_exact(3) {
+        if *j == N {
+            break;
+        }
+        if let Some(coeff) = coeff_from_three_bytes(b) {
+            a.0[*j] = coeff;
+            *j += 1;
+        }
+    }
+}
+
+/// Algorithm 31: `RejBoundedPoly`
+pub(crate) fn rej_bounded_poly(seed: &[&[u8]], eta: u32) -> Poly {
+    let mut xof = Shake256::default();
+    for part in seed {
+        xof.update(part);
+    }
+    let mut reader = xof.finalize_xof();
+
+    let mut a = Poly::default();
+    let mut j = 0;
+    let mut block = [0u8; 136];
+    while j < N {
+        reader.read(&mut block);
+        rej_bounded_block(&block, eta, &mut a, &mut j);
+    }
+    a
+}
+
+/// Fill `a` from index `j` onwards with the coefficients accepted from a block of
+/// `RejBoundedPoly`
+fn rej_bounded_block(block: &[u8], eta: u32, a: &mut Poly, j: &mut usize) {
+    for &z in block {
+        for half in [z & 0x0f, z >> 4] {
+            if *j < N {
+                if let Some(coeff) = coeff_from_half_byte(half, eta) {
+                    a.0[*j] = coeff;
+                    *j += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Algorithm 32: `ExpandA`, returning the matrix `Â` in NTT form
+pub(crate) fn expand_a<K: ArraySize, L: ArraySize>(rho: &[u8]) -> Matrix<K, L> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if avx2::available() {
+        // SAFETY: AVX2 support was checked above
+        return unsafe { x4::expand_a(rho) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if neon::available() {
+        // SAFETY: NEON support was checked above
+        return unsafe { x4::expand_a(rho) };
+    }
+    Array::from_fn(|r| Array::from_fn(|s| expand_a_entry(rho, r, s)))
+}
+
+/// The entry `Â[r][s]` of the matrix returned by `ExpandA`
+#[allow(clippy::cast_possible_truncation)] // r, s < 256
+pub(crate) fn expand_a_entry(rho: &[u8], r: usize, s: usize) -> Poly {
+    rej_ntt_poly(&[rho, &[s as u8, r as u8]])
+}
+
+/// Algorithm 33: `ExpandS`, returning the secret vectors `(s1, s2)`
+#[allow(clippy::cast_possible_truncation)] // K + L < 2^16
+pub(crate) fn expand_s<K: ArraySize, L: ArraySize>(rho: &[u8], eta: u32) -> (Vector<L>, Vector<K>) {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if avx2::available() {
+        // SAFETY: AVX2 support was checked above
+        return unsafe { x4::expand_s(rho, eta) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if neon::available() {
+        // SAFETY: NEON support was checked above
+        return unsafe { x4::expand_s(rho, eta) };
+    }
+    let s1 = Array::from_fn(|r| rej_bounded_poly(&[rho, &(r as u16).to_le_bytes()], eta));
+    let s2 =
+        Array::from_fn(|r| rej_bounded_poly(&[rho, &((r + L::USIZE) as u16).to_le_bytes()], eta));
+    (s1, s2)
+}
+
+/// Algorithm 34: `ExpandMask`
+pub(crate) fn expand_mask<L: ArraySize>(rho: &[u8], kappa: u16, gamma1: u32) -> Vector<L> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if avx2::available() {
+        // SAFETY: AVX2 support was checked above
+        return unsafe { x4::expand_mask(rho, kappa, gamma1) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if neon::available() {
+        // SAFETY: NEON support was checked above
+        return unsafe { x4::expand_mask(rho, kappa, gamma1) };
+    }
+    Array::from_fn(|r| expand_mask_entry(rho, kappa, r, gamma1))
+}
+
+/// The entry `y[r]` of the vector returned by `ExpandMask`
+#[allow(clippy::cast_possible_truncation)] // L < 2^16
+pub(crate) fn expand_mask_entry(rho: &[u8], kappa: u16, r: usize, gamma1: u32) -> Poly {
+    // `c = 1 + bitlen(gamma1 - 1)`, and gamma1 is always a power of two
+    let c = gamma1.trailing_zeros() as usize + 1;
+    let mut v = [0u8; 32 * 20];
+    let v = &mut v[..32 * c];
+    let mut xof = Shake256::default();
+    xof.update(rho);
+    xof.update(&kappa.wrapping_add(r as u16).to_le_bytes());
+    xof.finalize_xof().read(v);
+    bit_unpack(v, gamma1 - 1, gamma1)
+}
+
+/// Sampling of four polynomials at a time, using 4-way parallel SHAKE
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+mod x4 {
+    use super::{rej_bounded_block, rej_ntt_block};
+    use crate::algebra::{Matrix, Poly, Vector, N};
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    use crate::avx2::{Shake128x4, Shake256x4};
+    use crate::encode::bit_unpack;
+    #[cfg(target_arch = "aarch64")]
+    use crate::neon::{Shake128x4, Shake256x4};
+    use hybrid_array::ArraySize;
+
+    /// Split `0..count` into groups of four indices. The last group is padded by repeating its
+    /// first index, so that some polynomials are sampled twice.
+    fn groups(count: usize) -> impl Iterator<Item = [usize; 4]> {
+        (0..count).step_by(4).map(move |first| {
+            core::array::from_fn(|i| if first + i < count { first + i } else { first })
+        })
+    }
+
+    /// The seed `rho || index` of a polynomial, with the index in little-endian order
+    #[allow(clippy::cast_possible_truncation)] // Indices are less than 2^16
+    fn seed<const LEN: usize>(rho: &[u8], index: usize) -> [u8; LEN] {
+        let mut seed = [0; LEN];
+        seed[..LEN - 2].copy_from_slice(rho);
+        seed[LEN - 2..].copy_from_slice(&(index as u16).to_le_bytes());
+        seed
+    }
+
+    /// Algorithm 30: `RejNTTPoly`, for four seeds of the same length
+    #[cfg_attr(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature(enable = "avx2")
+    )]
+    #[cfg_attr(target_arch = "aarch64", target_feature(enable = "neon"))]
+    unsafe fn rej_ntt_poly<const LEN: usize>(seeds: &[[u8; LEN]; 4]) -> [Poly; 4] {
+        let mut xof = Shake128x4::new(seeds.each_ref().map(<[u8; LEN]>::as_slice));
+        let mut a = [Poly::default(); 4];
+        let mut j = [0; 4];
+        let mut blocks = [[0u8; 168]; 4];
+        while j.iter().any(|&j| j < N) {
+            xof.squeeze(&mut blocks);
+            for ((block, a), j) in blocks.iter().zip(&mut a).zip(&mut j) {
+                rej_ntt_block(block, a, j);
+            }
+        }
+        a
+    }
+
+    /// Algorithm 31: `RejBoundedPoly`, for four seeds of the same length
+    #[cfg_attr(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature(enable = "avx2")
+    )]
+    #[cfg_attr(target_arch = "aarch64", target_feature(enable = "neon"))]
+    unsafe fn rej_bounded_poly<const LEN: usize>(seeds: &[[u8; LEN]; 4], eta: u32) -> [Poly; 4] {
+        let mut xof = Shake256x4::new(seeds.each_ref().map(<[u8; LEN]>::as_slice));
+        let mut a = [Poly::default(); 4];
+        let mut j = [0; 4];
+        let mut blocks = [[0u8; 136]; 4];
+        while j.iter().any(|&j| j < N) {
+            xof.squeeze(&mut blocks);
+            for ((block, a), j) in blocks.iter().zip(&mut a).zip(&mut j) {
+                rej_bounded_block(block, eta, a, j);
+            }
+        }
+        a
+    }
+
+    /// Algorithm 32: `ExpandA`
+    ///
+    /// # Safety
+    /// The CPU must support AVX2 or NEON.
+    #[cfg_attr(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature(enable = "avx2")
+    )]
+    #[cfg_attr(target_arch = "aarch64", target_feature(enable = "neon"))]
+    pub(super) unsafe fn expand_a<K: ArraySize, L: ArraySize>(rho: &[u8]) -> Matrix<K, L> {
+        let mut a = Matrix::<K, L>::default();
+        for group in groups(K::USIZE * L::USIZE) {
+            // Entry `(r, s)` is seeded with `rho || s || r`, which is `rho || index` in
+            // little-endian order for `index = 256 * r + s`
+            let seeds = group.map(|e| seed::<34>(rho, 256 * (e / L::USIZE) + e % L::USIZE));
+            let polys = rej_ntt_poly(&seeds);
+            for (e, poly) in group.into_iter().zip(polys) {
+                a[e / L::USIZE][e % L::USIZE] = poly;
+            }
+        }
+        a
+    }
+
+    /// Algorithm 33: `ExpandS`
+    ///
+    /// # Safety
+    /// The CPU must support AVX2 or NEON.
+    #[cfg_attr(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature(enable = "avx2")
+    )]
+    #[cfg_attr(target_arch = "aarch64", target_feature(enable = "neon"))]
+    pub(super) unsafe fn expand_s<K: ArraySize, L: ArraySize>(
+        rho: &[u8],
+        eta: u32,
+    ) -> (Vector<L>, Vector<K>) {
+        let mut s1 = Vector::<L>::default();
+        let mut s2 = Vector::<K>::default();
+        for group in groups(L::USIZE + K::USIZE) {
+            let seeds = group.map(|r| seed::<66>(rho, r));
+            let polys = rej_bounded_poly(&seeds, eta);
+            for (r, poly) in group.into_iter().zip(polys) {
+                if r < L::USIZE {
+                    s1[r] = poly;
+                } else {
+                    s2[r - L::USIZE] = poly;
+                }
+            }
+        }
+        (s1, s2)
+    }
+
+    /// Algorithm 34: `ExpandMask`
+    ///
+    /// # Safety
+    /// The CPU must support AVX2 or NEON.
+    #[cfg_attr(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature(enable = "avx2")
+    )]
+    #[cfg_attr(target_arch = "aarch64", target_feature(enable = "neon"))]
+    pub(super) unsafe fn expand_mask<L: ArraySize>(
+        rho: &[u8],
+        kappa: u16,
+        gamma1: u32,
+    ) -> Vector<L> {
+        // `c = 1 + bitlen(gamma1 - 1)`, and gamma1 is always a power of two
+        let c = gamma1.trailing_zeros() as usize + 1;
+        let mut y = Vector::<L>::default();
+        for group in groups(L::USIZE) {
+            let seeds = group.map(|r| seed::<66>(rho, usize::from(kappa) + r));
+            let mut xof = Shake256x4::new(seeds.each_ref().map(<[u8; 66]>::as_slice));
+
+            // Five blocks hold the `32 * c <= 640` bytes needed
+            let mut v = [[0u8; 5 * 136]; 4];
+            let mut blocks = [[0u8; 136]; 4];
+            for offset in (0..v[0].len()).step_by(136) {
+                xof.squeeze(&mut blocks);
+                for (v, block) in v.iter_mut().zip(&blocks) {
+                    v[offset..offset + 136].copy_from_slice(block);
+                }
+            }
+            for (r, v) in group.into_iter().zip(&v) {
+                y[r] = bit_unpack(&v[..32 * c], gamma1 - 1, gamma1);
+            }
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::centered;
+
+    #[test]
+    fn test_sample_in_ball_weight() {
+        for tau in [39, 49, 60] {
+            let c = sample_in_ball(b"sample in ball test seed", tau);
+            let nonzero = c.0.iter().filter(|&&x| x != 0).count();
+            assert_eq!(nonzero, tau);
+            assert!(c.0.iter().all(|&x| x == 0 || x == 1 || x == Q - 1));
+        }
+    }
+
+    #[test]
+    fn test_rej_bounded_poly_range() {
+        for eta in [2, 4] {
+            let s = rej_bounded_poly(&[&[7u8; 64], &[0, 0]], eta);
+            assert!(s.0.iter().all(|&x| centered(x).unsigned_abs() <= eta));
+        }
+    }
+
+    #[test]
+    fn test_rej_ntt_poly_range() {
+        let a = rej_ntt_poly(&[&[3u8; 32], &[1, 2]]);
+        assert!(a.0.iter().all(|&x| x < Q));
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    proptest::proptest! {
+        #[test]
+        fn test_expand_x4_matches_portable(rho: [u8; 32], rho_prime: [u8; 64], kappa: u16) {
+            use typenum::{U4, U5, U7, U8};
+
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            let available = avx2::available();
+            #[cfg(target_arch = "aarch64")]
+            let available = neon::available();
+            if !available {
+                return Ok(());
+            }
+
+            // SAFETY: AVX2 or NEON support was checked above
+            let a = unsafe { x4::expand_a::<U8, U7>(&rho) };
+            for (r, row) in a.iter().enumerate() {
+                for (s, entry) in row.iter().enumerate() {
+                    proptest::prop_assert_eq!(entry, &expand_a_entry(&rho, r, s));
+                }
+            }
+
+            #[allow(clippy::cast_possible_truncation)] // r < 9
+            for eta in [2, 4] {
+                // SAFETY: AVX2 or NEON support was checked above
+                let (s1, s2) = unsafe { x4::expand_s::<U4, U5>(&rho_prime, eta) };
+                for (r, s) in s1.iter().chain(&s2).enumerate() {
+                    let expected = rej_bounded_poly(&[&rho_prime, &(r as u16).to_le_bytes()], eta);
+                    proptest::prop_assert_eq!(s, &expected);
+                }
+            }
+
+            for gamma1 in [1 << 17, 1 << 19] {
+                // SAFETY: AVX2 or NEON support was checked above
+                let y = unsafe { x4::expand_mask::<U7>(&rho_prime, kappa, gamma1) };
+                for (r, y) in y.iter().enumerate() {
+                    proptest::prop_assert_eq!(y, &expand_mask_entry(&rho_prime, kappa, r, gamma1));
+                }
+            }
+        }
+    }
+}
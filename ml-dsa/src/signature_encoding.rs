@@ -0,0 +1,323 @@
+use crate::algebra::{Poly, Vector};
+use crate::encode::{bit_pack, bit_unpack, hint_bit_pack, hint_bit_unpack};
+use crate::error::SignatureError;
+use crate::params::{MlDsa44, MlDsa65, MlDsa87};
+use crate::rounding::{Hint, HintPoly};
+use crate::SignatureParams;
+use ::signature::SignatureEncoding;
+use core::fmt::Debug;
+use hybrid_array::sizes::{U2420, U3309, U4627};
+use hybrid_array::{Array, ArraySize};
+use typenum::Unsigned;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A parsed ML-DSA signature for a given parameter set
+///
+/// Consists of the commitment hash `c̃`, the response vector `z` and the hint `h`. The signature
+/// is kept in its encoding, which is the smallest form and is always canonical, and the parts of
+/// `z` and `h` are decoded when they are used.
+pub struct Signature<P: SignatureParams> {
+    pub(crate) bytes: Array<u8, P::SigLen>,
+}
+
+impl<P: SignatureParams> Signature<P> {
+    /// Encode a signature from its parts
+    ///
+    /// Implements `sigEncode` (algorithm 26) as defined in FIPS-204.
+    pub(crate) fn encode(c_tilde: &[u8], z: &Vector<P::L>, h: &Hint<P::K>) -> Self {
+        let mut bytes = Array::<u8, P::SigLen>::default();
+        let (c_tilde_out, rest) = bytes.split_at_mut(P::CTildeLen::USIZE);
+        let (z_out, h_out) = rest.split_at_mut(P::L::USIZE * P::Z_POLY_LEN);
+        c_tilde_out.copy_from_slice(c_tilde);
+        for (poly, out) in z.iter().zip(z_out.chunks_exact_mut(P::Z_POLY_LEN)) {
+            bit_pack(poly, P::GAMMA1 - 1, P::GAMMA1, out);
+        }
+        hint_bit_pack(h, P::OMEGA, h_out);
+        Signature { bytes }
+    }
+
+    /// The commitment hash `c̃`
+    pub(crate) fn c_tilde(&self) -> &[u8] {
+        &self.bytes[..P::CTildeLen::USIZE]
+    }
+
+    /// Entry `j` of the response vector `z`
+    pub(crate) fn z(&self, j: usize) -> Poly {
+        let start = P::CTildeLen::USIZE + j * P::Z_POLY_LEN;
+        bit_unpack(
+            &self.bytes[start..start + P::Z_POLY_LEN],
+            P::GAMMA1 - 1,
+            P::GAMMA1,
+        )
+    }
+
+    /// The hints of entry `i` of the hint vector `h`
+    pub(crate) fn hint(&self, i: usize) -> HintPoly {
+        let (indices, ends) =
+            self.bytes[P::SigLen::USIZE - P::OMEGA - P::K::USIZE..].split_at(P::OMEGA);
+        let start = if i == 0 { 0 } else { usize::from(ends[i - 1]) };
+        let mut h = HintPoly::default();
+        for &j in &indices[start..usize::from(ends[i])] {
+            h.set(usize::from(j));
+        }
+        h
+    }
+
+    /// Serialize the signature to a new stack-allocated array
+    ///
+    /// Implements `sigEncode` (algorithm 26) as defined in FIPS-204.
+    pub fn to_bytes(&self) -> Array<u8, P::SigLen> {
+        self.bytes.clone()
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Serialize the signature to a `Vec<u8>` of length `P::SigLen`.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.bytes.to_vec()
+    }
+}
+
+impl<P: SignatureParams> TryFrom<&[u8]> for Signature<P> {
+    type Error = SignatureError;
+
+    /// Implements `sigDecode` (algorithm 27) as defined in FIPS-204.
+    ///
+    /// Only canonical encodings are accepted, so that a decoded signature always re-encodes to
+    /// `bytes`. Signatures with a coefficient of `z` that would fail the norm check of
+    /// verification are rejected as well.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes =
+            Array::<u8, P::SigLen>::try_from(bytes).map_err(|_| SignatureError::InvalidLength)?;
+        let signature = Signature { bytes };
+
+        if (0..P::L::USIZE).any(|j| signature.z(j).infinity_norm() >= P::GAMMA1 - P::BETA) {
+            return Err(SignatureError::ZOutOfRange);
+        }
+        let h = &signature.bytes[P::SigLen::USIZE - P::OMEGA - P::K::USIZE..];
+        hint_bit_unpack::<P::K>(h, P::OMEGA)?;
+
+        Ok(signature)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<P: SignatureParams> From<&Signature<P>> for Vec<u8> {
+    fn from(sig: &Signature<P>) -> Vec<u8> {
+        sig.to_vec()
+    }
+}
+
+/// A trait specifying the length of a serialized signature for a given parameter set
+pub trait SignatureLen {
+    /// The length of the signature in bytes
+    type SigLen: ArraySize + Debug + Eq;
+}
+
+impl<P: SignatureParams> SignatureEncoding for Signature<P> {
+    type Repr = Array<u8, P::SigLen>;
+
+    fn encoded_len(&self) -> usize {
+        P::SigLen::USIZE
+    }
+}
+
+impl<P: SignatureParams> From<Signature<P>> for Array<u8, P::SigLen> {
+    fn from(sig: Signature<P>) -> Array<u8, P::SigLen> {
+        sig.bytes
+    }
+}
+
+impl<P: SignatureParams> TryFrom<&Array<u8, P::SigLen>> for Signature<P> {
+    type Error = SignatureError;
+
+    fn try_from(bytes: &Array<u8, P::SigLen>) -> Result<Self, Self::Error> {
+        Signature::try_from(bytes.as_slice())
+    }
+}
+
+impl SignatureLen for MlDsa44 {
+    type SigLen = U2420;
+}
+
+impl SignatureLen for MlDsa65 {
+    type SigLen = U3309;
+}
+
+impl SignatureLen for MlDsa87 {
+    type SigLen = U4627;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algebra::{from_signed, Vector};
+    use crate::error::SignatureError;
+    use crate::rounding::Hint;
+    use crate::signature_encoding::Signature;
+    use crate::util::macros::test_parameter_sets;
+    use crate::{MlDsa44, ParameterSet, SigningKey};
+    use hybrid_array::Array;
+    use proptest::prelude::*;
+    use signature::{SignatureEncoding, Signer};
+    use typenum::Unsigned;
+
+    fn test_serialize_deserialize<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let msg = b"Hello, world!";
+        let sig = sk.try_sign(msg).unwrap();
+        let sig_bytes = sig.to_bytes();
+        assert_eq!(
+            sig.encoded_len(),
+            sig_bytes.len(),
+            "sig.encoded_len() should equal encoded byte length"
+        );
+        let sig2 = Signature::<P>::try_from(sig_bytes.as_slice()).unwrap();
+        assert_eq!(sig, sig2);
+    }
+
+    test_parameter_sets!(test_serialize_deserialize);
+
+    #[cfg(feature = "alloc")]
+    fn test_serialize_deserialize_vec<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let msg = b"Hello, world!";
+        let sig = sk.try_sign(msg).unwrap();
+        let sig_vec: Vec<u8> = (&sig).into();
+        assert_eq!(sig.encoded_len(), sig_vec.len());
+        let sig2 = Signature::<P>::try_from(sig_vec.as_slice()).unwrap();
+        assert_eq!(sig, sig2);
+    }
+
+    #[cfg(feature = "alloc")]
+    test_parameter_sets!(test_serialize_deserialize_vec);
+
+    #[test]
+    fn test_deserialize_fail_on_incorrect_length() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let sig = sk.try_sign(b"Hello, world!").unwrap();
+        let sig_bytes: Array<u8, _> = sig.into();
+        let incorrect_sig_bytes = &sig_bytes[..sig_bytes.len() - 1];
+        assert_eq!(
+            Signature::<MlDsa44>::try_from(incorrect_sig_bytes),
+            Err(SignatureError::InvalidLength),
+            "Deserialization should fail on incorrect length"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_fail_on_malformed_hint() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let mut sig_bytes = sk.try_sign(b"Hello, world!").unwrap().to_bytes();
+        // Claim more hints in the first polynomial than omega allows
+        let first_count = sig_bytes.len() - 4;
+        sig_bytes[first_count] = 81;
+        assert_eq!(
+            Signature::<MlDsa44>::try_from(sig_bytes.as_slice()),
+            Err(SignatureError::TooManyHints)
+        );
+    }
+
+    /// A signature of `P` with the hint section replaced by the given hint indices and
+    /// cumulative counts
+    fn with_hints<P: ParameterSet>(indices: &[u8], counts: &[u8]) -> Array<u8, P::SigLen> {
+        let sk = SigningKey::<P>::from_seed(&[0; 32].into());
+        let mut sig_bytes = sk.try_sign(b"Hello, world!").unwrap().to_bytes();
+        let (_, h) = sig_bytes.split_at_mut(P::SigLen::USIZE - P::OMEGA - P::K::USIZE);
+        let (y, c) = h.split_at_mut(P::OMEGA);
+        y.fill(0);
+        y[..indices.len()].copy_from_slice(indices);
+        c.fill(counts[counts.len() - 1]);
+        c[..counts.len()].copy_from_slice(counts);
+        sig_bytes
+    }
+
+    fn test_hint_encoding<P: ParameterSet>() {
+        let decode = |bytes: &Array<u8, P::SigLen>| Signature::<P>::try_from(bytes.as_slice());
+
+        // Canonical encodings decode and re-encode to the same bytes
+        for (indices, counts) in [(&[][..], &[0][..]), (&[3, 7, 0, 255], &[2, 2, 4])] {
+            let sig_bytes = with_hints::<P>(indices, counts);
+            assert_eq!(decode(&sig_bytes).unwrap().to_bytes(), sig_bytes);
+        }
+        // All omega hints in the last polynomial
+        let omega = u8::try_from(P::OMEGA).unwrap();
+        let indices: Vec<u8> = (0..omega).collect();
+        let mut counts = vec![0; P::K::USIZE];
+        counts[P::K::USIZE - 1] = omega;
+        let sig_bytes = with_hints::<P>(&indices, &counts);
+        assert_eq!(decode(&sig_bytes).unwrap().to_bytes(), sig_bytes);
+
+        // Indices must be strictly increasing within a polynomial
+        let sig_bytes = with_hints::<P>(&[7, 3], &[2]);
+        assert_eq!(decode(&sig_bytes), Err(SignatureError::UnsortedHints));
+        let sig_bytes = with_hints::<P>(&[3, 3], &[2]);
+        assert_eq!(decode(&sig_bytes), Err(SignatureError::UnsortedHints));
+
+        // Cumulative counts must not decrease
+        let sig_bytes = with_hints::<P>(&[3, 7], &[2, 1, 2]);
+        assert_eq!(decode(&sig_bytes), Err(SignatureError::HintCountDecreasing));
+
+        // At most omega hints in total
+        let sig_bytes = with_hints::<P>(&[], &[0, omega + 1]);
+        assert_eq!(decode(&sig_bytes), Err(SignatureError::TooManyHints));
+
+        // Unused index bytes must be zero
+        let sig_bytes = with_hints::<P>(&[3, 7, 1], &[2]);
+        assert_eq!(decode(&sig_bytes), Err(SignatureError::NonzeroHintPadding));
+        let mut sig_bytes = with_hints::<P>(&[], &[0]);
+        sig_bytes[P::SigLen::USIZE - P::K::USIZE - 1] = 1;
+        assert_eq!(decode(&sig_bytes), Err(SignatureError::NonzeroHintPadding));
+    }
+
+    test_parameter_sets!(test_hint_encoding);
+
+    fn test_z_range<P: ParameterSet>() {
+        let sk = SigningKey::<P>::from_seed(&[0; 32].into());
+        let sig = sk.try_sign(b"Hello, world!").unwrap();
+        let bound = i32::try_from(P::GAMMA1 - P::BETA).unwrap();
+
+        for (z, ok) in [
+            (bound - 1, true),
+            (-(bound - 1), true),
+            (bound, false),
+            (-bound, false),
+            (i32::try_from(P::GAMMA1).unwrap(), false),
+            (-(i32::try_from(P::GAMMA1).unwrap() - 1), false),
+        ] {
+            let mut z_vec = Vector::<P::L>::from_fn(|j| sig.z(j));
+            let h = Hint::<P::K>::from_fn(|i| sig.hint(i));
+            z_vec[P::L::USIZE - 1].0[255] = from_signed(z);
+            let modified = Signature::<P>::encode(sig.c_tilde(), &z_vec, &h);
+            let result = Signature::<P>::try_from(modified.to_bytes().as_slice());
+            if ok {
+                assert_eq!(result, Ok(modified));
+            } else {
+                assert_eq!(result, Err(SignatureError::ZOutOfRange));
+            }
+        }
+    }
+
+    test_parameter_sets!(test_z_range);
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        // Any accepted encoding is the encoding of the decoded signature
+        #[test]
+        fn test_decoding_not_malleable(
+            flips in prop::collection::vec((0..2420usize, 1..=255u8), 1..4),
+        ) {
+            let sk = SigningKey::<MlDsa44>::from_seed(&[0; 32].into());
+            let mut sig_bytes = sk.try_sign(b"Hello, world!").unwrap().to_bytes();
+            for (i, x) in flips {
+                sig_bytes[i] ^= x;
+            }
+            if let Ok(sig) = Signature::<MlDsa44>::try_from(sig_bytes.as_slice()) {
+                prop_assert_eq!(sig.to_bytes(), sig_bytes);
+            }
+        }
+    }
+}
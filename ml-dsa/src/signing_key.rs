@@ -0,0 +1,646 @@
+use crate::algebra::{vec_add, vec_infinity_norm, vec_sub, Vector, D};
+use crate::encode::{bit_pack, bit_unpack};
+use crate::error::SigningKeyError;
+use crate::hashes::{commitment_hash, h, message_representative, Mu};
+use crate::ntt::{matrix_vec_ntt_mul, ntt, ntt_inverse_vec, ntt_vec, scalar_vec_ntt_mul};
+use crate::params::MlDsaParams;
+use crate::params::{MlDsa44, MlDsa65, MlDsa87, T0_POLY_LEN};
+use crate::prehash::{PreHash, PrehashHeader};
+use crate::rounding::{high_bits_vec, hint_weight, low_bits_vec, make_hint_vec, power2round_vec};
+use crate::sampling::{expand_a, expand_mask, expand_s, sample_in_ball};
+use crate::signature_encoding::Signature;
+use crate::streaming;
+use crate::verifying_key::VerifyingKey;
+use crate::{ParameterSet, SignatureParams, VerifyingKeyLen};
+use ::signature::digest::Digest;
+use ::signature::{
+    DigestSigner, Error, KeypairRef, RandomizedDigestSigner, RandomizedSigner, Signer,
+};
+use core::fmt;
+use hybrid_array::sizes::{U2560, U4032, U4896};
+use hybrid_array::{Array, ArraySize};
+use subtle::{Choice, ConstantTimeEq};
+use typenum::{Unsigned, U128, U32, U64};
+
+/// A `SigningKey` allows signing messages with a fixed parameter set
+///
+/// Keys can be stored either as the 32-byte seed `ξ` they were generated from (see
+/// [`SigningKey::to_seed`]) or using the expanded encoding of FIPS-204 (see
+/// [`SigningKey::to_bytes`]). The `Debug` output omits the secret values.
+#[derive(Clone)]
+pub struct SigningKey<P: ParameterSet> {
+    // The seed `ξ`, if the key was generated from or imported as a seed
+    pub(crate) seed: Option<Array<u8, U32>>,
+    pub(crate) key: Array<u8, U32>,
+    pub(crate) s1: Vector<P::L>,
+    pub(crate) s2: Vector<P::K>,
+    pub(crate) t0: Vector<P::K>,
+    pub(crate) verifying_key: VerifyingKey<P>,
+}
+
+// Keys are compared by their expanded form, regardless of whether the seed is known
+impl<P: ParameterSet> ConstantTimeEq for SigningKey<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.key.as_slice().ct_eq(other.key.as_slice())
+            & self.s1.as_slice().ct_eq(other.s1.as_slice())
+            & self.s2.as_slice().ct_eq(other.s2.as_slice())
+            & self.t0.as_slice().ct_eq(other.t0.as_slice())
+            & Choice::from(u8::from(self.verifying_key == other.verifying_key))
+    }
+}
+
+/// Constant-time comparison
+impl<P: ParameterSet> PartialEq for SigningKey<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<P: ParameterSet> Eq for SigningKey<P> {}
+
+impl<P: ParameterSet> fmt::Debug for SigningKey<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("verifying_key", &self.verifying_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A trait specifying the length of a serialized signing key for a given parameter set
+pub trait SigningKeyLen: VerifyingKeyLen {
+    /// The length of the serialized signing key in bytes
+    type SkLen: ArraySize;
+}
+
+impl<P: ParameterSet> SigningKey<P> {
+    /// Create a new `SigningKey` from a cryptographic random number generator
+    pub fn new(rng: &mut impl rand_core::CryptoRngCore) -> Self {
+        let mut xi = Array::<u8, U32>::default();
+        rng.fill_bytes(&mut xi);
+        Self::from_seed(&xi)
+    }
+
+    /// Create a new `SigningKey` from a cryptographic random number generator, and run a
+    /// pairwise consistency test on it before returning it.
+    ///
+    /// This is intended for FIPS 140-3 style deployments, which require newly generated keys to
+    /// be checked by signing and verifying a message.
+    /// # Errors
+    /// Returns [`SigningKeyError::PairwiseConsistency`] if the pairwise consistency test fails.
+    pub fn try_new(rng: &mut impl rand_core::CryptoRngCore) -> Result<Self, SigningKeyError> {
+        let signing_key = Self::new(rng);
+        signing_key.pairwise_consistency_test()?;
+        Ok(signing_key)
+    }
+
+    /// Run a pairwise consistency test: sign a fixed message and check that the signature
+    /// verifies under the corresponding verifying key.
+    /// # Errors
+    /// Returns [`SigningKeyError::PairwiseConsistency`] if the signature does not verify.
+    pub fn pairwise_consistency_test(&self) -> Result<(), SigningKeyError> {
+        const PCT_MESSAGE: &[u8] = b"ML-DSA pairwise consistency test";
+        let signature = self
+            .try_sign_with_context(PCT_MESSAGE, &[], None)
+            .map_err(|_| SigningKeyError::PairwiseConsistency)?;
+        self.verifying_key
+            .try_verify_with_context(PCT_MESSAGE, &[], &signature)
+            .map_err(|_| SigningKeyError::PairwiseConsistency)
+    }
+
+    #[doc(hidden)]
+    #[allow(clippy::must_use_candidate)]
+    /// Construct a new `SigningKey` from a pre-chosen seed.
+    /// Implements `ML-DSA.KeyGen_internal` as defined in FIPS-204.
+    /// Published for KAT validation purposes but not intended for general use.
+    /// `xi` must be a 32 byte slice, panics otherwise.
+    pub fn ml_dsa_keygen_internal(xi: &[u8]) -> Self {
+        Self::from_seed(xi.try_into().unwrap())
+    }
+
+    /// Deterministically derive a `SigningKey` from a 32-byte seed `ξ`
+    ///
+    /// Implements `ML-DSA.KeyGen_internal` (algorithm 6) as defined in FIPS-204.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)] // K, L < 256
+    pub fn from_seed(xi: &Array<u8, U32>) -> Self {
+        let k = [P::K::U8];
+        let l = [P::L::U8];
+        let seeds = h::<U128>(&[xi, &k, &l]);
+        let (rho, rest) = seeds.split_at(32);
+        let (rho_prime, key) = rest.split_at(64);
+
+        let (s1, s2) = expand_s::<P::K, P::L>(rho_prime, P::ETA);
+        let (t1, t0) = power2round_t::<P>(rho, &s1, &s2);
+
+        #[allow(deprecated)] // clone_from_slice
+        let verifying_key = VerifyingKey::new(Array::clone_from_slice(rho), t1);
+        #[allow(deprecated)] // clone_from_slice
+        SigningKey {
+            seed: Some(*xi),
+            key: Array::clone_from_slice(key),
+            s1,
+            s2,
+            t0,
+            verifying_key,
+        }
+    }
+
+    #[doc(hidden)]
+    /// Sign a message with a pre-chosen randomizer.
+    /// Implements `ML-DSA.Sign_internal` as defined in FIPS-204.
+    /// Published for KAT validation purposes but not intended for general use.
+    /// `opt_rand` must be a 32 byte slice, panics otherwise.
+    /// If `opt_rand` is `None`, the deterministic variant is used.
+    pub fn ml_dsa_sign_internal(&self, msg: &[&[u8]], opt_rand: Option<&[u8]>) -> Signature<P> {
+        let rnd: Array<u8, U32> =
+            opt_rand.map_or_else(Array::default, |rand| rand.try_into().unwrap());
+        let mu = message_representative(&self.verifying_key.tr, msg);
+        self.sign_mu_internal(&mu, &rnd)
+    }
+
+    /// Deterministically sign an externally computed message representative `μ`.
+    ///
+    /// `μ` can be computed from the verifying key and the message with a [`MuBuilder`](crate::MuBuilder).
+    pub fn sign_mu(&self, mu: &Mu) -> Signature<P> {
+        self.sign_mu_internal(mu, &Array::default())
+    }
+
+    /// Sign an externally computed message representative `μ` using hedged randomness.
+    ///
+    /// `μ` can be computed from the verifying key and the message with a [`MuBuilder`](crate::MuBuilder).
+    pub fn sign_mu_with_rng(
+        &self,
+        rng: &mut impl rand_core::CryptoRngCore,
+        mu: &Mu,
+    ) -> Signature<P> {
+        let mut rnd = Array::<u8, U32>::default();
+        rng.fill_bytes(&mut rnd);
+        self.sign_mu_internal(mu, &rnd)
+    }
+
+    /// Steps 7 onwards of `ML-DSA.Sign_internal` (algorithm 7) as defined in FIPS-204.
+    fn sign_mu_internal(&self, mu: &Mu, rnd: &Array<u8, U32>) -> Signature<P> {
+        let rho_prime = h::<U64>(&[&self.key, rnd, mu]);
+        sign_with_rho_prime(
+            &self.verifying_key.rho,
+            &self.s1,
+            &self.s2,
+            &self.t0,
+            &rho_prime,
+            mu,
+        )
+    }
+
+    /// Implements `ML-DSA.Sign` as defined in FIPS-204, using a context string.
+    /// Context strings must be 255 bytes or less.
+    /// If `opt_rand` is `None`, the deterministic variant is used.
+    /// # Errors
+    /// Returns an error if the context string is too long.
+    pub fn try_sign_with_context(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+    ) -> Result<Signature<P>, Error> {
+        let ctx_len = u8::try_from(ctx.len()).map_err(|_| Error::new())?;
+        let ctx_len_bytes = ctx_len.to_be_bytes();
+
+        let ctx_msg = [&[0], &ctx_len_bytes, ctx, msg];
+        Ok(self.ml_dsa_sign_internal(&ctx_msg, opt_rand))
+    }
+
+    /// Implements `HashML-DSA.Sign` as defined in FIPS-204, using a context string.
+    /// `ph_msg` must be the digest of the message computed with the pre-hash function `H`.
+    /// Context strings must be 255 bytes or less.
+    /// If `opt_rand` is `None`, the deterministic variant is used.
+    /// # Errors
+    /// Returns an error if the context string is too long or the digest has the wrong length.
+    pub fn try_sign_prehash_with_context<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+    ) -> Result<Signature<P>, Error> {
+        let header = PrehashHeader::new::<H>(ctx).ok_or(Error::new())?;
+        if ph_msg.len() != H::OutputSize::USIZE {
+            return Err(Error::new());
+        }
+
+        let ctx_msg = [header.ctx_len(), ctx, header.oid(), ph_msg];
+        Ok(self.ml_dsa_sign_internal(&ctx_msg, opt_rand))
+    }
+
+    /// The 32-byte seed `ξ` this key was derived from.
+    ///
+    /// Returns `None` if the key was imported from its expanded encoding, as the seed cannot be
+    /// recovered from it.
+    pub fn to_seed(&self) -> Option<Array<u8, U32>> {
+        self.seed
+    }
+
+    /// Serialize the signing key to a new stack-allocated array
+    ///
+    /// Implements `skEncode` (algorithm 24) as defined in FIPS-204.
+    pub fn to_bytes(&self) -> Array<u8, P::SkLen> {
+        let mut bytes = Array::<u8, P::SkLen>::default();
+        let (head, rest) = bytes.split_at_mut(128);
+        head[..32].copy_from_slice(&self.verifying_key.rho);
+        head[32..64].copy_from_slice(&self.key);
+        head[64..].copy_from_slice(&self.verifying_key.tr);
+
+        encode_secrets::<P>(&self.s1, &self.s2, &self.t0, rest);
+        bytes
+    }
+
+    /// Serialize the signing key to a new heap-allocated vector
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+/// Encode `s1`, `s2` and `t0` as in `skEncode` (algorithm 24), into the bytes following `tr`
+pub(crate) fn encode_secrets<P: MlDsaParams>(
+    s1: &Vector<P::L>,
+    s2: &Vector<P::K>,
+    t0: &Vector<P::K>,
+    out: &mut [u8],
+) {
+    let (s, t0_bytes) = out.split_at_mut((P::L::USIZE + P::K::USIZE) * P::ETA_POLY_LEN);
+    let s_polys = s1.iter().chain(s2.iter());
+    for (poly, out) in s_polys.zip(s.chunks_exact_mut(P::ETA_POLY_LEN)) {
+        bit_pack(poly, P::ETA, P::ETA, out);
+    }
+    for (poly, out) in t0.iter().zip(t0_bytes.chunks_exact_mut(T0_POLY_LEN)) {
+        bit_pack(poly, (1 << (D - 1)) - 1, 1 << (D - 1), out);
+    }
+}
+
+/// Decode `s1`, `s2` and `t0` as in `skDecode` (algorithm 25), from the bytes following `tr`.
+///
+/// The coefficients of `s1` and `s2` must lie in `[-η, η]`, and `t0` must match the value
+/// recomputed from them and `ρ`. Returns `(s1, s2, t0, t1)`.
+#[allow(clippy::type_complexity)] // A tuple of vectors
+pub(crate) fn decode_secrets<P: MlDsaParams>(
+    rho: &[u8],
+    bytes: &[u8],
+) -> Result<(Vector<P::L>, Vector<P::K>, Vector<P::K>, Vector<P::K>), SigningKeyError> {
+    let (s, t0) = bytes.split_at((P::L::USIZE + P::K::USIZE) * P::ETA_POLY_LEN);
+    let mut s_polys = s
+        .chunks_exact(P::ETA_POLY_LEN)
+        .map(|chunk| bit_unpack(chunk, P::ETA, P::ETA));
+    let s1 = Vector::<P::L>::from_fn(|_| s_polys.next().expect("length checked by caller"));
+    let s2 = Vector::<P::K>::from_fn(|_| s_polys.next().expect("length checked by caller"));
+    if vec_infinity_norm(&s1) > P::ETA {
+        return Err(SigningKeyError::S1OutOfRange);
+    }
+    if vec_infinity_norm(&s2) > P::ETA {
+        return Err(SigningKeyError::S2OutOfRange);
+    }
+    let t0 = Vector::<P::K>::from_fn(|i| {
+        bit_unpack(
+            &t0[i * T0_POLY_LEN..(i + 1) * T0_POLY_LEN],
+            (1 << (D - 1)) - 1,
+            1 << (D - 1),
+        )
+    });
+
+    let (t1, expected_t0) = power2round_t::<P>(rho, &s1, &s2);
+    if t0 != expected_t0 {
+        return Err(SigningKeyError::T0Mismatch);
+    }
+    Ok((s1, s2, t0, t1))
+}
+
+/// Steps 8 onwards of `ML-DSA.Sign_internal` (algorithm 7) as defined in FIPS-204, given the
+/// private seed `ρ''`
+pub(crate) fn sign_with_rho_prime<P: SignatureParams>(
+    rho: &[u8],
+    s1: &Vector<P::L>,
+    s2: &Vector<P::K>,
+    t0: &Vector<P::K>,
+    rho_prime: &[u8],
+    mu: &[u8],
+) -> Signature<P> {
+    if cfg!(feature = "low-memory") {
+        streaming::sign(rho, s1, s2, t0, rho_prime, mu)
+    } else {
+        sign_expanded(rho, s1, s2, t0, rho_prime, mu)
+    }
+}
+
+/// Implementation of [`sign_with_rho_prime`] keeping `Â` and the intermediate vectors in memory
+pub(crate) fn sign_expanded<P: SignatureParams>(
+    rho: &[u8],
+    s1: &Vector<P::L>,
+    s2: &Vector<P::K>,
+    t0: &Vector<P::K>,
+    rho_prime: &[u8],
+    mu: &[u8],
+) -> Signature<P> {
+    let s1_hat = ntt_vec(s1);
+    let s2_hat = ntt_vec(s2);
+    let t0_hat = ntt_vec(t0);
+    let a_hat = expand_a::<P::K, P::L>(rho);
+
+    let mut kappa: u16 = 0;
+    loop {
+        let y = expand_mask::<P::L>(rho_prime, kappa, P::GAMMA1);
+        #[allow(clippy::cast_possible_truncation)] // L < 256
+        {
+            kappa = kappa.wrapping_add(P::L::U16);
+        }
+
+        let w = ntt_inverse_vec(&matrix_vec_ntt_mul(&a_hat, &ntt_vec(&y)));
+        let w1 = high_bits_vec(&w, P::GAMMA2);
+
+        let c_tilde = commitment_hash::<P>(mu, &w1);
+        let c_hat = ntt(&sample_in_ball(&c_tilde, P::TAU));
+        let cs1 = ntt_inverse_vec(&scalar_vec_ntt_mul(&c_hat, &s1_hat));
+        let cs2 = ntt_inverse_vec(&scalar_vec_ntt_mul(&c_hat, &s2_hat));
+
+        let z = vec_add(&y, &cs1);
+        let w_cs2 = vec_sub(&w, &cs2);
+        let r0 = low_bits_vec(&w_cs2, P::GAMMA2);
+        if vec_infinity_norm(&z) >= P::GAMMA1 - P::BETA
+            || vec_infinity_norm(&r0) >= P::GAMMA2 - P::BETA
+        {
+            continue;
+        }
+
+        let ct0 = ntt_inverse_vec(&scalar_vec_ntt_mul(&c_hat, &t0_hat));
+        let minus_ct0 = Vector::<P::K>::from_fn(|i| ct0[i].neg());
+        let h = make_hint_vec(&minus_ct0, &vec_add(&w_cs2, &ct0), P::GAMMA2);
+        if vec_infinity_norm(&ct0) >= P::GAMMA2 || hint_weight(&h) > P::OMEGA {
+            continue;
+        }
+
+        return Signature::encode(&c_tilde, &z, &h);
+    }
+}
+
+/// Compute `(t1, t0) = Power2Round(NTT^-1(Â ∘ NTT(s1)) + s2)`, as in steps 5 and 6 of
+/// `ML-DSA.KeyGen_internal` (algorithm 6)
+pub(crate) fn power2round_t<P: MlDsaParams>(
+    rho: &[u8],
+    s1: &Vector<P::L>,
+    s2: &Vector<P::K>,
+) -> (Vector<P::K>, Vector<P::K>) {
+    if cfg!(feature = "low-memory") {
+        return streaming::power2round_t::<P>(rho, s1, s2);
+    }
+
+    let a_hat = expand_a::<P::K, P::L>(rho);
+    let t = vec_add(
+        &ntt_inverse_vec(&matrix_vec_ntt_mul(&a_hat, &ntt_vec(s1))),
+        s2,
+    );
+    power2round_vec(&t)
+}
+
+impl<P: ParameterSet> TryFrom<&[u8]> for SigningKey<P> {
+    type Error = SigningKeyError;
+
+    /// Decode a signing key from either its 32-byte seed or its expanded encoding.
+    ///
+    /// Expanded keys are decoded with `skDecode` (algorithm 25) as defined in FIPS-204, and
+    /// validated: the coefficients of `s1` and `s2` must lie in `[-η, η]`, and `t0` and the public
+    /// key hash `tr` must match the values recomputed from `s1` and `s2`.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if let Ok(seed) = bytes.try_into() {
+            return Ok(Self::from_seed(seed));
+        }
+        if bytes.len() != P::SkLen::USIZE {
+            return Err(SigningKeyError::InvalidLength);
+        }
+
+        let (head, rest) = bytes.split_at(128);
+        #[allow(deprecated)] // clone_from_slice
+        let rho = Array::clone_from_slice(&head[..32]);
+        let (s1, s2, t0, t1) = decode_secrets::<P>(&rho, rest)?;
+        let verifying_key = VerifyingKey::new(rho, t1);
+        if verifying_key.tr.as_slice() != &head[64..] {
+            return Err(SigningKeyError::TrMismatch);
+        }
+
+        #[allow(deprecated)] // clone_from_slice
+        Ok(SigningKey {
+            seed: None,
+            key: Array::clone_from_slice(&head[32..64]),
+            s1,
+            s2,
+            t0,
+            verifying_key,
+        })
+    }
+}
+
+impl<P: ParameterSet> Signer<Signature<P>> for SigningKey<P> {
+    /// Deterministic signing, with an empty context string
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature<P>, Error> {
+        self.try_sign_with_context(msg, &[], None)
+    }
+
+    /// Deterministic signing, with an empty context string. Signing cannot fail in this case,
+    /// so the signature is returned directly rather than through a copy unwrapped from
+    /// `try_sign`.
+    fn sign(&self, msg: &[u8]) -> Signature<P> {
+        self.ml_dsa_sign_internal(&[&[0], &[0], msg], None)
+    }
+}
+
+impl<P: ParameterSet> RandomizedSigner<Signature<P>> for SigningKey<P> {
+    /// Hedged signing, with an empty context string
+    fn try_sign_with_rng(
+        &self,
+        rng: &mut impl signature::rand_core::CryptoRngCore,
+        msg: &[u8],
+    ) -> Result<Signature<P>, Error> {
+        let mut rnd = Array::<u8, U32>::default();
+        rng.try_fill_bytes(&mut rnd)?;
+        self.try_sign_with_context(msg, &[], Some(&rnd))
+    }
+}
+
+/// Deterministic HashML-DSA signing, with an empty context string
+impl<P, D> DigestSigner<D, Signature<P>> for SigningKey<P>
+where
+    P: ParameterSet,
+    D: Digest + PreHash,
+{
+    fn try_sign_digest(&self, digest: D) -> Result<Signature<P>, Error> {
+        self.try_sign_prehash_with_context::<D>(&digest.finalize(), &[], None)
+    }
+}
+
+/// Hedged HashML-DSA signing, with an empty context string
+impl<P, D> RandomizedDigestSigner<D, Signature<P>> for SigningKey<P>
+where
+    P: ParameterSet,
+    D: Digest + PreHash,
+{
+    fn try_sign_digest_with_rng(
+        &self,
+        rng: &mut impl signature::rand_core::CryptoRngCore,
+        digest: D,
+    ) -> Result<Signature<P>, Error> {
+        let mut rnd = Array::<u8, U32>::default();
+        rng.try_fill_bytes(&mut rnd)?;
+        self.try_sign_prehash_with_context::<D>(&digest.finalize(), &[], Some(&rnd))
+    }
+}
+
+impl<P: ParameterSet> AsRef<VerifyingKey<P>> for SigningKey<P> {
+    fn as_ref(&self) -> &VerifyingKey<P> {
+        &self.verifying_key
+    }
+}
+
+impl<P: ParameterSet> KeypairRef for SigningKey<P> {
+    type VerifyingKey = VerifyingKey<P>;
+}
+
+impl SigningKeyLen for MlDsa44 {
+    type SkLen = U2560;
+}
+
+impl SigningKeyLen for MlDsa65 {
+    type SkLen = U4032;
+}
+
+impl SigningKeyLen for MlDsa87 {
+    type SkLen = U4896;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::SigningKeyError, util::macros::test_parameter_sets, ParameterSet, SigningKey,
+    };
+    use typenum::Unsigned;
+
+    fn test_serialize_deserialize<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let bytes = sk.to_bytes();
+        let sk2 = SigningKey::<P>::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(sk, sk2);
+    }
+    test_parameter_sets!(test_serialize_deserialize);
+
+    #[cfg(feature = "alloc")]
+    fn test_serialize_deserialize_vec<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vec = sk.to_vec();
+        let sk2 = SigningKey::<P>::try_from(vec.as_slice()).unwrap();
+        assert_eq!(sk, sk2);
+    }
+    #[cfg(feature = "alloc")]
+    test_parameter_sets!(test_serialize_deserialize_vec);
+
+    fn test_seed_roundtrip<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let seed = sk.to_seed().unwrap();
+        let sk2 = SigningKey::<P>::from_seed(&seed);
+        assert_eq!(sk, sk2);
+        let sk3 = SigningKey::<P>::try_from(seed.as_slice()).unwrap();
+        assert_eq!(sk, sk3);
+        assert_eq!(sk3.to_seed(), Some(seed));
+
+        // The seed cannot be recovered from the expanded encoding
+        let sk4 = SigningKey::<P>::try_from(sk.to_bytes().as_slice()).unwrap();
+        assert_eq!(sk, sk4);
+        assert_eq!(sk4.to_seed(), None);
+    }
+    test_parameter_sets!(test_seed_roundtrip);
+
+    #[test]
+    fn test_deserialize_fail_on_inconsistent_tr() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+
+        // Modify the public key hash
+        let mut bytes = sk.to_bytes();
+        bytes[100] ^= 1;
+        assert_eq!(
+            SigningKey::<MlDsa44>::try_from(bytes.as_slice()),
+            Err(SigningKeyError::TrMismatch)
+        );
+    }
+
+    fn test_deserialize_fail_on_invalid_secrets<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let s1_offset = 128;
+        let s2_offset = s1_offset + P::L::USIZE * P::ETA_POLY_LEN;
+        let t0_offset = s2_offset + P::K::USIZE * P::ETA_POLY_LEN;
+
+        // An all-ones encoding decodes to `η - (2^bitlen(2η) - 1) < -η`
+        let mut bytes = sk.to_bytes();
+        bytes[s1_offset] = 0xff;
+        assert_eq!(
+            SigningKey::<P>::try_from(bytes.as_slice()),
+            Err(SigningKeyError::S1OutOfRange)
+        );
+
+        let mut bytes = sk.to_bytes();
+        bytes[s2_offset + 1] = 0xff;
+        assert_eq!(
+            SigningKey::<P>::try_from(bytes.as_slice()),
+            Err(SigningKeyError::S2OutOfRange)
+        );
+
+        let mut bytes = sk.to_bytes();
+        bytes[t0_offset] ^= 1;
+        assert_eq!(
+            SigningKey::<P>::try_from(bytes.as_slice()),
+            Err(SigningKeyError::T0Mismatch)
+        );
+
+        // Modifying the public seed rho changes the recomputed t
+        let mut bytes = sk.to_bytes();
+        bytes[0] ^= 1;
+        assert!(SigningKey::<P>::try_from(bytes.as_slice()).is_err());
+    }
+    test_parameter_sets!(test_deserialize_fail_on_invalid_secrets);
+
+    fn test_pairwise_consistency<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::try_new(&mut rng).unwrap();
+        assert_eq!(sk.pairwise_consistency_test(), Ok(()));
+
+        // A key whose public part does not match the secret part fails the test
+        let mut bad_sk = sk.clone();
+        bad_sk.verifying_key = SigningKey::<P>::new(&mut rng).verifying_key;
+        assert_eq!(
+            bad_sk.pairwise_consistency_test(),
+            Err(SigningKeyError::PairwiseConsistency)
+        );
+    }
+    test_parameter_sets!(test_pairwise_consistency);
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let expected_vk = format!("{:?}", sk.verifying_key);
+        assert_eq!(
+            format!("{sk:?}"),
+            format!("SigningKey {{ verifying_key: {expected_vk}, .. }}")
+        );
+    }
+
+    #[test]
+    fn test_deserialize_fail_on_incorrect_length() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let bytes = sk.to_bytes();
+        let incorrect_bytes = &bytes[..bytes.len() - 1];
+        assert_eq!(
+            SigningKey::<MlDsa44>::try_from(incorrect_bytes),
+            Err(SigningKeyError::InvalidLength)
+        );
+    }
+}
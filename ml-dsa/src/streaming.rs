@@ -0,0 +1,353 @@
+//! Low-memory key generation, signing and verification
+//!
+//! These functions compute the same results as the default implementations, but never hold the
+//! matrix `Â` or a whole intermediate vector in memory. Entries of `Â` and of the masking vector
+//! `y` are regenerated on demand, and products with `Â` are computed one row at a time. This
+//! keeps the working set to a handful of polynomials (1 KiB each) on top of the output, at the
+//! cost of sampling `Â` twice per signing attempt and repeating the NTT of `y`, `z` and `s1`
+//! for every row. Each part of a signature is written to its encoding as soon as it is computed.
+//!
+//! They are used in place of the default implementations when the `low-memory` feature is
+//! enabled.
+use crate::algebra::{add, from_signed, mul, sub, Poly, Vector, N};
+use crate::encode::bit_pack;
+use crate::hashes::CommitmentHasher;
+use crate::ntt::{ntt, ntt_inverse};
+use crate::params::MlDsaParams;
+use crate::rounding::{high_bits, low_bits, make_hint, power2round, use_hint};
+use crate::sampling::{expand_a_entry, expand_mask_entry, sample_in_ball};
+use crate::signature_encoding::Signature;
+use crate::SignatureParams;
+use ::signature::Error;
+use hybrid_array::Array;
+use typenum::Unsigned;
+
+/// Compute the row `i` of `Â ∘ NTT(v)`, with the entries of `v` produced by `v_j`
+fn matrix_row_ntt_mul<P: MlDsaParams>(
+    rho: &[u8],
+    i: usize,
+    mut v_j: impl FnMut(usize) -> Poly,
+) -> Poly {
+    let mut acc = Poly::default();
+    for j in 0..P::L::USIZE {
+        let v_hat = ntt(&v_j(j));
+        let a = expand_a_entry(rho, i, j);
+        for n in 0..N {
+            acc.0[n] = add(acc.0[n], mul(a.0[n], v_hat.0[n]));
+        }
+    }
+    acc
+}
+
+/// Compute `NTT^-1(ĉ ∘ NTT(p))`
+fn ntt_scalar_mul(c_hat: &Poly, p: &Poly) -> Poly {
+    let mut p_hat = ntt(p);
+    for n in 0..N {
+        p_hat.0[n] = mul(c_hat.0[n], p_hat.0[n]);
+    }
+    ntt_inverse(&p_hat)
+}
+
+/// Compute `(t1, t0) = Power2Round(NTT^-1(Â ∘ NTT(s1)) + s2)`, as in steps 5 and 6 of
+/// `ML-DSA.KeyGen_internal` (algorithm 6)
+pub(crate) fn power2round_t<P: MlDsaParams>(
+    rho: &[u8],
+    s1: &Vector<P::L>,
+    s2: &Vector<P::K>,
+) -> (Vector<P::K>, Vector<P::K>) {
+    let mut t1 = Vector::<P::K>::default();
+    let mut t0 = Vector::<P::K>::default();
+    for i in 0..P::K::USIZE {
+        let t = ntt_inverse(&matrix_row_ntt_mul::<P>(rho, i, |j| s1[j])).add(&s2[i]);
+        for (n, &x) in t.0.iter().enumerate() {
+            let (hi, lo) = power2round(x);
+            t1[i].0[n] = hi;
+            t0[i].0[n] = from_signed(lo);
+        }
+    }
+    (t1, t0)
+}
+
+/// Compute `NTT(c)`, with `c = SampleInBall(c̃)`. It is kept out of line so that `c` does not
+/// stay on the stack of its caller.
+#[inline(never)]
+fn challenge_ntt<P: MlDsaParams>(c_tilde: &[u8]) -> Poly {
+    ntt(&sample_in_ball(c_tilde, P::TAU))
+}
+
+/// Compute the commitment hash of `μ` and the rows of `w1`, with row `i` produced by `w1_i`.
+/// It is kept out of line so that the hash state does not stay on the stack of its caller.
+#[inline(never)]
+fn commitment<P: MlDsaParams>(
+    mu: &[u8],
+    mut w1_i: impl FnMut(usize) -> Poly,
+) -> Array<u8, P::CTildeLen> {
+    let mut hasher = CommitmentHasher::<P>::new(mu);
+    for i in 0..P::K::USIZE {
+        hasher.update(&w1_i(i));
+    }
+    hasher.finalize()
+}
+
+/// Steps 8 onwards of `ML-DSA.Sign_internal` (algorithm 7) as defined in FIPS-204, given the
+/// private seed `ρ''`
+pub(crate) fn sign<P: SignatureParams>(
+    rho: &[u8],
+    s1: &Vector<P::L>,
+    s2: &Vector<P::K>,
+    t0: &Vector<P::K>,
+    rho_prime: &[u8],
+    mu: &[u8],
+) -> Signature<P> {
+    let mut signature = Signature {
+        bytes: Array::default(),
+    };
+    sign_into::<P>(
+        &SecretVectors { rho, s1, s2, t0 },
+        rho_prime,
+        mu,
+        &mut signature.bytes,
+    );
+    signature
+}
+
+/// The public seed and secret vectors of a signing key
+struct SecretVectors<'a, P: MlDsaParams> {
+    rho: &'a [u8],
+    s1: &'a Vector<P::L>,
+    s2: &'a Vector<P::K>,
+    t0: &'a Vector<P::K>,
+}
+
+/// Implementation of [`sign`], writing each part of the signature to its encoding in `out` as
+/// soon as it is computed (`sigEncode`, algorithm 26). It is kept out of line so that the
+/// temporaries of each attempt do not share a stack frame with the signature.
+#[inline(never)]
+fn sign_into<P: SignatureParams>(
+    sk: &SecretVectors<'_, P>,
+    rho_prime: &[u8],
+    mu: &[u8],
+    out: &mut Array<u8, P::SigLen>,
+) {
+    let (c_tilde_out, rest) = out.split_at_mut(P::CTildeLen::USIZE);
+    let (z_out, h_out) = rest.split_at_mut(P::L::USIZE * P::Z_POLY_LEN);
+
+    let mut kappa: u16 = 0;
+    loop {
+        let y_kappa = kappa;
+        #[allow(clippy::cast_possible_truncation)] // L < 256
+        {
+            kappa = kappa.wrapping_add(P::L::U16);
+        }
+        let y_j = |j| expand_mask_entry(rho_prime, y_kappa, j, P::GAMMA1);
+        let w_i = |i| ntt_inverse(&matrix_row_ntt_mul::<P>(sk.rho, i, y_j));
+
+        let c_tilde = commitment::<P>(mu, |i| {
+            let mut w1 = w_i(i);
+            for x in &mut w1.0 {
+                *x = high_bits(*x, P::GAMMA2);
+            }
+            w1
+        });
+        c_tilde_out.copy_from_slice(&c_tilde);
+        let c_hat = challenge_ntt::<P>(&c_tilde);
+
+        // The rejection conditions are independent of each other, so checking them in a
+        // different order from FIPS-204 yields the same signature
+        if encode_z(sk, &c_hat, y_j, z_out) && encode_hints(sk, &c_hat, w_i, h_out) {
+            return;
+        }
+    }
+}
+
+/// Compute `z = y + NTT^-1(ĉ ∘ NTT(s1))` one entry at a time, with the entries of `y` produced by
+/// `y_j`, and write its encoding to `out`. Returns `false` if `z` is rejected.
+#[inline(never)]
+fn encode_z<P: SignatureParams>(
+    sk: &SecretVectors<'_, P>,
+    c_hat: &Poly,
+    y_j: impl Fn(usize) -> Poly,
+    out: &mut [u8],
+) -> bool {
+    for (j, out) in out.chunks_exact_mut(P::Z_POLY_LEN).enumerate() {
+        let mut z = ntt_scalar_mul(c_hat, &sk.s1[j]);
+        let y = y_j(j);
+        for n in 0..N {
+            z.0[n] = add(z.0[n], y.0[n]);
+        }
+        if z.infinity_norm() >= P::GAMMA1 - P::BETA {
+            return false;
+        }
+        bit_pack(&z, P::GAMMA1 - 1, P::GAMMA1, out);
+    }
+    true
+}
+
+/// Compute the hint `h` one row at a time, with the rows of `w` produced by `w_i`, and write its
+/// encoding to `out` as `HintBitPack` (algorithm 20) does. Returns `false` if the low bits of
+/// `w - cs2`, `ct0` or the number of hints is out of range.
+#[inline(never)]
+#[allow(clippy::cast_possible_truncation)] // Hint indices are less than 256 and counts at most ω
+fn encode_hints<P: SignatureParams>(
+    sk: &SecretVectors<'_, P>,
+    c_hat: &Poly,
+    w_i: impl Fn(usize) -> Poly,
+    out: &mut [u8],
+) -> bool {
+    let (indices, ends) = out.split_at_mut(P::OMEGA);
+    let mut index = 0;
+    for (i, end) in ends.iter_mut().enumerate() {
+        // w - cs2
+        let mut w_cs2 = w_i(i);
+        let cs2 = ntt_scalar_mul(c_hat, &sk.s2[i]);
+        for n in 0..N {
+            w_cs2.0[n] = sub(w_cs2.0[n], cs2.0[n]);
+        }
+        if w_cs2
+            .0
+            .iter()
+            .any(|&x| low_bits(x, P::GAMMA2).unsigned_abs() >= P::GAMMA2 - P::BETA)
+        {
+            return false;
+        }
+
+        let ct0 = ntt_scalar_mul(c_hat, &sk.t0[i]);
+        if ct0.infinity_norm() >= P::GAMMA2 {
+            return false;
+        }
+
+        for n in 0..N {
+            if make_hint(sub(0, ct0.0[n]), add(w_cs2.0[n], ct0.0[n]), P::GAMMA2) {
+                if index == P::OMEGA {
+                    return false;
+                }
+                indices[index] = n as u8;
+                index += 1;
+            }
+        }
+        *end = index as u8;
+    }
+    indices[index..].fill(0);
+    true
+}
+
+/// Steps 4 onwards of `ML-DSA.Verify_internal` (algorithm 8) as defined in FIPS-204
+pub(crate) fn verify<P: SignatureParams>(
+    rho: &[u8],
+    t1: &Vector<P::K>,
+    mu: &[u8],
+    signature: &Signature<P>,
+) -> Result<(), Error> {
+    if (0..P::L::USIZE).any(|j| signature.z(j).infinity_norm() >= P::GAMMA1 - P::BETA) {
+        return Err(Error::new());
+    }
+
+    let c_hat = challenge_ntt::<P>(signature.c_tilde());
+
+    // w'_approx = NTT^-1(Â ∘ NTT(z) - NTT(c) ∘ NTT(t1 * 2^d)), one row at a time
+    let c_tilde = commitment::<P>(mu, |i| {
+        let mut w1 = matrix_row_ntt_mul::<P>(rho, i, |j| signature.z(j));
+        let t1_hat = ntt(&t1[i].shift_left_d());
+        for n in 0..N {
+            w1.0[n] = sub(w1.0[n], mul(c_hat.0[n], t1_hat.0[n]));
+        }
+        w1 = ntt_inverse(&w1);
+        let h = signature.hint(i);
+        for (n, x) in w1.0.iter_mut().enumerate() {
+            *x = use_hint(h.get(n), *x, P::GAMMA2);
+        }
+        w1
+    });
+
+    (c_tilde.as_slice() == signature.c_tilde())
+        .then_some(())
+        .ok_or(Error::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::vec_add;
+    use crate::hashes::{h, Mu};
+    use crate::ntt::{matrix_vec_ntt_mul, ntt_inverse_vec, ntt_vec};
+    use crate::rounding::power2round_vec;
+    use crate::sampling::expand_a;
+    use crate::signing_key::sign_expanded;
+    use crate::util::macros::test_parameter_sets;
+    use crate::{ParameterSet, SigningKey};
+    use rand::{RngCore, SeedableRng};
+    use typenum::{U32, U64};
+
+    fn sign_mu<P: ParameterSet>(sk: &SigningKey<P>, mu: &Mu, rnd: &[u8]) -> Signature<P> {
+        let rho_prime = h::<U64>(&[&sk.key, rnd, mu]);
+        sign(
+            &sk.verifying_key.rho,
+            &sk.s1,
+            &sk.s2,
+            &sk.t0,
+            &rho_prime,
+            mu,
+        )
+    }
+
+    fn verify_mu<P: ParameterSet>(sk: &SigningKey<P>, mu: &Mu, sig: &Signature<P>) -> bool {
+        let vk = &sk.verifying_key;
+        verify(&vk.rho, &vk.t1, mu, sig).is_ok()
+    }
+
+    fn test_power2round_t_matches<P: ParameterSet>() {
+        let sk = SigningKey::<P>::from_seed(&Array([7; 32]));
+        let rho = &sk.verifying_key.rho;
+        let a_hat = expand_a::<P::K, P::L>(rho);
+        let t = vec_add(
+            &ntt_inverse_vec(&matrix_vec_ntt_mul(&a_hat, &ntt_vec(&sk.s1))),
+            &sk.s2,
+        );
+        assert_eq!(power2round_t::<P>(rho, &sk.s1, &sk.s2), power2round_vec(&t));
+    }
+    test_parameter_sets!(test_power2round_t_matches);
+
+    fn test_sign_mu_matches<P: ParameterSet>() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(204);
+        let sk = SigningKey::<P>::new(&mut rng);
+        for _ in 0..16 {
+            let mut mu = Mu::default();
+            let mut rnd = Array::<u8, U32>::default();
+            rng.fill_bytes(&mut mu);
+            rng.fill_bytes(&mut rnd);
+            let sig = sign_mu(&sk, &mu, &rnd);
+            let rho_prime = h::<U64>(&[&sk.key, &rnd, &mu]);
+            let vk = &sk.verifying_key;
+            assert_eq!(
+                sig,
+                sign_expanded(&vk.rho, &sk.s1, &sk.s2, &sk.t0, &rho_prime, &mu)
+            );
+            assert!(verify_mu(&sk, &mu, &sig));
+
+            mu[0] ^= 1;
+            assert!(!verify_mu(&sk, &mu, &sig));
+        }
+    }
+    test_parameter_sets!(test_sign_mu_matches);
+
+    /// Sign and verify with ML-DSA-87 through the public API on a thread with a 32 KiB stack, the
+    /// bound documented for the `low-memory` feature. The keys are kept on the heap, as they would
+    /// be in static memory on an embedded target.
+    #[cfg(feature = "low-memory")]
+    #[test]
+    fn test_bounded_stack() {
+        use ::signature::{Keypair, Signer, Verifier};
+
+        let sk = Box::new(SigningKey::<crate::MlDsa87>::from_seed(&Array([1; 32])));
+        let vk = Box::new(sk.verifying_key().clone());
+        std::thread::Builder::new()
+            .stack_size(32 * 1024)
+            .spawn(move || {
+                let sig = sk.sign(b"message");
+                assert!(vk.verify(b"message", &sig).is_ok());
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+#[cfg(test)]
+pub mod macros {
+    /// Generate a test case running a generic test function for a single parameter set
+    #[macro_export]
+    macro_rules! gen_test {
+        ($name:ident, $t:ty) => {
+            paste::paste! {
+               #[test]
+               fn [<$name _ $t:snake>]() {
+                   $name::<$t>()
+               }
+            }
+        };
+    }
+
+    macro_rules! test_parameter_sets {
+        ($name:ident) => {
+            #[allow(unused_imports)]
+            use crate::params::*;
+            crate::gen_test!($name, MlDsa44);
+            crate::gen_test!($name, MlDsa65);
+            crate::gen_test!($name, MlDsa87);
+        };
+    }
+
+    pub(crate) use test_parameter_sets;
+}
@@ -0,0 +1,441 @@
+use crate::algebra::{vec_infinity_norm, vec_sub, Matrix, Vector, Q};
+use crate::encode::{simple_bit_pack, simple_bit_unpack};
+use crate::hashes::{commitment_hash, h, message_representative, Mu};
+use crate::ntt::{matrix_vec_ntt_mul, ntt, ntt_inverse_vec, ntt_vec, scalar_vec_ntt_mul};
+use crate::params::MlDsaParams;
+use crate::params::{MlDsa44, MlDsa65, MlDsa87, T1_POLY_LEN};
+use crate::prehash::{PreHash, PrehashHeader};
+use crate::rounding::{use_hint_vec, Hint};
+use crate::sampling::{expand_a, sample_in_ball};
+use crate::signature_encoding::Signature;
+use crate::streaming;
+use crate::{ParameterSet, SignatureParams};
+use ::signature::digest::Digest;
+use ::signature::{DigestVerifier, Error, Verifier};
+use hybrid_array::sizes::{U1312, U1952, U2592};
+use hybrid_array::{Array, ArraySize};
+use typenum::{Unsigned, U32, U64};
+
+/// A trait specifying the length of a serialized verifying key for a given parameter set
+pub trait VerifyingKeyLen {
+    /// The length of the serialized verifying key in bytes
+    type VkLen: ArraySize;
+}
+
+/// A `VerifyingKey` is an ML-DSA public key, allowing
+/// verification of signatures created with the corresponding
+/// `SigningKey`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<P: ParameterSet> {
+    pub(crate) rho: Array<u8, U32>,
+    pub(crate) t1: Vector<P::K>,
+    // Cached `H(pk, 64)`, used to compute the message representative
+    pub(crate) tr: Array<u8, U64>,
+}
+
+impl<P: ParameterSet> VerifyingKey<P> {
+    pub(crate) fn new(rho: Array<u8, U32>, t1: Vector<P::K>) -> Self {
+        let mut vk = VerifyingKey {
+            rho,
+            t1,
+            tr: Array::default(),
+        };
+        vk.tr = h(&[&vk.to_bytes()]);
+        vk
+    }
+
+    #[doc(hidden)]
+    /// Verify a raw message (without context).
+    /// Implements `ML-DSA.Verify_internal` as defined in FIPS-204.
+    /// Published for KAT validation purposes but not intended for general use.
+    pub fn ml_dsa_verify_internal(
+        &self,
+        msg: &[&[u8]],
+        signature: &Signature<P>,
+    ) -> Result<(), Error> {
+        self.verify_mu(&message_representative(&self.tr, msg), signature)
+    }
+
+    /// Verify a signature over an externally computed message representative `μ`.
+    ///
+    /// `μ` can be computed from the verifying key and the message with a [`MuBuilder`](crate::MuBuilder).
+    /// # Errors
+    /// Returns an error if the signature is invalid
+    pub fn verify_mu(&self, mu: &Mu, signature: &Signature<P>) -> Result<(), Error> {
+        verify(&self.rho, &self.t1, mu, signature)
+    }
+
+    /// Implements `ML-DSA.Verify` as defined in FIPS-204, using a context string.
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context is too long or if the signature is invalid
+    pub fn try_verify_with_context(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), Error> {
+        self.verify_mu(&pure_mu(&self.tr, msg, ctx)?, signature)
+    }
+
+    /// Implements `HashML-DSA.Verify` as defined in FIPS-204, using a context string.
+    /// `ph_msg` must be the digest of the message computed with the pre-hash function `H`.
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context is too long, the digest has the wrong length or if the
+    /// signature is invalid
+    pub fn try_verify_prehash_with_context<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), Error> {
+        self.verify_mu(&prehash_mu::<H>(&self.tr, ph_msg, ctx)?, signature)
+    }
+
+    /// Precompute the expanded form of this key for repeated verification.
+    ///
+    /// See [`PreparedVerifyingKey`].
+    pub fn prepare(&self) -> PreparedVerifyingKey<P> {
+        PreparedVerifyingKey::from(self)
+    }
+
+    /// Serialize the verifying key to a new stack-allocated array
+    ///
+    /// Implements `pkEncode` (algorithm 22) as defined in FIPS-204.
+    pub fn to_bytes(&self) -> Array<u8, P::VkLen> {
+        pk_encode::<P>(&self.rho, &self.t1)
+    }
+
+    /// Serialize the verifying key to a new heap-allocated vector
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl<P: ParameterSet> From<&VerifyingKey<P>> for Array<u8, P::VkLen> {
+    fn from(vk: &VerifyingKey<P>) -> Array<u8, P::VkLen> {
+        vk.to_bytes()
+    }
+}
+
+impl<P: ParameterSet> TryFrom<&[u8]> for VerifyingKey<P> {
+    type Error = Error;
+
+    /// Implements `pkDecode` (algorithm 23) as defined in FIPS-204.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let (rho, t1) = pk_decode::<P>(bytes)?;
+        Ok(VerifyingKey::new(rho, t1))
+    }
+}
+
+impl<P: ParameterSet> Verifier<Signature<P>> for VerifyingKey<P> {
+    fn verify(&self, msg: &[u8], signature: &Signature<P>) -> Result<(), Error> {
+        self.try_verify_with_context(msg, &[], signature)
+    }
+}
+
+/// HashML-DSA verification, with an empty context string
+impl<P, D> DigestVerifier<D, Signature<P>> for VerifyingKey<P>
+where
+    P: ParameterSet,
+    D: Digest + PreHash,
+{
+    fn verify_digest(&self, digest: D, signature: &Signature<P>) -> Result<(), Error> {
+        self.try_verify_prehash_with_context::<D>(&digest.finalize(), &[], signature)
+    }
+}
+
+/// Compute `μ` for `ML-DSA.Verify`, with `M' = 0 || |ctx| || ctx || M`
+fn pure_mu(tr: &Array<u8, U64>, msg: &[u8], ctx: &[u8]) -> Result<Mu, Error> {
+    let ctx_len = u8::try_from(ctx.len()).map_err(|_| Error::new())?;
+    let ctx_len_bytes = ctx_len.to_be_bytes();
+
+    Ok(message_representative(
+        tr,
+        &[&[0], &ctx_len_bytes, ctx, msg],
+    ))
+}
+
+/// Compute `μ` for `HashML-DSA.Verify`, with `M' = 1 || |ctx| || ctx || OID(H) || PH(M)`
+fn prehash_mu<H: PreHash>(tr: &Array<u8, U64>, ph_msg: &[u8], ctx: &[u8]) -> Result<Mu, Error> {
+    let header = PrehashHeader::new::<H>(ctx).ok_or(Error::new())?;
+    if ph_msg.len() != H::OutputSize::USIZE {
+        return Err(Error::new());
+    }
+
+    Ok(message_representative(
+        tr,
+        &[header.ctx_len(), ctx, header.oid(), ph_msg],
+    ))
+}
+
+/// Implements `pkEncode` (algorithm 22) as defined in FIPS-204
+pub(crate) fn pk_encode<P: MlDsaParams + VerifyingKeyLen>(
+    rho: &[u8],
+    t1: &Vector<P::K>,
+) -> Array<u8, P::VkLen> {
+    let mut bytes = Array::<u8, P::VkLen>::default();
+    let (rho_bytes, t1_bytes) = bytes.split_at_mut(32);
+    rho_bytes.copy_from_slice(rho);
+    for (poly, out) in t1.iter().zip(t1_bytes.chunks_exact_mut(T1_POLY_LEN)) {
+        simple_bit_pack(poly, (Q - 1) >> crate::algebra::D, out);
+    }
+    bytes
+}
+
+/// Implements `pkDecode` (algorithm 23) as defined in FIPS-204, returning `(ρ, t1)`
+#[allow(clippy::type_complexity)] // A pair of arrays
+pub(crate) fn pk_decode<P: MlDsaParams + VerifyingKeyLen>(
+    bytes: &[u8],
+) -> Result<(Array<u8, U32>, Vector<P::K>), Error> {
+    if bytes.len() != P::VkLen::USIZE {
+        return Err(Error::new());
+    }
+    let (rho, t1) = bytes.split_at(32);
+    #[allow(deprecated)] // clone_from_slice
+    let rho = Array::clone_from_slice(rho);
+    let t1 = Vector::<P::K>::from_fn(|i| {
+        simple_bit_unpack(
+            &t1[i * T1_POLY_LEN..(i + 1) * T1_POLY_LEN],
+            (Q - 1) >> crate::algebra::D,
+        )
+    });
+    Ok((rho, t1))
+}
+
+/// `NTT(t1 * 2^d)`
+fn t1_hat<K: ArraySize>(t1: &Vector<K>) -> Vector<K> {
+    ntt_vec(&Vector::<K>::from_fn(|i| t1[i].shift_left_d()))
+}
+
+/// Verify a signature over `μ` given the public seed `ρ` and `t1`.
+///
+/// Implements steps 4 onwards of `ML-DSA.Verify_internal` (algorithm 8) as defined in FIPS-204.
+pub(crate) fn verify<P: SignatureParams>(
+    rho: &[u8],
+    t1: &Vector<P::K>,
+    mu: &[u8],
+    signature: &Signature<P>,
+) -> Result<(), Error> {
+    if cfg!(feature = "low-memory") {
+        return streaming::verify(rho, t1, mu, signature);
+    }
+
+    let a_hat = expand_a::<P::K, P::L>(rho);
+    verify_mu_prepared(&a_hat, &t1_hat(t1), mu, signature)
+}
+
+/// Verify a signature over `μ` given `Â` and `NTT(t1 * 2^d)`.
+///
+/// Implements the core of `ML-DSA.Verify_internal` (algorithm 8) as defined in FIPS-204.
+fn verify_mu_prepared<P: SignatureParams>(
+    a_hat: &Matrix<P::K, P::L>,
+    t1_hat: &Vector<P::K>,
+    mu: &[u8],
+    signature: &Signature<P>,
+) -> Result<(), Error> {
+    let z = Vector::<P::L>::from_fn(|j| signature.z(j));
+    if vec_infinity_norm(&z) >= P::GAMMA1 - P::BETA {
+        return Err(Error::new());
+    }
+
+    let c_hat = ntt(&sample_in_ball(signature.c_tilde(), P::TAU));
+
+    // w'_approx = NTT^-1(Â ∘ NTT(z) - NTT(c) ∘ NTT(t1 * 2^d))
+    let az = matrix_vec_ntt_mul(a_hat, &ntt_vec(&z));
+    let ct1 = scalar_vec_ntt_mul(&c_hat, t1_hat);
+    let w_approx = ntt_inverse_vec(&vec_sub(&az, &ct1));
+
+    let h = Hint::<P::K>::from_fn(|i| signature.hint(i));
+    let w1 = use_hint_vec(&h, &w_approx, P::GAMMA2);
+    let c_tilde = commitment_hash::<P>(mu, &w1);
+
+    (c_tilde.as_slice() == signature.c_tilde())
+        .then_some(())
+        .ok_or(Error::new())
+}
+
+/// A [`VerifyingKey`] with its public matrix `Â` and `NTT(t1 * 2^d)` precomputed.
+///
+/// Expanding `Â` from `ρ` by rejection sampling dominates the cost of verification. A prepared
+/// key does this work once, so verifying many signatures against the same long-lived key only
+/// pays for the signature-dependent part.
+///
+/// The precomputed values make this type large: `Â` alone takes `k * l` KiB (56 KiB for
+/// ML-DSA-87), so prefer keeping it behind a `Box` or `Arc`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedVerifyingKey<P: ParameterSet> {
+    verifying_key: VerifyingKey<P>,
+    a_hat: Matrix<P::K, P::L>,
+    t1_hat: Vector<P::K>,
+}
+
+impl<P: ParameterSet> PreparedVerifyingKey<P> {
+    /// The verifying key this key was prepared from
+    pub fn verifying_key(&self) -> &VerifyingKey<P> {
+        &self.verifying_key
+    }
+
+    /// Verify a signature over an externally computed message representative `μ`.
+    ///
+    /// See [`VerifyingKey::verify_mu`].
+    /// # Errors
+    /// Returns an error if the signature is invalid
+    pub fn verify_mu(&self, mu: &Mu, signature: &Signature<P>) -> Result<(), Error> {
+        verify_mu_prepared(&self.a_hat, &self.t1_hat, mu, signature)
+    }
+
+    /// Implements `ML-DSA.Verify` as defined in FIPS-204, using a context string.
+    ///
+    /// See [`VerifyingKey::try_verify_with_context`].
+    /// # Errors
+    /// Returns an error if the context is too long or if the signature is invalid
+    pub fn try_verify_with_context(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), Error> {
+        self.verify_mu(&pure_mu(&self.verifying_key.tr, msg, ctx)?, signature)
+    }
+
+    /// Implements `HashML-DSA.Verify` as defined in FIPS-204, using a context string.
+    ///
+    /// See [`VerifyingKey::try_verify_prehash_with_context`].
+    /// # Errors
+    /// Returns an error if the context is too long, the digest has the wrong length or if the
+    /// signature is invalid
+    pub fn try_verify_prehash_with_context<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), Error> {
+        self.verify_mu(
+            &prehash_mu::<H>(&self.verifying_key.tr, ph_msg, ctx)?,
+            signature,
+        )
+    }
+}
+
+impl<P: ParameterSet> From<&VerifyingKey<P>> for PreparedVerifyingKey<P> {
+    fn from(vk: &VerifyingKey<P>) -> Self {
+        PreparedVerifyingKey {
+            a_hat: expand_a::<P::K, P::L>(&vk.rho),
+            t1_hat: t1_hat(&vk.t1),
+            verifying_key: vk.clone(),
+        }
+    }
+}
+
+impl<P: ParameterSet> From<VerifyingKey<P>> for PreparedVerifyingKey<P> {
+    fn from(vk: VerifyingKey<P>) -> Self {
+        PreparedVerifyingKey::from(&vk)
+    }
+}
+
+impl<P: ParameterSet> AsRef<VerifyingKey<P>> for PreparedVerifyingKey<P> {
+    fn as_ref(&self) -> &VerifyingKey<P> {
+        &self.verifying_key
+    }
+}
+
+impl<P: ParameterSet> Verifier<Signature<P>> for PreparedVerifyingKey<P> {
+    fn verify(&self, msg: &[u8], signature: &Signature<P>) -> Result<(), Error> {
+        self.try_verify_with_context(msg, &[], signature)
+    }
+}
+
+/// HashML-DSA verification, with an empty context string
+impl<P, D> DigestVerifier<D, Signature<P>> for PreparedVerifyingKey<P>
+where
+    P: ParameterSet,
+    D: Digest + PreHash,
+{
+    fn verify_digest(&self, digest: D, signature: &Signature<P>) -> Result<(), Error> {
+        self.try_verify_prehash_with_context::<D>(&digest.finalize(), &[], signature)
+    }
+}
+
+impl VerifyingKeyLen for MlDsa44 {
+    type VkLen = U1312;
+}
+
+impl VerifyingKeyLen for MlDsa65 {
+    type VkLen = U1952;
+}
+
+impl VerifyingKeyLen for MlDsa87 {
+    type VkLen = U2592;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::macros::test_parameter_sets;
+    use crate::*;
+    use hybrid_array::Array;
+    use sha2::Digest;
+    use signature::DigestVerifier;
+    use signature::{Keypair, Signer, Verifier};
+
+    fn test_vk_serialize_deserialize<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let vk_bytes: Array<u8, _> = (&vk).into();
+        let vk2 = VerifyingKey::<P>::try_from(vk_bytes.as_slice()).unwrap();
+        assert_eq!(vk, vk2);
+    }
+    test_parameter_sets!(test_vk_serialize_deserialize);
+
+    fn test_prepared_vk_matches<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let prepared = vk.prepare();
+        assert_eq!(prepared.verifying_key(), &vk);
+
+        let msg = b"Hello world";
+        let sig = sk.try_sign_with_context(msg, b"ctx", None).unwrap();
+        assert!(prepared.try_verify_with_context(msg, b"ctx", &sig).is_ok());
+        assert!(prepared.try_verify_with_context(msg, b"xtc", &sig).is_err());
+        assert!(prepared
+            .try_verify_with_context(b"Hello World", b"ctx", &sig)
+            .is_err());
+
+        let sig = sk.sign(msg);
+        assert!(prepared.verify(msg, &sig).is_ok());
+
+        let digest = sha2::Sha512::digest(msg);
+        let sig = sk
+            .try_sign_prehash_with_context::<sha2::Sha512>(&digest, &[], None)
+            .unwrap();
+        assert!(vk
+            .try_verify_prehash_with_context::<sha2::Sha512>(&digest, &[], &sig)
+            .is_ok());
+        assert!(prepared
+            .try_verify_prehash_with_context::<sha2::Sha512>(&digest, &[], &sig)
+            .is_ok());
+        assert!(prepared
+            .verify_digest(sha2::Sha512::new_with_prefix(msg), &sig)
+            .is_ok());
+        assert!(prepared.verify(msg, &sig).is_err());
+
+        // A key prepared from another key rejects the signature
+        let other = SigningKey::<P>::new(&mut rng).verifying_key().prepare();
+        assert!(other
+            .try_verify_prehash_with_context::<sha2::Sha512>(&digest, &[], &sig)
+            .is_err());
+    }
+    test_parameter_sets!(test_prepared_vk_matches);
+
+    #[test]
+    fn test_vk_deserialize_fail_on_incorrect_length() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<MlDsa44>::new(&mut rng);
+        let bytes = sk.verifying_key().to_bytes();
+        assert!(VerifyingKey::<MlDsa44>::try_from(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+#![allow(non_snake_case)]
+
+use ml_dsa::*;
+use serde::Deserialize;
+use signature::Keypair;
+
+const KEYGEN_KAT_JSON: &str = include_str!("acvp/ML-DSA-keyGen-FIPS204/internalProjection.json");
+
+#[derive(Deserialize, Debug)]
+struct TestCase {
+    #[serde(with = "hex::serde")]
+    seed: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    pk: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    sk: Vec<u8>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TestGroup {
+    parameterSet: String,
+    tests: Vec<TestCase>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TestFile {
+    testGroups: Vec<TestGroup>,
+}
+
+macro_rules! parameter_case {
+    ($param:ident, $test_case:expr) => {{
+        let sk = SigningKey::<$param>::ml_dsa_keygen_internal(&$test_case.seed);
+        let vk = sk.verifying_key();
+        assert_eq!(sk.to_bytes().as_slice(), $test_case.sk);
+        assert_eq!(vk.to_bytes().as_slice(), $test_case.pk);
+    }};
+}
+
+#[test]
+fn test_keygen_cvp() {
+    let mut i = 0;
+    let test_file: TestFile = serde_json::from_str(KEYGEN_KAT_JSON).unwrap();
+    for test_group in test_file.testGroups {
+        let p = test_group.parameterSet;
+        for test_case in test_group.tests {
+            match p.as_str() {
+                MlDsa44::NAME => parameter_case!(MlDsa44, test_case),
+                MlDsa65::NAME => parameter_case!(MlDsa65, test_case),
+                MlDsa87::NAME => parameter_case!(MlDsa87, test_case),
+                _ => panic!("Unknown parameter set: {}", p),
+            }
+            i += 1;
+        }
+    }
+    print!("Number of test cases: {}", i);
+}
@@ -0,0 +1,136 @@
+//! `sigGen` test vectors from the NIST ACVP server.
+//!
+//! The vectors test `ML-DSA.Sign_internal` on a message and on an externally computed `μ`, and
+//! `ML-DSA.Sign` and `HashML-DSA.Sign` through the external interface. The test checks that they
+//! cover every interface with both deterministic and hedged signing, so that a vector file with
+//! missing groups fails.
+#![allow(non_snake_case)]
+
+mod common;
+
+use common::{with_prehash, FixedRng, HexString, Interface};
+use ml_dsa::*;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+
+const SIGGEN_KAT_JSON: &str = include_str!("acvp/ML-DSA-sigGen-FIPS204/internalProjection.json");
+
+/// Test cases whose expected signature does not verify under the group's public key, with this
+/// crate or with the independent `fips204` crate, both of which produce the same signature
+const DEFECTIVE_TC_IDS: &[u32] = &[16];
+
+#[derive(Deserialize, Debug)]
+struct TestCase {
+    tcId: u32,
+    sk: HexString,
+    message: Option<HexString>,
+    mu: Option<HexString>,
+    rnd: Option<HexString>,
+    context: Option<HexString>,
+    hashAlg: Option<String>,
+    signature: HexString,
+}
+
+#[derive(Deserialize, Debug)]
+struct TestGroup {
+    parameterSet: String,
+    deterministic: bool,
+    signatureInterface: Option<String>,
+    preHash: Option<String>,
+    #[serde(default)]
+    externalMu: bool,
+    tests: Vec<TestCase>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TestFile {
+    testGroups: Vec<TestGroup>,
+}
+
+macro_rules! parameter_case {
+    ($param:ident, $interface:expr, $test_group:expr, $test_case:expr) => {{
+        let sk = SigningKey::<$param>::try_from($test_case.sk.data.as_slice()).unwrap();
+        let rnd = $test_case.rnd.as_ref().map(|x| x.data.as_slice());
+        assert_eq!(rnd.is_none(), $test_group.deterministic);
+        let msg = || $test_case.message.as_ref().unwrap().data.as_slice();
+        let ctx = $test_case
+            .context
+            .as_ref()
+            .map_or(&[][..], |x| x.data.as_slice());
+
+        macro_rules! sign_prehash {
+            ($hash:ty, $digest:expr) => {
+                sk.try_sign_prehash_with_context::<$hash>(&$digest, ctx, rnd)
+                    .unwrap()
+            };
+        }
+
+        let sig = match $interface {
+            Interface::ExternalMu => {
+                let mu = $test_case.mu.as_ref().unwrap().data.as_slice();
+                let mu = mu.try_into().unwrap();
+                match rnd {
+                    Some(rnd) => sk.sign_mu_with_rng(&mut FixedRng(rnd), mu),
+                    None => sk.sign_mu(mu),
+                }
+            }
+            Interface::Internal => sk.ml_dsa_sign_internal(&[msg()], rnd),
+            Interface::Pure => sk.try_sign_with_context(msg(), ctx, rnd).unwrap(),
+            Interface::PreHash => {
+                with_prehash!($test_case.hashAlg.as_deref().unwrap(), msg(), sign_prehash)
+            }
+        };
+        assert_eq!(sig.to_bytes().as_slice(), $test_case.signature.data);
+    }};
+}
+
+/// Run every test case in `json`, and check that the groups cover exactly the interfaces in
+/// `expected`, each with deterministic and hedged signing
+fn test_sign_cvp(json: &str, expected: &[Interface]) {
+    let mut i = 0;
+    let mut covered = BTreeSet::new();
+    let test_file: TestFile = serde_json::from_str(json).unwrap();
+    for test_group in test_file.testGroups {
+        let p = test_group.parameterSet.as_str();
+        let interface = Interface::new(
+            test_group.signatureInterface.as_deref(),
+            test_group.preHash.as_deref(),
+            test_group.externalMu,
+        );
+        for test_case in &test_group.tests {
+            if DEFECTIVE_TC_IDS.contains(&test_case.tcId) {
+                continue;
+            }
+            match p {
+                MlDsa44::NAME => parameter_case!(MlDsa44, interface, test_group, test_case),
+                MlDsa65::NAME => parameter_case!(MlDsa65, interface, test_group, test_case),
+                MlDsa87::NAME => parameter_case!(MlDsa87, interface, test_group, test_case),
+                _ => panic!("Unknown parameter set: {}", p),
+            }
+            covered.insert((interface, test_group.deterministic));
+            i += 1;
+        }
+    }
+    let expected = expected
+        .iter()
+        .flat_map(|&interface| [(interface, false), (interface, true)])
+        .collect();
+    assert_eq!(
+        covered, expected,
+        "Vectors do not cover the expected groups"
+    );
+    print!("Number of test cases: {}", i);
+}
+
+#[test]
+fn test_sign_cvp_all_interfaces() {
+    test_sign_cvp(
+        SIGGEN_KAT_JSON,
+        &[
+            Interface::Internal,
+            Interface::ExternalMu,
+            Interface::Pure,
+            Interface::PreHash,
+        ],
+    );
+}
@@ -0,0 +1,130 @@
+//! `sigVer` test vectors from the NIST ACVP server.
+//!
+//! The vectors test `ML-DSA.Verify_internal` on a message and on an externally computed `μ`, and
+//! `ML-DSA.Verify` and `HashML-DSA.Verify` through the external interface. The test checks that
+//! they cover every interface with both passing and failing cases, so that a vector file with
+//! missing groups fails.
+#![allow(non_snake_case)]
+
+mod common;
+
+use common::{with_prehash, HexString, Interface};
+use ml_dsa::*;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+
+const SIGVER_KAT_JSON: &str = include_str!("acvp/ML-DSA-sigVer-FIPS204/internalProjection.json");
+
+#[derive(Deserialize, Debug)]
+struct TestCase {
+    pk: Option<HexString>,
+    message: Option<HexString>,
+    mu: Option<HexString>,
+    context: Option<HexString>,
+    hashAlg: Option<String>,
+    signature: HexString,
+    testPassed: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct TestGroup {
+    parameterSet: String,
+    pk: Option<HexString>,
+    signatureInterface: Option<String>,
+    preHash: Option<String>,
+    #[serde(default)]
+    externalMu: bool,
+    tests: Vec<TestCase>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TestFile {
+    testGroups: Vec<TestGroup>,
+}
+
+macro_rules! parameter_case {
+    ($param:ident, $interface:expr, $test_group:expr, $test_case:expr) => {{
+        let pk = $test_case.pk.as_ref().or($test_group.pk.as_ref()).unwrap();
+        let vk = VerifyingKey::<$param>::try_from(pk.data.as_slice()).unwrap();
+        let msg = || $test_case.message.as_ref().unwrap().data.as_slice();
+        let ctx = $test_case
+            .context
+            .as_ref()
+            .map_or(&[][..], |x| x.data.as_slice());
+
+        if let Ok(sig) = Signature::<$param>::try_from($test_case.signature.data.as_slice()) {
+            macro_rules! verify_prehash {
+                ($hash:ty, $digest:expr) => {
+                    vk.try_verify_prehash_with_context::<$hash>(&$digest, ctx, &sig)
+                };
+            }
+
+            let success = match $interface {
+                Interface::ExternalMu => {
+                    let mu = $test_case.mu.as_ref().unwrap().data.as_slice();
+                    vk.verify_mu(mu.try_into().unwrap(), &sig)
+                }
+                Interface::Internal => vk.ml_dsa_verify_internal(&[msg()], &sig),
+                Interface::Pure => vk.try_verify_with_context(msg(), ctx, &sig),
+                Interface::PreHash => {
+                    with_prehash!(
+                        $test_case.hashAlg.as_deref().unwrap(),
+                        msg(),
+                        verify_prehash
+                    )
+                }
+            };
+            assert_eq!($test_case.testPassed, success.is_ok());
+        } else {
+            assert!(!$test_case.testPassed);
+        }
+    }};
+}
+
+/// Run every test case in `json`, and check that the groups cover exactly the interfaces in
+/// `expected`, each with passing and failing cases
+fn test_verify_cvp(json: &str, expected: &[Interface]) {
+    let mut i = 0;
+    let mut covered = BTreeSet::new();
+    let test_file: TestFile = serde_json::from_str(json).unwrap();
+    for test_group in test_file.testGroups {
+        let p = test_group.parameterSet.as_str();
+        let interface = Interface::new(
+            test_group.signatureInterface.as_deref(),
+            test_group.preHash.as_deref(),
+            test_group.externalMu,
+        );
+        for test_case in &test_group.tests {
+            match p {
+                MlDsa44::NAME => parameter_case!(MlDsa44, interface, test_group, test_case),
+                MlDsa65::NAME => parameter_case!(MlDsa65, interface, test_group, test_case),
+                MlDsa87::NAME => parameter_case!(MlDsa87, interface, test_group, test_case),
+                _ => panic!("Unknown parameter set: {}", p),
+            }
+            covered.insert((interface, test_case.testPassed));
+            i += 1;
+        }
+    }
+    let expected = expected
+        .iter()
+        .flat_map(|&interface| [(interface, false), (interface, true)])
+        .collect();
+    assert_eq!(
+        covered, expected,
+        "Vectors do not cover the expected groups"
+    );
+    print!("Number of test cases: {}", i);
+}
+
+#[test]
+fn test_verify_cvp_all_interfaces() {
+    test_verify_cvp(
+        SIGVER_KAT_JSON,
+        &[
+            Interface::Internal,
+            Interface::ExternalMu,
+            Interface::Pure,
+            Interface::PreHash,
+        ],
+    );
+}
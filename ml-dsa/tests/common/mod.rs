@@ -0,0 +1,104 @@
+//! Helpers shared by the ACVP tests
+#![allow(dead_code)]
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct HexString {
+    #[serde(with = "hex::serde")]
+    pub data: Vec<u8>,
+}
+
+/// A random number generator returning the fixed randomness `rnd` of a hedged test case
+pub struct FixedRng<'a>(pub &'a [u8]);
+
+impl rand_core::RngCore for FixedRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let (head, tail) = self.0.split_at(dest.len());
+        dest.copy_from_slice(head);
+        self.0 = tail;
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl rand_core::CryptoRng for FixedRng<'_> {}
+
+/// Expand to `$f!(H, digest)`, with `H` the HashML-DSA pre-hash function called `$hash_alg` in
+/// ACVP vectors and `digest` the hash of `$msg` under `H`
+macro_rules! with_prehash {
+    ($hash_alg:expr, $msg:expr, $f:ident) => {{
+        macro_rules! digest {
+            ($hash:ty) => {
+                <$hash as digest::Digest>::digest($msg)
+            };
+        }
+        macro_rules! xof {
+            ($hash:ty, $len:expr) => {{
+                let mut digest = [0u8; $len];
+                <$hash as digest::ExtendableOutput>::digest_xof($msg, &mut digest);
+                digest
+            }};
+        }
+        match $hash_alg {
+            "SHA2-224" => $f!(sha2::Sha224, digest!(sha2::Sha224)),
+            "SHA2-256" => $f!(sha2::Sha256, digest!(sha2::Sha256)),
+            "SHA2-384" => $f!(sha2::Sha384, digest!(sha2::Sha384)),
+            "SHA2-512" => $f!(sha2::Sha512, digest!(sha2::Sha512)),
+            "SHA2-512/224" => $f!(sha2::Sha512_224, digest!(sha2::Sha512_224)),
+            "SHA2-512/256" => $f!(sha2::Sha512_256, digest!(sha2::Sha512_256)),
+            "SHA3-224" => $f!(sha3::Sha3_224, digest!(sha3::Sha3_224)),
+            "SHA3-256" => $f!(sha3::Sha3_256, digest!(sha3::Sha3_256)),
+            "SHA3-384" => $f!(sha3::Sha3_384, digest!(sha3::Sha3_384)),
+            "SHA3-512" => $f!(sha3::Sha3_512, digest!(sha3::Sha3_512)),
+            "SHAKE-128" => $f!(sha3::Shake128, xof!(sha3::Shake128, 32)),
+            "SHAKE-256" => $f!(sha3::Shake256, xof!(sha3::Shake256, 64)),
+            alg => panic!("Unknown hash algorithm: {}", alg),
+        }
+    }};
+}
+pub(crate) use with_prehash;
+
+/// The signing or verification interface tested by an ACVP test group
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Interface {
+    /// `ML-DSA.Sign_internal` and `ML-DSA.Verify_internal` on a message
+    Internal,
+    /// The internal functions on an externally computed `μ`
+    ExternalMu,
+    /// `ML-DSA.Sign` and `ML-DSA.Verify`
+    Pure,
+    /// `HashML-DSA.Sign` and `HashML-DSA.Verify`
+    PreHash,
+}
+
+impl Interface {
+    /// Classify a test group by its `signatureInterface`, `preHash` and `externalMu` fields
+    pub fn new(
+        signature_interface: Option<&str>,
+        pre_hash: Option<&str>,
+        external_mu: bool,
+    ) -> Self {
+        match (signature_interface, pre_hash, external_mu) {
+            (None | Some("internal"), _, true) => Interface::ExternalMu,
+            (None | Some("internal"), _, false) => Interface::Internal,
+            (Some("external"), Some("pure"), false) => Interface::Pure,
+            (Some("external"), Some("preHash"), false) => Interface::PreHash,
+            (interface, pre_hash, external_mu) => {
+                panic!("Unknown test group: {interface:?}, {pre_hash:?}, {external_mu}")
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Known-answer tests for the round-3 Dilithium parameter sets
+//!
+//! The round-3 submission's `PQCsignKAT_*.rsp` files are generated from the NIST AES-256 CTR
+//! DRBG. This test regenerates them with the same DRBG, and checks the first entry against the
+//! SHA-256 published as `nistkat-sha256` in PQClean's `META.yml` for each scheme, and the whole
+//! file against the output of the reference implementation (PQClean `clean` at commit 61b51a7).
+#![cfg(all(feature = "dilithium_r3", feature = "alloc"))]
+
+use aes::cipher::{BlockCipherEncrypt, KeyInit};
+use aes::Aes256;
+use ml_dsa::dilithium_r3::*;
+use sha2::{Digest, Sha256};
+use signature::{Keypair, Signer, Verifier};
+use std::fmt::Write;
+use std::ops::Range;
+
+/// The NIST AES-256 CTR DRBG of the PQC submission package (`rng.c`), without personalization
+struct Drbg {
+    key: [u8; 32],
+    v: [u8; 16],
+}
+
+impl Drbg {
+    fn new(entropy: &[u8; 48]) -> Self {
+        let mut drbg = Drbg {
+            key: [0; 32],
+            v: [0; 16],
+        };
+        drbg.update(Some(entropy));
+        drbg
+    }
+
+    fn next_block(&mut self, cipher: &Aes256) -> [u8; 16] {
+        let v = u128::from_be_bytes(self.v).wrapping_add(1);
+        self.v = v.to_be_bytes();
+        let mut block = self.v.into();
+        cipher.encrypt_block(&mut block);
+        block.into()
+    }
+
+    fn update(&mut self, data: Option<&[u8; 48]>) {
+        let cipher = Aes256::new(&self.key.into());
+        let mut temp = [0u8; 48];
+        for chunk in temp.chunks_exact_mut(16) {
+            chunk.copy_from_slice(&self.next_block(&cipher));
+        }
+        if let Some(data) = data {
+            temp.iter_mut().zip(data).for_each(|(t, d)| *t ^= d);
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
+    }
+
+    fn fill(&mut self, out: &mut [u8]) {
+        let cipher = Aes256::new(&self.key.into());
+        for chunk in out.chunks_mut(16) {
+            let block = self.next_block(&cipher);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(None);
+    }
+}
+
+/// Write `name = HEX` to the response file, in uppercase as the reference implementation does
+fn write_hex(rsp: &mut String, name: &str, data: &[u8]) {
+    writeln!(rsp, "{name} = {}", hex::encode_upper(data)).unwrap();
+}
+
+/// Generate the response file of the round-3 submission, returning it together with the range
+/// of its first entry
+fn generate_rsp<P: DilithiumParams>() -> (String, Range<usize>) {
+    let mut entropy = [0u8; 48];
+    entropy.iter_mut().zip(0..).for_each(|(e, i)| *e = i);
+    let mut drbg = Drbg::new(&entropy);
+
+    let cases: Vec<_> = (1..=100)
+        .map(|i| {
+            let mut seed = [0u8; 48];
+            drbg.fill(&mut seed);
+            let mut msg = vec![0u8; 33 * i];
+            drbg.fill(&mut msg);
+            (seed, msg)
+        })
+        .collect();
+
+    let mut rsp = format!("# {}\n\n", P::NAME);
+    let mut first_entry = 0..0;
+    for (count, (seed, msg)) in cases.iter().enumerate() {
+        let start = rsp.len();
+        let mut xi = [0u8; 32];
+        Drbg::new(seed).fill(&mut xi);
+        let sk = SigningKey::<P>::from_seed(&xi.into());
+        let vk = sk.verifying_key();
+        let sig = sk.sign(msg);
+        assert!(vk.verify(msg, &sig).is_ok());
+
+        let sk_bytes = sk.to_vec();
+        assert_eq!(SigningKey::<P>::try_from(sk_bytes.as_slice()).unwrap(), sk);
+
+        writeln!(rsp, "count = {count}").unwrap();
+        write_hex(&mut rsp, "seed", seed);
+        writeln!(rsp, "mlen = {}", msg.len()).unwrap();
+        write_hex(&mut rsp, "msg", msg);
+        write_hex(&mut rsp, "pk", &vk.to_bytes());
+        write_hex(&mut rsp, "sk", &sk_bytes);
+        writeln!(rsp, "smlen = {}", sig.to_bytes().len() + msg.len()).unwrap();
+        write_hex(&mut rsp, "sm", &[sig.to_bytes().as_slice(), msg].concat());
+        if count == 0 {
+            first_entry = start..rsp.len();
+        }
+        rsp.push('\n');
+    }
+
+    (rsp, first_entry)
+}
+
+fn test_kat<P: DilithiumParams>(first_entry_sha256: &str, rsp_sha256: &str) {
+    let (rsp, first_entry) = generate_rsp::<P>();
+    assert_eq!(
+        hex::encode(Sha256::digest(&rsp[first_entry])),
+        first_entry_sha256
+    );
+    assert_eq!(hex::encode(Sha256::digest(&rsp)), rsp_sha256);
+}
+
+#[test]
+fn test_kat_dilithium2() {
+    test_kat::<Dilithium2>(
+        "faa8998108fa541309c9df5044018c5d26cc23654594bef639dd64b838646cbd",
+        "38ed991c5ca11e39ab23945ca37af89e059d16c5474bf8ba96b15cb4e948af2a",
+    );
+}
+
+#[test]
+fn test_kat_dilithium3() {
+    test_kat::<Dilithium3>(
+        "8439f580566c46b99449b2cbbd597ce59bcd5d184b90c1108b79a08f6bdbbcb1",
+        "8196b32212753f525346201ffec1c7a0a852596fa0b57bd4e2746231dab44d55",
+    );
+}
+
+#[test]
+fn test_kat_dilithium5() {
+    test_kat::<Dilithium5>(
+        "984ea5f06b13778292f60ecc07301af76e375f1bb9f4a39d676513439e1e83a2",
+        "7ded97a6e6c809b43b54c248171d7504fa6a0cab651bf288bb00034782667481",
+    );
+}
@@ -0,0 +1,97 @@
+//! PKCS#8 private key and SPKI public key tests
+//!
+//! The example keys were generated with OpenSSL 3.5:
+//!
+//! ```text
+//! $ openssl genpkey -algorithm ML-DSA-65 -provparam ml-dsa.output_formats=seed-priv \
+//!     -outform DER -out ml-dsa-65-both.der
+//! $ openssl pkey -inform DER -in ml-dsa-65-both.der -provparam ml-dsa.output_formats=seed-only \
+//!     -outform DER -out ml-dsa-65-seed.der
+//! $ openssl pkey -inform DER -in ml-dsa-65-both.der -provparam ml-dsa.output_formats=priv-only \
+//!     -outform DER -out ml-dsa-65-expanded.der
+//! $ openssl pkey -inform DER -in ml-dsa-65-both.der -pubout -outform DER -out ml-dsa-65-pub.der
+//! $ printf 'Hello from OpenSSL' | openssl pkeyutl -sign -rawin -inkey ml-dsa-65-both.der \
+//!     -keyform DER -out ml-dsa-65-sig.bin
+//! ```
+
+#![cfg(feature = "pkcs8")]
+use ml_dsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ml_dsa::{MlDsa44, MlDsa65, Signature, SigningKey, VerifyingKey};
+use signature::{Keypair, Verifier};
+
+#[cfg(feature = "alloc")]
+use ml_dsa::pkcs8::{EncodePrivateKey, EncodePublicKey};
+
+/// ML-DSA-65 private key in the `seed` form
+const SEED_DER: &[u8] = include_bytes!("examples/ml-dsa-65-seed.der");
+
+/// ML-DSA-65 private key in the `expandedKey` form
+const EXPANDED_DER: &[u8] = include_bytes!("examples/ml-dsa-65-expanded.der");
+
+/// ML-DSA-65 private key in the `both` form
+const BOTH_DER: &[u8] = include_bytes!("examples/ml-dsa-65-both.der");
+
+/// ML-DSA-65 `SubjectPublicKeyInfo`
+const PUBLIC_KEY_DER: &[u8] = include_bytes!("examples/ml-dsa-65-pub.der");
+
+/// ML-DSA-65 signature over `MESSAGE` with an empty context string
+const SIGNATURE: &[u8] = include_bytes!("examples/ml-dsa-65-sig.bin");
+
+const MESSAGE: &[u8] = b"Hello from OpenSSL";
+
+#[test]
+fn decode_private_key_forms() {
+    let seed = SigningKey::<MlDsa65>::from_pkcs8_der(SEED_DER).unwrap();
+    let expanded = SigningKey::<MlDsa65>::from_pkcs8_der(EXPANDED_DER).unwrap();
+    let both = SigningKey::<MlDsa65>::from_pkcs8_der(BOTH_DER).unwrap();
+
+    assert_eq!(seed, expanded);
+    assert_eq!(seed, both);
+    assert!(seed.to_seed().is_some());
+    assert_eq!(expanded.to_seed(), None);
+    assert_eq!(both.to_seed(), seed.to_seed());
+}
+
+#[test]
+fn decode_public_key() {
+    let sk = SigningKey::<MlDsa65>::from_pkcs8_der(SEED_DER).unwrap();
+    let vk = VerifyingKey::<MlDsa65>::from_public_key_der(PUBLIC_KEY_DER).unwrap();
+    assert_eq!(sk.verifying_key(), vk);
+
+    let sig = Signature::<MlDsa65>::try_from(SIGNATURE).unwrap();
+    vk.verify(MESSAGE, &sig).unwrap();
+}
+
+#[test]
+fn decode_wrong_parameter_set() {
+    assert!(SigningKey::<MlDsa44>::from_pkcs8_der(SEED_DER).is_err());
+    assert!(VerifyingKey::<MlDsa44>::from_public_key_der(PUBLIC_KEY_DER).is_err());
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn encode_private_key() {
+    let sk = SigningKey::<MlDsa65>::from_pkcs8_der(BOTH_DER).unwrap();
+    assert_eq!(sk.to_pkcs8_der().unwrap().as_bytes(), SEED_DER);
+
+    let sk = SigningKey::<MlDsa65>::from_pkcs8_der(EXPANDED_DER).unwrap();
+    assert_eq!(sk.to_pkcs8_der().unwrap().as_bytes(), EXPANDED_DER);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn encode_public_key() {
+    let vk = VerifyingKey::<MlDsa65>::from_public_key_der(PUBLIC_KEY_DER).unwrap();
+    assert_eq!(vk.to_public_key_der().unwrap().as_bytes(), PUBLIC_KEY_DER);
+}
+
+#[test]
+#[cfg(feature = "pem")]
+fn decode_pem() {
+    let sk =
+        SigningKey::<MlDsa65>::from_pkcs8_pem(include_str!("examples/ml-dsa-65-seed.pem")).unwrap();
+    let vk =
+        VerifyingKey::<MlDsa65>::from_public_key_pem(include_str!("examples/ml-dsa-65-pub.pem"))
+            .unwrap();
+    assert_eq!(sk.verifying_key(), vk);
+}
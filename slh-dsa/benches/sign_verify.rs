@@ -14,6 +14,18 @@ pub fn sign_benchmark<P: ParameterSet>(c: &mut Criterion) {
     });
 }
 
+pub fn cached_sign_benchmark<P: ParameterSet>(c: &mut Criterion) {
+    let mut rng = rand::thread_rng();
+    let sk = CachedSigningKey::new(SigningKey::<P>::new(&mut rng));
+    c.bench_function(&format!("cached sign: {}", P::NAME), |b| {
+        b.iter(|| {
+            let msg = b"Hello, world!";
+            let sig = sk.try_sign(msg).unwrap();
+            black_box(sig)
+        })
+    });
+}
+
 pub fn verify_benchmark<P: ParameterSet>(c: &mut Criterion) {
     let mut rng = rand::thread_rng();
     let sk = SigningKey::<P>::new(&mut rng);
@@ -36,6 +48,13 @@ criterion_group!(name = sign_benches;
               sign_benchmark<Sha2_128f>, sign_benchmark<Sha2_192f>, sign_benchmark<Sha2_256f>,
 );
 
+criterion_group!(name = cached_sign_benches;
+    config = Criterion::default().sample_size(10);
+    targets = cached_sign_benchmark<Shake128s>, cached_sign_benchmark<Shake192s>,
+              cached_sign_benchmark<Shake256s>, cached_sign_benchmark<Sha2_128s>,
+              cached_sign_benchmark<Sha2_192s>, cached_sign_benchmark<Sha2_256s>,
+);
+
 criterion_group!(name = verify_benches;
     config = Criterion::default().sample_size(10);
     targets = sign_benchmark<Shake128s>, sign_benchmark<Shake192s>, sign_benchmark<Shake256s>,
@@ -44,4 +63,4 @@ criterion_group!(name = verify_benches;
               sign_benchmark<Sha2_128f>, sign_benchmark<Sha2_192f>, sign_benchmark<Sha2_256f>,
 );
 
-criterion_main!(sign_benches, verify_benches);
+criterion_main!(sign_benches, cached_sign_benches, verify_benches);
@@ -23,11 +23,11 @@ use zerocopy::AsBytes;
 
 /// `Address` represents a hash address as defined by FIPS-205 section 4.2
 pub trait Address: AsRef<[u8]> {
+    /// The type of the address, which determines the meaning of its last 12 bytes
     const TYPE_CONST: u32;
 
-    #[allow(clippy::doc_markdown)] // False positive
     /// Returns the address as a compressed 22-byte array
-    /// ADRSc = ADRS[3] ∥ ADRS[8 : 16] ∥ ADRS[19] ∥ ADRS[20 : 32]
+    /// `ADRSc = ADRS[3] ∥ ADRS[8 : 16] ∥ ADRS[19] ∥ ADRS[20 : 32]`
     fn compressed(&self) -> Array<u8, U22> {
         let bytes = self.as_ref();
         let mut compressed = Array::<u8, U22>::default();
@@ -39,85 +39,124 @@ pub trait Address: AsRef<[u8]> {
     }
 }
 
+/// Address of the hashes in a WOTS+ chain (`WOTS_HASH`)
 #[derive(Clone, AsBytes)]
 #[repr(C)]
 pub struct WotsHash {
+    /// Layer of the hypertree, counted from the bottom
     pub layer_adrs: U32,
+    /// High 32 bits of the tree address, which are zero in all FIPS-205 parameter sets
     pub tree_adrs_high: U32,
+    /// Low 64 bits of the tree address, the index of the XMSS tree within its layer
     pub tree_adrs_low: U64,
     type_const: U32, // 0
+    /// Index of the WOTS+ or FORS key pair, the leaf of the XMSS tree
     pub key_pair_adrs: U32,
+    /// Index of the WOTS+ chain
     pub chain_adrs: U32,
+    /// Position within the WOTS+ chain
     pub hash_adrs: U32,
 }
 
+/// Address for compressing the ends of the WOTS+ chains into a public key (`WOTS_PK`)
 #[derive(Clone, AsBytes)]
 #[repr(C)]
 pub struct WotsPk {
+    /// Layer of the hypertree, counted from the bottom
     pub layer_adrs: U32,
+    /// High 32 bits of the tree address, which are zero in all FIPS-205 parameter sets
     pub tree_adrs_high: U32,
+    /// Low 64 bits of the tree address, the index of the XMSS tree within its layer
     pub tree_adrs_low: U64,
     type_const: U32, // 1
+    /// Index of the WOTS+ or FORS key pair, the leaf of the XMSS tree
     pub key_pair_adrs: U32,
     padding: U64, // 0
 }
 
+/// Address of a node of an XMSS tree (`TREE`)
 #[derive(Clone, AsBytes)]
 #[repr(C)]
 pub struct HashTree {
+    /// Layer of the hypertree, counted from the bottom
     pub layer_adrs: U32,
+    /// High 32 bits of the tree address, which are zero in all FIPS-205 parameter sets
     pub tree_adrs_high: U32,
+    /// Low 64 bits of the tree address, the index of the XMSS tree within its layer
     pub tree_adrs_low: U64,
     type_const: U32, // 2
     padding: U32,    // 0
+    /// Height of the node, with the leaves at height 0
     pub tree_height: U32,
+    /// Index of the node within its level of the tree
     pub tree_index: U32,
 }
 
+/// Address of a node of a FORS tree (`FORS_TREE`)
 #[derive(Clone, AsBytes)]
 #[repr(C)]
 pub struct ForsTree {
     layer_adrs: U32, // 0
+    /// High 32 bits of the tree address, which are zero in all FIPS-205 parameter sets
     pub tree_adrs_high: U32,
+    /// Low 64 bits of the tree address, the index of the XMSS tree within its layer
     pub tree_adrs_low: U64,
     type_const: U32, // 3
+    /// Index of the WOTS+ or FORS key pair, the leaf of the XMSS tree
     pub key_pair_adrs: U32,
+    /// Height of the node, with the leaves at height 0
     pub tree_height: U32,
+    /// Index of the node within its level of the tree
     pub tree_index: U32,
 }
 
+/// Address for compressing the FORS tree roots into a public key (`FORS_ROOTS`)
 #[derive(Clone, AsBytes)]
 #[repr(C)]
 pub struct ForsRoots {
     layer_adrs: U32, // 0
+    /// High 32 bits of the tree address, which are zero in all FIPS-205 parameter sets
     pub tree_adrs_high: U32,
+    /// Low 64 bits of the tree address, the index of the XMSS tree within its layer
     pub tree_adrs_low: U64,
     type_const: U32, // 4
+    /// Index of the WOTS+ or FORS key pair, the leaf of the XMSS tree
     pub key_pair_adrs: U32,
     padding: U64, // 0
 }
 
+/// Address for generating WOTS+ secret values (`WOTS_PRF`)
 #[derive(Clone, AsBytes)]
 #[repr(C)]
 pub struct WotsPrf {
+    /// Layer of the hypertree, counted from the bottom
     pub layer_adrs: U32,
+    /// High 32 bits of the tree address, which are zero in all FIPS-205 parameter sets
     pub tree_adrs_high: U32,
+    /// Low 64 bits of the tree address, the index of the XMSS tree within its layer
     pub tree_adrs_low: U64,
     type_const: U32, // 5
+    /// Index of the WOTS+ or FORS key pair, the leaf of the XMSS tree
     pub key_pair_adrs: U32,
+    /// Index of the WOTS+ chain
     pub chain_adrs: U32,
     hash_adrs: U32, // 0
 }
 
+/// Address for generating FORS secret values (`FORS_PRF`)
 #[derive(Clone, AsBytes)]
 #[repr(C)]
 pub struct ForsPrf {
     layer_adrs: U32, // 0
+    /// High 32 bits of the tree address, which are zero in all FIPS-205 parameter sets
     pub tree_adrs_high: U32,
+    /// Low 64 bits of the tree address, the index of the XMSS tree within its layer
     pub tree_adrs_low: U64,
     type_const: U32, // 6
+    /// Index of the WOTS+ or FORS key pair, the leaf of the XMSS tree
     pub key_pair_adrs: U32,
     tree_height: U32, // 0
+    /// Index of the node within its level of the tree
     pub tree_index: U32,
 }
 
@@ -185,6 +224,8 @@ impl AsRef<[u8]> for ForsPrf {
 }
 
 impl WotsHash {
+    /// The address for generating the secret values of the same WOTS+ chain
+    #[must_use]
     pub fn prf_adrs(&self) -> WotsPrf {
         WotsPrf {
             layer_adrs: self.layer_adrs,
@@ -197,6 +238,8 @@ impl WotsHash {
         }
     }
 
+    /// The address for compressing the public key of the same WOTS+ key pair
+    #[must_use]
     pub fn pk_adrs(&self) -> WotsPk {
         WotsPk {
             layer_adrs: self.layer_adrs,
@@ -208,6 +251,8 @@ impl WotsHash {
         }
     }
 
+    /// The address of the nodes of the same XMSS tree
+    #[must_use]
     pub fn tree_adrs(&self) -> HashTree {
         HashTree {
             layer_adrs: self.layer_adrs,
@@ -222,6 +267,9 @@ impl WotsHash {
 }
 
 impl ForsTree {
+    /// The address of the FORS key pair `key_pair_adrs` under the XMSS tree `tree_adrs_low`
+    /// of the bottom layer
+    #[must_use]
     pub fn new(tree_adrs_low: u64, key_pair_adrs: u32) -> ForsTree {
         ForsTree {
             layer_adrs: 0.into(),
@@ -233,6 +281,8 @@ impl ForsTree {
             tree_index: 0.into(),
         }
     }
+    /// The address for generating the secret value of the same FORS leaf
+    #[must_use]
     pub fn prf_adrs(&self) -> ForsPrf {
         ForsPrf {
             layer_adrs: 0.into(),
@@ -245,6 +295,8 @@ impl ForsTree {
         }
     }
 
+    /// The address for compressing the roots of the same FORS key pair
+    #[must_use]
     pub fn fors_roots(&self) -> ForsRoots {
         ForsRoots {
             layer_adrs: 0.into(),
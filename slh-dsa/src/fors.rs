@@ -3,15 +3,18 @@ use core::fmt::Debug;
 use hybrid_array::{Array, ArraySize};
 use typenum::Unsigned;
 
-use crate::{address, PkSeed, SkSeed};
+use crate::{address, SkSeed};
 
 use crate::hypertree::HypertreeParams;
 use crate::util::base_2b;
 
+/// The signature of a single FORS tree: a revealed secret value and its authentication path
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ForsMTSig<P: ForsParams> {
-    sk: Array<u8, P::N>,
-    auth: Array<Array<u8, P::N>, P::A>,
+    /// The secret value of the selected leaf
+    pub sk: Array<u8, P::N>,
+    /// The sibling of each node on the path from the leaf to the root, starting at the leaf
+    pub auth: Array<Array<u8, P::N>, P::A>,
 }
 
 impl<P: ForsParams> ForsMTSig<P> {
@@ -64,8 +67,9 @@ impl<P: ForsParams> TryFrom<&[u8]> for ForsMTSig<P> {
     }
 }
 
+/// A FORS signature, made of the signature of each of the `K` FORS trees
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct ForsSignature<P: ForsParams>(Array<ForsMTSig<P>, P::K>);
+pub struct ForsSignature<P: ForsParams>(pub Array<ForsMTSig<P>, P::K>);
 
 impl<P: ForsParams> TryFrom<&[u8]> for ForsSignature<P> {
     // TODO - real error type
@@ -90,8 +94,10 @@ impl<P: ForsParams> Default for ForsSignature<P> {
 }
 
 impl<P: ForsParams> ForsSignature<P> {
+    /// The length of a serialized signature in bytes
     pub const SIZE: usize = P::K::USIZE * (P::A::USIZE + 1) * P::N::USIZE;
 
+    /// Serialize the signature to `slice`, which must be `SIZE` bytes long
     pub fn write_to(&self, slice: &mut [u8]) {
         debug_assert!(
             slice.len() == Self::SIZE,
@@ -104,6 +110,7 @@ impl<P: ForsParams> ForsSignature<P> {
             .for_each(|(i, c)| self.0[i].write_to(c));
     }
 
+    /// Serialize the signature to a new `Vec`
     #[cfg(feature = "alloc")]
     pub fn to_vec(&self) -> Vec<u8> {
         let mut v = vec![0u8; Self::SIZE];
@@ -112,14 +119,89 @@ impl<P: ForsParams> ForsSignature<P> {
     }
 }
 
-pub(crate) trait ForsParams: HypertreeParams {
+/// A borrowed view of a FORS tree signature, either from a [`ForsMTSig`] or directly from bytes
+pub struct ForsMTSigRef<'a, P: ForsParams> {
+    /// The secret value of the selected leaf
+    pub sk: &'a Array<u8, P::N>,
+    /// The authentication path of the leaf
+    pub auth: &'a Array<Array<u8, P::N>, P::A>,
+}
+
+#[allow(clippy::expl_impl_clone_on_copy)] // Deriving would require `P: Clone`
+impl<P: ForsParams> Clone for ForsMTSigRef<'_, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: ForsParams> Copy for ForsMTSigRef<'_, P> {}
+
+impl<'a, P: ForsParams> ForsMTSigRef<'a, P> {
+    /// Borrow a signature from `A + 1` consecutive hash values
+    fn from_chunks(chunks: &'a [Array<u8, P::N>]) -> Self {
+        let (sk, auth) = chunks.split_first().expect("chunks is not empty");
+        ForsMTSigRef {
+            sk,
+            auth: auth.try_into().expect("chunk count matches A"),
+        }
+    }
+}
+
+impl<'a, P: ForsParams> From<&'a ForsMTSig<P>> for ForsMTSigRef<'a, P> {
+    fn from(sig: &'a ForsMTSig<P>) -> Self {
+        ForsMTSigRef {
+            sk: &sig.sk,
+            auth: &sig.auth,
+        }
+    }
+}
+
+/// A borrowed view of a FORS signature, either from a [`ForsSignature`] or directly from bytes
+pub struct ForsSignatureRef<'a, P: ForsParams>(pub Array<ForsMTSigRef<'a, P>, P::K>);
+
+impl<P: ForsParams> Clone for ForsSignatureRef<'_, P> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'a, P: ForsParams> TryFrom<&'a [u8]> for ForsSignatureRef<'a, P> {
+    // TODO - real error type
+    type Error = ();
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        if slice.len() != ForsSignature::<P>::SIZE {
+            return Err(());
+        }
+        let (chunks, _) = Array::<u8, P::N>::slice_as_chunks(slice);
+        Ok(Self(
+            chunks
+                .chunks_exact(P::A::USIZE + 1)
+                .map(ForsMTSigRef::from_chunks)
+                .collect(),
+        ))
+    }
+}
+
+impl<'a, P: ForsParams> From<&'a ForsSignature<P>> for ForsSignatureRef<'a, P> {
+    fn from(sig: &'a ForsSignature<P>) -> Self {
+        Self(sig.0.iter().map(ForsMTSigRef::from).collect())
+    }
+}
+
+/// The FORS few-time signature scheme, with `K` trees of `2^A` leaves
+pub trait ForsParams: HypertreeParams {
+    /// The number of FORS trees
     type K: ArraySize + Eq + Debug;
+    /// The height of each FORS tree
     type A: ArraySize + Eq + Debug;
-    type MD: ArraySize; // ceil(K*A/8)
+    /// The length of a FORS message digest in bytes, `ceil(K*A/8)`
+    type MD: ArraySize;
 
+    /// Algorithm 14: the secret value of leaf `idx`, counting the leaves of all `K` trees in
+    /// order, for the FORS key pair at `adrs`
     fn fors_sk_gen(
         sk_seed: &SkSeed<Self::N>,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::ForsTree,
         idx: u32,
     ) -> Array<u8, Self::N> {
@@ -128,11 +210,13 @@ pub(crate) trait ForsParams: HypertreeParams {
         Self::prf_sk(pk_seed, sk_seed, &adrs)
     }
 
+    /// Algorithm 15: compute the node at height `z` and index `i` of the FORS key pair at
+    /// `adrs`, counting the nodes at that height of all `K` trees in order
     fn fors_node(
         sk_seed: &SkSeed<Self::N>,
         i: u32,
         z: u32,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::ForsTree,
     ) -> Array<u8, Self::N> {
         debug_assert!(z <= Self::A::U32);
@@ -144,69 +228,196 @@ pub(crate) trait ForsParams: HypertreeParams {
             adrs.tree_index.set(i);
             Self::f(pk_seed, &adrs, &sk)
         } else {
-            let lnode = Self::fors_node(sk_seed, 2 * i, z - 1, pk_seed, &adrs);
-            let rnode = Self::fors_node(sk_seed, 2 * i + 1, z - 1, pk_seed, &adrs);
+            let (lnode, rnode) = if z == 1 {
+                (
+                    Self::fors_node(sk_seed, 2 * i, 0, pk_seed, &adrs),
+                    Self::fors_node(sk_seed, 2 * i + 1, 0, pk_seed, &adrs),
+                )
+            } else {
+                // Compute the four subtrees two levels down together
+                let [a, b, c, d] = Self::fors_node_x4(
+                    sk_seed,
+                    [0, 1, 2, 3].map(|k| 4 * i + k),
+                    z - 2,
+                    pk_seed,
+                    &adrs,
+                );
+                adrs.tree_height.set(z - 1);
+                adrs.tree_index.set(2 * i);
+                let lnode = Self::h(pk_seed, &adrs, &a, &b);
+                adrs.tree_index.set(2 * i + 1);
+                let rnode = Self::h(pk_seed, &adrs, &c, &d);
+                (lnode, rnode)
+            };
             adrs.tree_height.set(z);
             adrs.tree_index.set(i);
             Self::h(pk_seed, &adrs, &lnode, &rnode)
         }
     }
 
+    /// Four independent instances of `fors_node` at the same height, computed together with
+    /// `f_x4` and `h_x4`
+    #[doc(hidden)]
+    fn fors_node_x4(
+        sk_seed: &SkSeed<Self::N>,
+        nodes: [u32; 4],
+        z: u32,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::ForsTree,
+    ) -> [Array<u8, Self::N>; 4] {
+        let node_adrs = nodes.map(|i| {
+            let mut adrs = adrs.clone();
+            adrs.tree_height.set(z);
+            adrs.tree_index.set(i);
+            adrs
+        });
+        if z == 0 {
+            let sk = nodes.map(|i| Self::fors_sk_gen(sk_seed, pk_seed, adrs, i));
+            Self::f_x4(pk_seed, &node_adrs, &sk)
+        } else {
+            let lnodes = Self::fors_node_x4(sk_seed, nodes.map(|i| 2 * i), z - 1, pk_seed, adrs);
+            let rnodes =
+                Self::fors_node_x4(sk_seed, nodes.map(|i| 2 * i + 1), z - 1, pk_seed, adrs);
+            Self::h_x4(pk_seed, &node_adrs, &lnodes, &rnodes)
+        }
+    }
+
+    /// Sign with the FORS tree `i`, revealing the secret value of leaf `idx` of that tree
+    #[doc(hidden)]
+    fn fors_sign_tree(
+        sk_seed: &SkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::ForsTree,
+        i: u32,
+        idx: u16,
+    ) -> ForsMTSig<Self> {
+        let sk = Self::fors_sk_gen(sk_seed, pk_seed, adrs, (i << Self::A::U32) + u32::from(idx));
+        let mut auth = Array::<Array<u8, Self::N>, Self::A>::default();
+        for j in 0..Self::A::U32 {
+            let s = (idx >> j) ^ 1;
+            auth[j as usize] = Self::fors_node(
+                sk_seed,
+                (i << (Self::A::U32 - j)) + u32::from(s),
+                j,
+                pk_seed,
+                adrs,
+            );
+        }
+        ForsMTSig { sk, auth }
+    }
+
+    /// Algorithm 16: sign the message digest `md` with the FORS key pair at `adrs`
     fn fors_sign(
         md: &Array<u8, Self::MD>,
         sk_seed: &SkSeed<Self::N>,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::ForsTree,
     ) -> ForsSignature<Self> {
         let mut sig = ForsSignature::<Self>::default();
         let indices = base_2b::<Self::K, Self::A>(md);
-        for i in 0..Self::K::U32 {
-            sig.0[i as usize].sk = Self::fors_sk_gen(
-                sk_seed,
-                pk_seed,
-                adrs,
-                (i << Self::A::U32) + u32::from(indices[i as usize]),
-            );
-            for j in 0..Self::A::U32 {
-                let s = (indices[i as usize] >> j) ^ 1;
-                sig.0[i as usize].auth[j as usize] = Self::fors_node(
-                    sk_seed,
-                    (i << (Self::A::U32 - j)) + u32::from(s),
-                    j,
-                    pk_seed,
-                    adrs,
-                );
-            }
+        let sign_tree = |(i, tree_sig): (u32, &mut ForsMTSig<Self>)| {
+            *tree_sig = Self::fors_sign_tree(sk_seed, pk_seed, adrs, i, indices[i as usize]);
+        };
+
+        // The K trees are independent, so with the `rayon` feature they are signed in parallel
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            (0..Self::K::U32)
+                .into_par_iter()
+                .zip(sig.0.par_iter_mut())
+                .for_each(sign_tree);
         }
+        #[cfg(not(feature = "rayon"))]
+        (0..Self::K::U32).zip(sig.0.iter_mut()).for_each(sign_tree);
+
         sig
     }
 
-    fn fors_pk_from_sig(
-        sig: &ForsSignature<Self>,
+    /// Equivalent to `fors_sign` followed by `fors_pk_from_sig`, but the signature of each tree
+    /// is written to `buf` as soon as it is computed, so the whole `ForsSignature` is never held
+    /// in memory. Returns the FORS public key.
+    #[doc(hidden)]
+    fn fors_sign_to(
         md: &Array<u8, Self::MD>,
-        pk_seed: &PkSeed<Self::N>,
+        sk_seed: &SkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::ForsTree,
+        buf: &mut [u8],
     ) -> Array<u8, Self::N> {
-        let mut adrs = adrs.clone();
+        debug_assert!(
+            buf.len() == ForsSignature::<Self>::SIZE,
+            "Writing FORS sig to slice of incorrect length"
+        );
         let indices = base_2b::<Self::K, Self::A>(md);
         let mut roots = Array::<Array<u8, Self::N>, Self::K>::default();
-        for i in 0..Self::K::U32 {
-            let sk = &sig.0[i as usize].sk;
-            adrs.tree_height.set(0);
-            adrs.tree_index
-                .set((i << Self::A::U32) + u32::from(indices[i as usize]));
-            let mut node = Self::f(pk_seed, &adrs, sk);
-            for j in 0..Self::A::U32 {
-                adrs.tree_height.set(j + 1);
-                adrs.tree_index.set(adrs.tree_index.get() >> 1);
-                if indices[i as usize] >> j & 1 == 0 {
-                    node = Self::h(pk_seed, &adrs, &node, &sig.0[i as usize].auth[j as usize]);
-                } else {
-                    node = Self::h(pk_seed, &adrs, &sig.0[i as usize].auth[j as usize], &node);
-                }
+        let sign_tree = |i: u32, buf: &mut [u8], root: &mut Array<u8, Self::N>| {
+            let idx = indices[i as usize];
+            let tree_sig = Self::fors_sign_tree(sk_seed, pk_seed, adrs, i, idx);
+            tree_sig.write_to(buf);
+            *root = Self::fors_tree_root((&tree_sig).into(), i, idx, pk_seed, adrs);
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            (0..Self::K::U32)
+                .into_par_iter()
+                .zip(
+                    buf.par_chunks_exact_mut(ForsMTSig::<Self>::SIZE)
+                        .zip(roots.par_iter_mut()),
+                )
+                .for_each(|(i, (buf, root))| sign_tree(i, buf, root));
+        }
+        #[cfg(not(feature = "rayon"))]
+        (0..Self::K::U32)
+            .zip(
+                buf.chunks_exact_mut(ForsMTSig::<Self>::SIZE)
+                    .zip(roots.iter_mut()),
+            )
+            .for_each(|(i, (buf, root))| sign_tree(i, buf, root));
+
+        Self::t(pk_seed, &adrs.fors_roots(), &roots)
+    }
+
+    /// Compute the root of FORS tree `i` from its signature of leaf `idx`
+    #[doc(hidden)]
+    fn fors_tree_root(
+        sig: ForsMTSigRef<'_, Self>,
+        i: u32,
+        idx: u16,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::ForsTree,
+    ) -> Array<u8, Self::N> {
+        let mut adrs = adrs.clone();
+        adrs.tree_height.set(0);
+        adrs.tree_index.set((i << Self::A::U32) + u32::from(idx));
+        let mut node = Self::f(pk_seed, &adrs, sig.sk);
+        for j in 0..Self::A::U32 {
+            adrs.tree_height.set(j + 1);
+            adrs.tree_index.set(adrs.tree_index.get() >> 1);
+            if idx >> j & 1 == 0 {
+                node = Self::h(pk_seed, &adrs, &node, &sig.auth[j as usize]);
+            } else {
+                node = Self::h(pk_seed, &adrs, &sig.auth[j as usize], &node);
             }
-            roots[i as usize] = node;
         }
+        node
+    }
+
+    /// Algorithm 17: compute a FORS public key from the signature `sig` of `md`.
+    /// The signature is valid if the result is the public key of the key pair at `adrs`.
+    fn fors_pk_from_sig(
+        sig: &ForsSignatureRef<'_, Self>,
+        md: &Array<u8, Self::MD>,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::ForsTree,
+    ) -> Array<u8, Self::N> {
+        let indices = base_2b::<Self::K, Self::A>(md);
+        let roots = Array::<Array<u8, Self::N>, Self::K>::from_fn(|i| {
+            let i_u32 = u32::try_from(i).expect("K is less than 2^32");
+            Self::fors_tree_root(sig.0[i], i_u32, indices[i], pk_seed, adrs)
+        });
         Self::t(pk_seed, &adrs.fors_roots(), &roots)
     }
 }
@@ -215,7 +426,7 @@ pub(crate) trait ForsParams: HypertreeParams {
 mod tests {
     use self::address::ForsTree;
     use crate::util::macros::test_parameter_sets;
-    use crate::Shake128f;
+    use crate::{PkSeed, Shake128f};
 
     use rand::{thread_rng, Rng, RngCore};
 
@@ -476,7 +687,7 @@ mod tests {
 
         let sk_seed = SkSeed::new(&mut rng);
 
-        let pk_seed = PkSeed::new(&mut rng);
+        let pk_seed = PkSeed::new(&mut rng).into();
 
         let mut msg = Array::<u8, Fors::MD>::default();
         rng.fill_bytes(msg.as_mut_slice());
@@ -497,7 +708,7 @@ mod tests {
         let pk = Fors::t(&pk_seed, &adrs.fors_roots(), &pks);
 
         let sig = Fors::fors_sign(&msg, &sk_seed, &pk_seed, &adrs);
-        let pk_recovered = Fors::fors_pk_from_sig(&sig, &msg, &pk_seed, &adrs);
+        let pk_recovered = Fors::fors_pk_from_sig(&(&sig).into(), &msg, &pk_seed, &adrs);
         assert_eq!(pk, pk_recovered);
     }
 
@@ -509,7 +720,7 @@ mod tests {
 
         let sk_seed = SkSeed::new(&mut rng);
 
-        let pk_seed = PkSeed::new(&mut rng);
+        let pk_seed = PkSeed::new(&mut rng).into();
 
         let mut msg = Array::<u8, Fors::MD>::default();
         rng.fill_bytes(msg.as_mut_slice());
@@ -534,7 +745,7 @@ mod tests {
         // Modify the message
         msg[0] ^= 0xff; // Invert the first byte of the message
 
-        let pk_recovered = Fors::fors_pk_from_sig(&sig, &msg, &pk_seed, &adrs);
+        let pk_recovered = Fors::fors_pk_from_sig(&(&sig).into(), &msg, &pk_seed, &adrs);
         assert_ne!(
             pk, pk_recovered,
             "Signature verification should fail with a modified message"
@@ -2,8 +2,12 @@
 //!
 //! Each parameter set defines several functions derived from the core hash function (SHA2 or SHAKE)
 //! A `HashSuite` contains all of these functions, defined in FIPS-205 section 10
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
 mod sha2;
 mod shake;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod sse2;
 
 use core::fmt::Debug;
 
@@ -15,10 +19,23 @@ pub use shake::*;
 use crate::{address::Address, PkSeed, SkPrf, SkSeed};
 
 /// A trait specifying the hash functions described in FIPS-205 section 10
-pub(crate) trait HashSuite: Sized + Clone + Debug + PartialEq + Eq {
+pub trait HashSuite: Sized + Clone + Debug + PartialEq + Eq {
+    /// The security parameter `n`: the length of hash values and seeds in bytes
     type N: ArraySize + Debug + Clone + PartialEq + Eq;
+    /// The length of the message digest computed by `h_msg` in bytes
     type M: ArraySize + Debug + Clone + PartialEq + Eq;
 
+    /// `PK.seed` along with any hashing state that depends only on it.
+    /// It is computed once per key and passed to `prf_sk`, `t`, `h` and `f` in place of the seed.
+    type PkSeedState: Clone
+        + Send
+        + Sync
+        + Debug
+        + PartialEq
+        + Eq
+        + From<PkSeed<Self::N>>
+        + AsRef<PkSeed<Self::N>>;
+
     /// Pseudorandom function that generates the randomizer for the randomized hashing of the message to be signed.
     fn prf_msg(
         sk_prf: &SkPrf<Self::N>,
@@ -36,7 +53,7 @@ pub(crate) trait HashSuite: Sized + Clone + Debug + PartialEq + Eq {
 
     /// PRF that is used to generate the secret values in WOTS+ and FORS private keys.
     fn prf_sk(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         sk_seed: &SkSeed<Self::N>,
         adrs: &impl Address,
     ) -> Array<u8, Self::N>;
@@ -44,7 +61,7 @@ pub(crate) trait HashSuite: Sized + Clone + Debug + PartialEq + Eq {
     /// A hash function that maps an L*N-byte string to an N-byte string. Used for the chain function in WOTS+.
     /// Message length must be a multiple of `N`. Panics otherwise.
     fn t<L: ArraySize>(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m: &Array<Array<u8, Self::N>, L>,
     ) -> Array<u8, Self::N>;
@@ -52,7 +69,7 @@ pub(crate) trait HashSuite: Sized + Clone + Debug + PartialEq + Eq {
     /// Specialization of `t` for 2*chunk messages. Used to compute Merkle tree nodes.
     /// May be reimplemented for better performance.
     fn h(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m1: &Array<u8, Self::N>,
         m2: &Array<u8, Self::N>,
@@ -61,10 +78,36 @@ pub(crate) trait HashSuite: Sized + Clone + Debug + PartialEq + Eq {
     /// Hash function that takes an N-byte input to an N-byte output
     /// Used for the WOTS+ chain function
     fn f(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m: &Array<u8, Self::N>,
     ) -> Array<u8, Self::N>;
+
+    /// Four independent evaluations of `f`.
+    /// May be reimplemented to compute them in parallel.
+    ///
+    /// This is called from the recursive tree hashing functions, so implementations are marked
+    /// `#[inline(never)]` to keep their buffers out of every level of the recursion's stack.
+    #[inline(never)]
+    fn f_x4(
+        pk_seed: &Self::PkSeedState,
+        adrs: &[impl Address; 4],
+        m: &[Array<u8, Self::N>; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        core::array::from_fn(|i| Self::f(pk_seed, &adrs[i], &m[i]))
+    }
+
+    /// Four independent evaluations of `h`.
+    /// May be reimplemented to compute them in parallel.
+    #[inline(never)]
+    fn h_x4(
+        pk_seed: &Self::PkSeedState,
+        adrs: &[impl Address; 4],
+        m1: &[Array<u8, Self::N>; 4],
+        m2: &[Array<u8, Self::N>; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        core::array::from_fn(|i| Self::h(pk_seed, &adrs[i], &m1[i], &m2[i]))
+    }
 }
 
 #[cfg(test)]
@@ -115,4 +158,28 @@ mod tests {
     fn h_msg_sha2_256_f() {
         h_msg::<Sha2_256f>(&hex!("8c86dfb66392d1b647df0deab90be68fb6f988513e84d3ef75fa68591122bb5d74f6413672db5164e56492b7ca2c2e0335"));
     }
+
+    fn test_f_h_x4<H: HashSuite>() {
+        use crate::address::WotsHash;
+        use rand::RngCore;
+
+        let mut rng = rand::thread_rng();
+        let pk_seed = PkSeed::new(&mut rng).into();
+        let adrs = [0, 1, 2, 3].map(|i| {
+            let mut adrs = WotsHash::default();
+            adrs.chain_adrs.set(i);
+            adrs.hash_adrs.set(rng.next_u32());
+            adrs
+        });
+        let m1 = [(); 4].map(|()| PkSeed::<H::N>::new(&mut rng).0);
+        let m2 = [(); 4].map(|()| PkSeed::<H::N>::new(&mut rng).0);
+
+        let f = H::f_x4(&pk_seed, &adrs, &m1);
+        let h = H::h_x4(&pk_seed, &adrs, &m1, &m2);
+        for i in 0..4 {
+            assert_eq!(f[i], H::f(&pk_seed, &adrs[i], &m1[i]));
+            assert_eq!(h[i], H::h(&pk_seed, &adrs[i], &m1[i], &m2[i]));
+        }
+    }
+    crate::util::macros::test_parameter_sets!(test_f_h_x4);
 }
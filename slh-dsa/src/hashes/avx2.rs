@@ -0,0 +1,218 @@
+//! Four-way SHAKE256 using AVX2
+//!
+//! The messages hashed by `F` and `H` fit in a single SHAKE256 block, so four independent calls
+//! can be computed at once by holding the same state word of each message in the 64-bit lanes of
+//! one 256-bit register. Callers check for AVX2 at runtime with [`Avx2::detect`] and fall back to
+//! the scalar hash function when it is unavailable.
+
+#[allow(clippy::wildcard_imports)]
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[allow(clippy::wildcard_imports)]
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use hybrid_array::{Array, ArraySize};
+
+cpufeatures::new!(avx2_cpuid, "avx2");
+
+/// The SHAKE256 rate in bytes
+const SHAKE256_RATE: usize = 136;
+
+/// Proof that the running CPU supports AVX2
+#[derive(Clone, Copy)]
+pub(crate) struct Avx2(());
+
+#[allow(clippy::unused_self)] // `self` is the proof of AVX2 support
+impl Avx2 {
+    /// Check for AVX2 support. The result of the CPUID check is cached.
+    pub(crate) fn detect() -> Option<Self> {
+        avx2_cpuid::get().then_some(Avx2(()))
+    }
+
+    /// Hash four messages of the same length with SHAKE256, producing `N` bytes for each.
+    /// The message of lane `i` is the concatenation of `parts(i)`, and must be shorter than the
+    /// SHAKE256 rate.
+    pub(crate) fn shake256<'a, N: ArraySize, const K: usize>(
+        self,
+        parts: impl Fn(usize) -> [&'a [u8]; K],
+    ) -> [Array<u8, N>; 4] {
+        let (blocks, len) = concat::<SHAKE256_RATE, K>(parts);
+        assert!(len < SHAKE256_RATE && N::USIZE <= SHAKE256_RATE);
+        // SAFETY: AVX2 support was checked by `detect`
+        unsafe { shake256_x4(&blocks, len) }
+    }
+}
+
+/// Copy the message of each lane into a zeroed buffer, returning the buffers and message length
+pub(super) fn concat<'a, const B: usize, const K: usize>(
+    parts: impl Fn(usize) -> [&'a [u8]; K],
+) -> ([[u8; B]; 4], usize) {
+    let mut blocks = [[0; B]; 4];
+    let mut lens = [0; 4];
+    for (i, block) in blocks.iter_mut().enumerate() {
+        for part in parts(i) {
+            block[lens[i]..lens[i] + part.len()].copy_from_slice(part);
+            lens[i] += part.len();
+        }
+    }
+    debug_assert!(lens.iter().all(|&len| len == lens[0]));
+    (blocks, lens[0])
+}
+
+/// Reinterpret the bits of a `u64` as an `i64`, as taken by the `set` intrinsics
+fn i64_bits(x: u64) -> i64 {
+    i64::from_ne_bytes(x.to_ne_bytes())
+}
+
+const KECCAK_RC: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// Rotation offsets of the ρ step, indexed by `x + 5 * y`
+const KECCAK_RHO: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rotl64(x: __m256i, n: u32) -> __m256i {
+    let left = _mm_set_epi64x(0, i64::from(n));
+    let right = _mm_set_epi64x(0, i64::from(64 - n));
+    _mm256_or_si256(_mm256_sll_epi64(x, left), _mm256_srl_epi64(x, right))
+}
+
+/// Keccak-f[1600] applied to four states, with lane `i` of each register belonging to state `i`
+#[allow(clippy::needless_range_loop)] // Indices follow the x, y coordinates of the specification
+#[target_feature(enable = "avx2")]
+unsafe fn keccak_f1600_x4(a: &mut [__m256i; 25]) {
+    for rc in KECCAK_RC {
+        // θ
+        let mut c = [_mm256_setzero_si256(); 5];
+        for x in 0..5 {
+            c[x] = _mm256_xor_si256(
+                _mm256_xor_si256(_mm256_xor_si256(a[x], a[x + 5]), a[x + 10]),
+                _mm256_xor_si256(a[x + 15], a[x + 20]),
+            );
+        }
+        for x in 0..5 {
+            let d = _mm256_xor_si256(c[(x + 4) % 5], rotl64(c[(x + 1) % 5], 1));
+            for y in 0..5 {
+                a[x + 5 * y] = _mm256_xor_si256(a[x + 5 * y], d);
+            }
+        }
+
+        // ρ and π
+        let mut b = [_mm256_setzero_si256(); 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = rotl64(a[x + 5 * y], KECCAK_RHO[x + 5 * y]);
+            }
+        }
+
+        // χ
+        for y in 0..5 {
+            for x in 0..5 {
+                a[x + 5 * y] = _mm256_xor_si256(
+                    b[x + 5 * y],
+                    _mm256_andnot_si256(b[(x + 1) % 5 + 5 * y], b[(x + 2) % 5 + 5 * y]),
+                );
+            }
+        }
+
+        // ι
+        a[0] = _mm256_xor_si256(a[0], _mm256_set1_epi64x(i64_bits(rc)));
+    }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn shake256_x4<N: ArraySize>(
+    blocks: &[[u8; SHAKE256_RATE]; 4],
+    len: usize,
+) -> [Array<u8, N>; 4] {
+    let word = |i: usize, k: usize| {
+        i64::from_le_bytes(blocks[i][8 * k..8 * k + 8].try_into().expect("8 bytes"))
+    };
+    let mut state = [_mm256_setzero_si256(); 25];
+    for (k, lane) in state[..SHAKE256_RATE / 8].iter_mut().enumerate() {
+        *lane = _mm256_set_epi64x(word(3, k), word(2, k), word(1, k), word(0, k));
+    }
+
+    // SHAKE padding: the domain separator 1111 and pad10*1
+    let pad = _mm256_set1_epi64x(0x1f << (8 * (len % 8)));
+    state[len / 8] = _mm256_xor_si256(state[len / 8], pad);
+    let last = SHAKE256_RATE / 8 - 1;
+    state[last] = _mm256_xor_si256(state[last], _mm256_set1_epi64x(i64::MIN));
+
+    keccak_f1600_x4(&mut state);
+
+    let mut out = [(); 4].map(|()| Array::<u8, N>::default());
+    for (k, lane) in state[..N::USIZE.div_ceil(8)].iter().enumerate() {
+        let mut words = [0u64; 4];
+        _mm256_storeu_si256(words.as_mut_ptr().cast(), *lane);
+        for (out, word) in out.iter_mut().zip(words) {
+            let end = N::USIZE.min(8 * k + 8);
+            out[8 * k..end].copy_from_slice(&word.to_le_bytes()[..end - 8 * k]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use digest::ExtendableOutput;
+    use hybrid_array::typenum::U32;
+
+    /// Four distinct messages, to be truncated to the length under test
+    fn messages() -> [[u8; SHAKE256_RATE]; 4] {
+        [1u8, 3, 5, 7].map(|step| {
+            let mut byte = 0u8;
+            core::array::from_fn(|_| {
+                byte = byte.wrapping_add(step);
+                byte
+            })
+        })
+    }
+
+    #[test]
+    fn test_shake256_x4() {
+        let Some(avx2) = Avx2::detect() else {
+            return;
+        };
+        let msgs = messages();
+        for len in 0..SHAKE256_RATE {
+            let split = len / 3;
+            let out: [Array<u8, U32>; 4] =
+                avx2.shake256(|i| [&msgs[i][..split], &msgs[i][split..len]]);
+            for (msg, out) in msgs.iter().zip(out) {
+                let mut expected = [0u8; 32];
+                sha3::Shake256::digest_xof(&msg[..len], &mut expected);
+                assert_eq!(out.as_slice(), expected, "length {len}");
+            }
+        }
+    }
+}
@@ -3,7 +3,10 @@
 
 use core::fmt::Debug;
 
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::sse2::{sha256_midstate, Sse2};
 use crate::hashes::HashSuite;
+use crate::sealed::Sealed;
 use crate::{
     address::Address, fors::ForsParams, hypertree::HypertreeParams, wots::WotsParams,
     xmss::XmssParams, ParameterSet,
@@ -34,6 +37,65 @@ fn mgf1<H: Digest, L: ArraySize>(seed: &[u8]) -> Array<u8, L> {
     result
 }
 
+/// The SHA-256 state after compressing the block `PK.seed || toByte(0, 64 - n)`, from which the
+/// four-way hashes continue
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn pk_seed_midstate<N: ArraySize>(pk_seed: &PkSeed<N>) -> [u32; 8] {
+    let mut block = [0u8; 64];
+    block[..N::USIZE].copy_from_slice(pk_seed.as_ref());
+    sha256_midstate(&block)
+}
+
+/// `PK.seed` with the SHA-256 state after absorbing the block `PK.seed || toByte(0, 64 - n)`,
+/// which begins every input to `prf_sk`, `t`, `h` and `f` in the category 1 parameter sets
+#[derive(Clone)]
+pub struct Sha2L1Seed<N: ArraySize> {
+    pk_seed: PkSeed<N>,
+    sha256: Sha256,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    sha256_state: [u32; 8],
+}
+
+impl<N: ArraySize> From<PkSeed<N>> for Sha2L1Seed<N>
+where
+    U64: core::ops::Sub<N>,
+    Diff<U64, N>: ArraySize,
+{
+    fn from(pk_seed: PkSeed<N>) -> Self {
+        let zeroes = Array::<u8, Diff<U64, N>>::default();
+        let sha256 = Sha256::new().chain_update(&pk_seed).chain_update(&zeroes);
+        Self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            sha256_state: pk_seed_midstate(&pk_seed),
+            pk_seed,
+            sha256,
+        }
+    }
+}
+
+impl<N: ArraySize> AsRef<PkSeed<N>> for Sha2L1Seed<N> {
+    fn as_ref(&self) -> &PkSeed<N> {
+        &self.pk_seed
+    }
+}
+
+impl<N: ArraySize + Debug> Debug for Sha2L1Seed<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Sha2L1Seed")
+            .field("pk_seed", &self.pk_seed)
+            .finish_non_exhaustive()
+    }
+}
+
+// The hash state is a function of the seed
+impl<N: ArraySize + PartialEq> PartialEq for Sha2L1Seed<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pk_seed == other.pk_seed
+    }
+}
+
+impl<N: ArraySize + Eq> Eq for Sha2L1Seed<N> {}
+
 /// Implementation of the component hash functions using SHA2 at Security Category 1
 ///
 /// Follows section 10.2 of FIPS-205
@@ -56,6 +118,7 @@ where
 {
     type N = N;
     type M = M;
+    type PkSeedState = Sha2L1Seed<N>;
 
     fn prf_msg(
         sk_prf: &SkPrf<Self::N>,
@@ -64,8 +127,9 @@ where
     ) -> Array<u8, Self::N> {
         let mut mac = Hmac::<Sha256>::new_from_slice(sk_prf.as_ref()).unwrap();
         mac.update(opt_rand.as_slice());
-        msg.iter()
-            .for_each(|msg_part| mac.update(msg_part.as_ref()));
+        for msg_part in msg {
+            mac.update(msg_part.as_ref());
+        }
         let result = mac.finalize().into_bytes();
         Array::clone_from_slice(&result[..Self::N::USIZE])
     }
@@ -80,21 +144,22 @@ where
         h.update(rand);
         h.update(pk_seed);
         h.update(pk_root);
-        msg.iter().for_each(|msg_part| h.update(msg_part.as_ref()));
+        for msg_part in msg {
+            h.update(msg_part.as_ref());
+        }
         let result = Array(h.finalize().into());
         let seed = rand.clone().concat(pk_seed.0.clone()).concat(result);
         mgf1::<Sha256, Self::M>(&seed)
     }
 
     fn prf_sk(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         sk_seed: &SkSeed<Self::N>,
         adrs: &impl Address,
     ) -> Array<u8, Self::N> {
-        let zeroes = Array::<u8, Diff<U64, N>>::default();
-        let hash = Sha256::new()
-            .chain_update(pk_seed)
-            .chain_update(&zeroes)
+        let hash = pk_seed
+            .sha256
+            .clone()
             .chain_update(adrs.compressed())
             .chain_update(sk_seed)
             .finalize();
@@ -102,30 +167,25 @@ where
     }
 
     fn t<L: ArraySize>(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m: &Array<Array<u8, Self::N>, L>,
     ) -> Array<u8, Self::N> {
-        let zeroes = Array::<u8, Diff<U64, N>>::default();
-        let mut sha = Sha256::new()
-            .chain_update(pk_seed)
-            .chain_update(&zeroes)
-            .chain_update(adrs.compressed());
+        let mut sha = pk_seed.sha256.clone().chain_update(adrs.compressed());
         m.iter().for_each(|x| sha.update(x.as_slice()));
         let hash = sha.finalize();
         Array::clone_from_slice(&hash[..Self::N::USIZE])
     }
 
     fn h(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m1: &Array<u8, Self::N>,
         m2: &Array<u8, Self::N>,
     ) -> Array<u8, Self::N> {
-        let zeroes = Array::<u8, Diff<U64, N>>::default();
-        let hash = Sha256::new()
-            .chain_update(pk_seed)
-            .chain_update(&zeroes)
+        let hash = pk_seed
+            .sha256
+            .clone()
             .chain_update(adrs.compressed())
             .chain_update(m1)
             .chain_update(m2)
@@ -134,19 +194,49 @@ where
     }
 
     fn f(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m: &Array<u8, Self::N>,
     ) -> Array<u8, Self::N> {
-        let zeroes = Array::<u8, Diff<U64, N>>::default();
-        let hash = Sha256::new()
-            .chain_update(pk_seed)
-            .chain_update(&zeroes)
+        let hash = pk_seed
+            .sha256
+            .clone()
             .chain_update(adrs.compressed())
             .chain_update(m)
             .finalize();
         Array::clone_from_slice(&hash[..Self::N::USIZE])
     }
+
+    #[inline(never)]
+    fn f_x4(
+        pk_seed: &Self::PkSeedState,
+        adrs: &[impl Address; 4],
+        m: &[Array<u8, Self::N>; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(sse2) = Sse2::detect_sha256() {
+            let adrs_c = adrs.each_ref().map(Address::compressed);
+            let hashes = sse2.sha256(&pk_seed.sha256_state, 64, |i| [&adrs_c[i], &m[i]]);
+            return hashes.map(|hash| Array::clone_from_slice(&hash[..Self::N::USIZE]));
+        }
+        core::array::from_fn(|i| Self::f(pk_seed, &adrs[i], &m[i]))
+    }
+
+    #[inline(never)]
+    fn h_x4(
+        pk_seed: &Self::PkSeedState,
+        adrs: &[impl Address; 4],
+        m1: &[Array<u8, Self::N>; 4],
+        m2: &[Array<u8, Self::N>; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(sse2) = Sse2::detect_sha256() {
+            let adrs_c = adrs.each_ref().map(Address::compressed);
+            let hashes = sse2.sha256(&pk_seed.sha256_state, 64, |i| [&adrs_c[i], &m1[i], &m2[i]]);
+            return hashes.map(|hash| Array::clone_from_slice(&hash[..Self::N::USIZE]));
+        }
+        core::array::from_fn(|i| Self::h(pk_seed, &adrs[i], &m1[i], &m2[i]))
+    }
 }
 
 /// SHA2 at L1 security with small signatures
@@ -165,11 +255,12 @@ impl HypertreeParams for Sha2_128s {
 impl ForsParams for Sha2_128s {
     type K = U<14>;
     type A = U<12>;
-    type MD = U<{ (12 * 14 + 7) / 8 }>;
+    type MD = U<{ (12 * 14usize).div_ceil(8) }>;
 }
 impl ParameterSet for Sha2_128s {
     const NAME: &'static str = "SLH-DSA-SHA2-128s";
 }
+impl Sealed for Sha2_128s {}
 
 /// SHA2 at L1 security with fast signatures
 pub type Sha2_128f = Sha2L1<U16, U34>;
@@ -192,6 +283,68 @@ impl ForsParams for Sha2_128f {
 impl ParameterSet for Sha2_128f {
     const NAME: &'static str = "SLH-DSA-SHA2-128f";
 }
+impl Sealed for Sha2_128f {}
+
+/// `PK.seed` with the SHA-256 and SHA-512 states after absorbing the blocks
+/// `PK.seed || toByte(0, 64 - n)` and `PK.seed || toByte(0, 128 - n)` respectively,
+/// which begin every input to `prf_sk`, `t`, `h` and `f` in the category 3 and 5 parameter sets
+#[derive(Clone)]
+pub struct Sha2L35Seed<N: ArraySize> {
+    pk_seed: PkSeed<N>,
+    sha256: Sha256,
+    sha512: Sha512,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    sha256_state: [u32; 8],
+}
+
+impl<N: ArraySize> From<PkSeed<N>> for Sha2L35Seed<N>
+where
+    U64: core::ops::Sub<N>,
+    Diff<U64, N>: ArraySize,
+    U128: core::ops::Sub<N>,
+    Diff<U128, N>: ArraySize,
+{
+    fn from(pk_seed: PkSeed<N>) -> Self {
+        let zeroes_256 = Array::<u8, Diff<U64, N>>::default();
+        let zeroes_512 = Array::<u8, Diff<U128, N>>::default();
+        let sha256 = Sha256::new()
+            .chain_update(&pk_seed)
+            .chain_update(&zeroes_256);
+        let sha512 = Sha512::new()
+            .chain_update(&pk_seed)
+            .chain_update(&zeroes_512);
+        Self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            sha256_state: pk_seed_midstate(&pk_seed),
+            pk_seed,
+            sha256,
+            sha512,
+        }
+    }
+}
+
+impl<N: ArraySize> AsRef<PkSeed<N>> for Sha2L35Seed<N> {
+    fn as_ref(&self) -> &PkSeed<N> {
+        &self.pk_seed
+    }
+}
+
+impl<N: ArraySize + Debug> Debug for Sha2L35Seed<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Sha2L35Seed")
+            .field("pk_seed", &self.pk_seed)
+            .finish_non_exhaustive()
+    }
+}
+
+// The hash states are a function of the seed
+impl<N: ArraySize + PartialEq> PartialEq for Sha2L35Seed<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pk_seed == other.pk_seed
+    }
+}
+
+impl<N: ArraySize + Eq> Eq for Sha2L35Seed<N> {}
 
 /// Implementation of the component hash functions using SHA2 at Security Category 3 and 5
 ///
@@ -217,6 +370,7 @@ where
 {
     type N = N;
     type M = M;
+    type PkSeedState = Sha2L35Seed<N>;
 
     fn prf_msg(
         sk_prf: &SkPrf<Self::N>,
@@ -225,8 +379,9 @@ where
     ) -> Array<u8, Self::N> {
         let mut mac = Hmac::<Sha512>::new_from_slice(sk_prf.as_ref()).unwrap();
         mac.update(opt_rand.as_slice());
-        msg.iter()
-            .for_each(|msg_part| mac.update(msg_part.as_ref()));
+        for msg_part in msg {
+            mac.update(msg_part.as_ref());
+        }
         let result = mac.finalize().into_bytes();
         Array::clone_from_slice(&result[..Self::N::USIZE])
     }
@@ -241,21 +396,22 @@ where
         h.update(rand);
         h.update(pk_seed);
         h.update(pk_root);
-        msg.iter().for_each(|msg_part| h.update(msg_part.as_ref()));
+        for msg_part in msg {
+            h.update(msg_part.as_ref());
+        }
         let result = Array(h.finalize().into());
         let seed = rand.clone().concat(pk_seed.0.clone()).concat(result);
         mgf1::<Sha512, Self::M>(&seed)
     }
 
     fn prf_sk(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         sk_seed: &SkSeed<Self::N>,
         adrs: &impl Address,
     ) -> Array<u8, Self::N> {
-        let zeroes = Array::<u8, Diff<U64, N>>::default();
-        let hash = Sha256::new()
-            .chain_update(pk_seed)
-            .chain_update(&zeroes)
+        let hash = pk_seed
+            .sha256
+            .clone()
             .chain_update(adrs.compressed())
             .chain_update(sk_seed)
             .finalize();
@@ -263,30 +419,25 @@ where
     }
 
     fn t<L: ArraySize>(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m: &Array<Array<u8, Self::N>, L>,
     ) -> Array<u8, Self::N> {
-        let zeroes = Array::<u8, Diff<U128, N>>::default();
-        let mut sha = Sha512::new()
-            .chain_update(pk_seed)
-            .chain_update(&zeroes)
-            .chain_update(adrs.compressed());
+        let mut sha = pk_seed.sha512.clone().chain_update(adrs.compressed());
         m.iter().for_each(|x| sha.update(x.as_slice()));
         let hash = sha.finalize();
         Array::clone_from_slice(&hash[..Self::N::USIZE])
     }
 
     fn h(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m1: &Array<u8, Self::N>,
         m2: &Array<u8, Self::N>,
     ) -> Array<u8, Self::N> {
-        let zeroes = Array::<u8, Diff<U128, N>>::default();
-        let hash = Sha512::new()
-            .chain_update(pk_seed)
-            .chain_update(&zeroes)
+        let hash = pk_seed
+            .sha512
+            .clone()
             .chain_update(adrs.compressed())
             .chain_update(m1)
             .chain_update(m2)
@@ -295,19 +446,33 @@ where
     }
 
     fn f(
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &impl Address,
         m: &Array<u8, Self::N>,
     ) -> Array<u8, Self::N> {
-        let zeroes = Array::<u8, Diff<U64, N>>::default();
-        let hash = Sha256::new()
-            .chain_update(pk_seed)
-            .chain_update(&zeroes)
+        let hash = pk_seed
+            .sha256
+            .clone()
             .chain_update(adrs.compressed())
             .chain_update(m)
             .finalize();
         Array::clone_from_slice(&hash[..Self::N::USIZE])
     }
+
+    #[inline(never)]
+    fn f_x4(
+        pk_seed: &Self::PkSeedState,
+        adrs: &[impl Address; 4],
+        m: &[Array<u8, Self::N>; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(sse2) = Sse2::detect_sha256() {
+            let adrs_c = adrs.each_ref().map(Address::compressed);
+            let hashes = sse2.sha256(&pk_seed.sha256_state, 64, |i| [&adrs_c[i], &m[i]]);
+            return hashes.map(|hash| Array::clone_from_slice(&hash[..Self::N::USIZE]));
+        }
+        core::array::from_fn(|i| Self::f(pk_seed, &adrs[i], &m[i]))
+    }
 }
 
 /// SHA2 at L3 security with small signatures
@@ -326,11 +491,12 @@ impl HypertreeParams for Sha2_192s {
 impl ForsParams for Sha2_192s {
     type K = U<17>;
     type A = U<14>;
-    type MD = U<{ (14 * 17 + 7) / 8 }>;
+    type MD = U<{ (14 * 17usize).div_ceil(8) }>;
 }
 impl ParameterSet for Sha2_192s {
     const NAME: &'static str = "SLH-DSA-SHA2-192s";
 }
+impl Sealed for Sha2_192s {}
 
 /// SHA2 at L3 security with fast signatures
 pub type Sha2_192f = Sha2L35<U24, U42>;
@@ -348,11 +514,12 @@ impl HypertreeParams for Sha2_192f {
 impl ForsParams for Sha2_192f {
     type K = U<33>;
     type A = U<8>;
-    type MD = U<{ (33 * 8 + 7) / 8 }>;
+    type MD = U<{ (33 * 8usize).div_ceil(8) }>;
 }
 impl ParameterSet for Sha2_192f {
     const NAME: &'static str = "SLH-DSA-SHA2-192f";
 }
+impl Sealed for Sha2_192f {}
 
 /// SHA2 at L5 security with small signatures
 pub type Sha2_256s = Sha2L35<U32, U47>;
@@ -370,11 +537,12 @@ impl HypertreeParams for Sha2_256s {
 impl ForsParams for Sha2_256s {
     type K = U<22>;
     type A = U<14>;
-    type MD = U<{ (14 * 22 + 7) / 8 }>;
+    type MD = U<{ (14 * 22usize).div_ceil(8) }>;
 }
 impl ParameterSet for Sha2_256s {
     const NAME: &'static str = "SLH-DSA-SHA2-256s";
 }
+impl Sealed for Sha2_256s {}
 
 /// SHA2 at L5 security with fast signatures
 pub type Sha2_256f = Sha2L35<U32, U49>;
@@ -392,8 +560,9 @@ impl HypertreeParams for Sha2_256f {
 impl ForsParams for Sha2_256f {
     type K = U<35>;
     type A = U<9>;
-    type MD = U<{ (35 * 9 + 7) / 8 }>;
+    type MD = U<{ (35 * 9usize).div_ceil(8) }>;
 }
 impl ParameterSet for Sha2_256f {
     const NAME: &'static str = "SLH-DSA-SHA2-256f";
 }
+impl Sealed for Sha2_256f {}
@@ -1,9 +1,12 @@
 use core::fmt::Debug;
 
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use super::avx2::Avx2;
 use crate::address::Address;
 use crate::fors::ForsParams;
 use crate::hashes::HashSuite;
 use crate::hypertree::HypertreeParams;
+use crate::sealed::Sealed;
 use crate::wots::WotsParams;
 use crate::xmss::XmssParams;
 use crate::{ParameterSet, PkSeed, SkPrf, SkSeed};
@@ -30,6 +33,7 @@ where
 {
     type N = N;
     type M = M;
+    type PkSeedState = PkSeed<N>;
 
     fn prf_msg(
         sk_prf: &SkPrf<Self::N>,
@@ -39,8 +43,9 @@ where
         let mut hasher = Shake256::default();
         hasher.update(sk_prf.as_ref());
         hasher.update(opt_rand.as_slice());
-        msg.iter()
-            .for_each(|msg_part| hasher.update(msg_part.as_ref()));
+        for msg_part in msg {
+            hasher.update(msg_part.as_ref());
+        }
         let mut output = Array::<u8, Self::N>::default();
         hasher.finalize_xof_into(&mut output);
         output
@@ -56,8 +61,9 @@ where
         hasher.update(rand.as_slice());
         hasher.update(pk_seed.as_ref());
         hasher.update(pk_root.as_ref());
-        msg.iter()
-            .for_each(|msg_part| hasher.update(msg_part.as_ref()));
+        for msg_part in msg {
+            hasher.update(msg_part.as_ref());
+        }
         let mut output = Array::<u8, Self::M>::default();
         hasher.finalize_xof_into(&mut output);
         output
@@ -122,6 +128,40 @@ where
         hasher.finalize_xof_into(&mut output);
         output
     }
+
+    #[inline(never)]
+    fn f_x4(
+        pk_seed: &PkSeed<Self::N>,
+        adrs: &[impl Address; 4],
+        m: &[Array<u8, Self::N>; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(avx2) = Avx2::detect() {
+            return avx2.shake256(|i| [pk_seed.as_ref(), adrs[i].as_ref(), m[i].as_slice()]);
+        }
+        core::array::from_fn(|i| Self::f(pk_seed, &adrs[i], &m[i]))
+    }
+
+    #[inline(never)]
+    fn h_x4(
+        pk_seed: &PkSeed<Self::N>,
+        adrs: &[impl Address; 4],
+        m1: &[Array<u8, Self::N>; 4],
+        m2: &[Array<u8, Self::N>; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(avx2) = Avx2::detect() {
+            return avx2.shake256(|i| {
+                [
+                    pk_seed.as_ref(),
+                    adrs[i].as_ref(),
+                    m1[i].as_slice(),
+                    m2[i].as_slice(),
+                ]
+            });
+        }
+        core::array::from_fn(|i| Self::h(pk_seed, &adrs[i], &m1[i], &m2[i]))
+    }
 }
 
 // TODO: Consolidate parameters between Shake and SHA2 instances
@@ -142,11 +182,12 @@ impl HypertreeParams for Shake128s {
 impl ForsParams for Shake128s {
     type K = U<14>;
     type A = U<12>;
-    type MD = U<{ (12 * 14 + 7) / 8 }>;
+    type MD = U<{ (12 * 14usize).div_ceil(8) }>;
 }
 impl ParameterSet for Shake128s {
     const NAME: &'static str = "SLH-DSA-SHAKE-128s";
 }
+impl Sealed for Shake128s {}
 
 /// SHAKE256 at L1 security with fast signatures
 pub type Shake128f = Shake<U16, U34>;
@@ -169,6 +210,7 @@ impl ForsParams for Shake128f {
 impl ParameterSet for Shake128f {
     const NAME: &'static str = "SLH-DSA-SHAKE-128f";
 }
+impl Sealed for Shake128f {}
 
 /// SHAKE256 at L3 security with small signatures
 pub type Shake192s = Shake<U24, U39>;
@@ -186,11 +228,12 @@ impl HypertreeParams for Shake192s {
 impl ForsParams for Shake192s {
     type K = U<17>;
     type A = U<14>;
-    type MD = U<{ (14 * 17 + 7) / 8 }>;
+    type MD = U<{ (14 * 17usize).div_ceil(8) }>;
 }
 impl ParameterSet for Shake192s {
     const NAME: &'static str = "SLH-DSA-SHAKE-192s";
 }
+impl Sealed for Shake192s {}
 
 /// SHAKE256 at L3 security with fast signatures
 pub type Shake192f = Shake<U24, U42>;
@@ -208,11 +251,12 @@ impl HypertreeParams for Shake192f {
 impl ForsParams for Shake192f {
     type K = U<33>;
     type A = U<8>;
-    type MD = U<{ (33 * 8 + 7) / 8 }>;
+    type MD = U<{ (33 * 8usize).div_ceil(8) }>;
 }
 impl ParameterSet for Shake192f {
     const NAME: &'static str = "SLH-DSA-SHAKE-192f";
 }
+impl Sealed for Shake192f {}
 
 /// SHAKE256 at L5 security with small signatures
 pub type Shake256s = Shake<U32, U47>;
@@ -230,11 +274,12 @@ impl HypertreeParams for Shake256s {
 impl ForsParams for Shake256s {
     type K = U<22>;
     type A = U<14>;
-    type MD = U<{ (14 * 22 + 7) / 8 }>;
+    type MD = U<{ (14 * 22usize).div_ceil(8) }>;
 }
 impl ParameterSet for Shake256s {
     const NAME: &'static str = "SLH-DSA-SHAKE-256s";
 }
+impl Sealed for Shake256s {}
 
 /// SHAKE256 at L5 security with fast signatures
 pub type Shake256f = Shake<U32, U49>;
@@ -252,11 +297,12 @@ impl HypertreeParams for Shake256f {
 impl ForsParams for Shake256f {
     type K = U<35>;
     type A = U<9>;
-    type MD = U<{ (35 * 9 + 7) / 8 }>;
+    type MD = U<{ (35 * 9usize).div_ceil(8) }>;
 }
 impl ParameterSet for Shake256f {
     const NAME: &'static str = "SLH-DSA-SHAKE-256f";
 }
+impl Sealed for Shake256f {}
 
 #[cfg(test)]
 mod tests {
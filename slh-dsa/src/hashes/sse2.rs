@@ -0,0 +1,297 @@
+//! Four-way SHA-256 using SSE2
+//!
+//! The messages hashed by `F` and `H` fit in two SHA-256 blocks, so four independent calls can
+//! be computed at once by holding the same state word of each message in the 32-bit lanes of one
+//! 128-bit register. SSE2 is part of x86-64 and of nearly every 32-bit x86 CPU still in use, but
+//! callers check for it at runtime with [`Sse2::detect_sha256`], which also declines when the
+//! CPU has the SHA extensions, and fall back to the scalar hash function.
+
+#[allow(clippy::wildcard_imports)]
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[allow(clippy::wildcard_imports)]
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::avx2::concat;
+
+cpufeatures::new!(sse2_cpuid, "sse2");
+cpufeatures::new!(sha_ni_cpuid, "sha");
+
+/// The longest message which SHA-256 pads to two blocks
+const SHA256_MAX_LEN: usize = 2 * 64 - 9;
+
+/// Proof that the running CPU supports SSE2
+#[derive(Clone, Copy)]
+pub(crate) struct Sse2(());
+
+#[allow(clippy::unused_self)] // `self` is the proof of SSE2 support
+impl Sse2 {
+    /// Check for SSE2 support where four-way SHA-256 is worthwhile. When the CPU has the SHA
+    /// extensions the scalar `sha2` backend is faster than four lanes of the vector code, so
+    /// this returns `None`. The result of the CPUID check is cached.
+    pub(crate) fn detect_sha256() -> Option<Self> {
+        if sha_ni_cpuid::get() {
+            return None;
+        }
+        sse2_cpuid::get().then_some(Sse2(()))
+    }
+
+    /// Hash four messages of the same length with SHA-256, continuing from `state`, the state of
+    /// every lane after compressing the first `offset` bytes of its message. `offset` must be a
+    /// multiple of the block size.
+    /// The rest of the message of lane `i` is the concatenation of `parts(i)`, and must be at most
+    /// 119 bytes.
+    pub(crate) fn sha256<'a, const K: usize>(
+        self,
+        state: &[u32; 8],
+        offset: usize,
+        parts: impl Fn(usize) -> [&'a [u8]; K],
+    ) -> [[u8; 32]; 4] {
+        let (blocks, len) = concat::<{ 2 * 64 }, K>(parts);
+        assert!(len <= SHA256_MAX_LEN && offset % 64 == 0);
+        // SAFETY: SSE2 support was checked by `detect_sha256`
+        unsafe { sha256_x4(state, offset, &blocks, len) }
+    }
+}
+
+/// The SHA-256 state after compressing `block`, the first block of a message, to be passed to
+/// [`Sse2::sha256`] with an offset of 64
+pub(crate) fn sha256_midstate(block: &[u8; 64]) -> [u32; 8] {
+    let mut state = SHA256_IV;
+    sha2::compress256(&mut state, core::slice::from_ref(block));
+    state
+}
+
+/// Reinterpret the bits of a `u32` as an `i32`, as taken by the `set` intrinsics
+fn i32_bits(x: u32) -> i32 {
+    i32::from_ne_bytes(x.to_ne_bytes())
+}
+
+const SHA256_K: [u32; 64] = [
+    0x428a_2f98,
+    0x7137_4491,
+    0xb5c0_fbcf,
+    0xe9b5_dba5,
+    0x3956_c25b,
+    0x59f1_11f1,
+    0x923f_82a4,
+    0xab1c_5ed5,
+    0xd807_aa98,
+    0x1283_5b01,
+    0x2431_85be,
+    0x550c_7dc3,
+    0x72be_5d74,
+    0x80de_b1fe,
+    0x9bdc_06a7,
+    0xc19b_f174,
+    0xe49b_69c1,
+    0xefbe_4786,
+    0x0fc1_9dc6,
+    0x240c_a1cc,
+    0x2de9_2c6f,
+    0x4a74_84aa,
+    0x5cb0_a9dc,
+    0x76f9_88da,
+    0x983e_5152,
+    0xa831_c66d,
+    0xb003_27c8,
+    0xbf59_7fc7,
+    0xc6e0_0bf3,
+    0xd5a7_9147,
+    0x06ca_6351,
+    0x1429_2967,
+    0x27b7_0a85,
+    0x2e1b_2138,
+    0x4d2c_6dfc,
+    0x5338_0d13,
+    0x650a_7354,
+    0x766a_0abb,
+    0x81c2_c92e,
+    0x9272_2c85,
+    0xa2bf_e8a1,
+    0xa81a_664b,
+    0xc24b_8b70,
+    0xc76c_51a3,
+    0xd192_e819,
+    0xd699_0624,
+    0xf40e_3585,
+    0x106a_a070,
+    0x19a4_c116,
+    0x1e37_6c08,
+    0x2748_774c,
+    0x34b0_bcb5,
+    0x391c_0cb3,
+    0x4ed8_aa4a,
+    0x5b9c_ca4f,
+    0x682e_6ff3,
+    0x748f_82ee,
+    0x78a5_636f,
+    0x84c8_7814,
+    0x8cc7_0208,
+    0x90be_fffa,
+    0xa450_6ceb,
+    0xbef9_a3f7,
+    0xc671_78f2,
+];
+
+const SHA256_IV: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn add(a: __m128i, b: __m128i) -> __m128i {
+    _mm_add_epi32(a, b)
+}
+
+macro_rules! rotr32 {
+    ($x:expr, $n:literal) => {{
+        let x = $x;
+        _mm_or_si128(_mm_srli_epi32::<$n>(x), _mm_slli_epi32::<{ 32 - $n }>(x))
+    }};
+}
+
+/// The SHA-256 compression function applied to four states, with lane `i` of each register
+/// belonging to state `i`
+#[allow(clippy::many_single_char_names)] // Names follow FIPS 180-4
+#[target_feature(enable = "sse2")]
+unsafe fn sha256_compress_x4(state: &mut [__m128i; 8], w: &mut [__m128i; 16]) {
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (t, k) in SHA256_K.into_iter().enumerate() {
+        if t >= 16 {
+            let w15 = w[(t - 15) % 16];
+            let w2 = w[(t - 2) % 16];
+            let s0 = _mm_xor_si128(
+                _mm_xor_si128(rotr32!(w15, 7), rotr32!(w15, 18)),
+                _mm_srli_epi32::<3>(w15),
+            );
+            let s1 = _mm_xor_si128(
+                _mm_xor_si128(rotr32!(w2, 17), rotr32!(w2, 19)),
+                _mm_srli_epi32::<10>(w2),
+            );
+            w[t % 16] = add(add(w[t % 16], s0), add(w[(t - 7) % 16], s1));
+        }
+
+        let s1 = _mm_xor_si128(_mm_xor_si128(rotr32!(e, 6), rotr32!(e, 11)), rotr32!(e, 25));
+        let ch = _mm_xor_si128(_mm_and_si128(e, f), _mm_andnot_si128(e, g));
+        let t1 = add(
+            add(add(h, s1), add(ch, _mm_set1_epi32(i32_bits(k)))),
+            w[t % 16],
+        );
+        let s0 = _mm_xor_si128(_mm_xor_si128(rotr32!(a, 2), rotr32!(a, 13)), rotr32!(a, 22));
+        let maj = _mm_xor_si128(_mm_and_si128(a, _mm_xor_si128(b, c)), _mm_and_si128(b, c));
+        let t2 = add(s0, maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = add(d, t1);
+        d = c;
+        c = b;
+        b = a;
+        a = add(t1, t2);
+    }
+    for (s, x) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = add(*s, x);
+    }
+}
+
+#[target_feature(enable = "sse2")]
+unsafe fn sha256_x4(
+    state: &[u32; 8],
+    offset: usize,
+    blocks: &[[u8; 2 * 64]; 4],
+    len: usize,
+) -> [[u8; 32]; 4] {
+    // Padding: a single one bit, zeroes, and the message length in bits
+    let n_blocks = (len + 9).div_ceil(64);
+    let mut blocks = *blocks;
+    let bit_len = u64::try_from(8 * (offset + len)).expect("message length fits in u64");
+    for block in &mut blocks {
+        block[len] = 0x80;
+        block[64 * n_blocks - 8..64 * n_blocks].copy_from_slice(&bit_len.to_be_bytes());
+    }
+
+    let mut state = state.map(|x| _mm_set1_epi32(i32_bits(x)));
+    for n in 0..n_blocks {
+        let word = |i: usize, t: usize| {
+            let offset = 64 * n + 4 * t;
+            i32::from_be_bytes(blocks[i][offset..offset + 4].try_into().expect("4 bytes"))
+        };
+        let mut w = [_mm_setzero_si128(); 16];
+        for (t, w) in w.iter_mut().enumerate() {
+            *w = _mm_set_epi32(word(3, t), word(2, t), word(1, t), word(0, t));
+        }
+        sha256_compress_x4(&mut state, &mut w);
+    }
+
+    let mut out = [[0u8; 32]; 4];
+    for (j, word) in state.iter().enumerate() {
+        let mut words = [0u32; 4];
+        _mm_storeu_si128(words.as_mut_ptr().cast(), *word);
+        for (out, word) in out.iter_mut().zip(words) {
+            out[4 * j..4 * j + 4].copy_from_slice(&word.to_be_bytes());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use digest::Digest;
+
+    /// Four distinct messages, to be truncated to the length under test
+    fn messages() -> [[u8; SHA256_MAX_LEN]; 4] {
+        [1u8, 3, 5, 7].map(|step| {
+            let mut byte = 0u8;
+            core::array::from_fn(|_| {
+                byte = byte.wrapping_add(step);
+                byte
+            })
+        })
+    }
+
+    #[test]
+    fn test_sha256_x4() {
+        let Some(sse2) = Sse2::detect_sha256() else {
+            return;
+        };
+        let msgs = messages();
+        for len in 0..=SHA256_MAX_LEN {
+            let split = len / 3;
+            let out = sse2.sha256(&SHA256_IV, 0, |i| [&msgs[i][..split], &msgs[i][split..len]]);
+            for (msg, out) in msgs.iter().zip(out) {
+                assert_eq!(out.as_slice(), sha2::Sha256::digest(&msg[..len]).as_slice());
+            }
+        }
+    }
+
+    #[test]
+    fn test_sha256_x4_midstate() {
+        let Some(sse2) = Sse2::detect_sha256() else {
+            return;
+        };
+        let prefix = [0xa5u8; 64];
+        let state = sha256_midstate(&prefix);
+        let msgs = messages();
+        for len in 0..=SHA256_MAX_LEN {
+            let out = sse2.sha256(&state, 64, |i| [&msgs[i][..len]]);
+            for (msg, out) in msgs.iter().zip(out) {
+                let expected = sha2::Sha256::new()
+                    .chain_update(prefix)
+                    .chain_update(&msg[..len])
+                    .finalize();
+                assert_eq!(out.as_slice(), expected.as_slice(), "length {len}");
+            }
+        }
+    }
+}
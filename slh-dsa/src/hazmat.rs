@@ -0,0 +1,66 @@
+//! Low-level components of SLH-DSA: WOTS+, XMSS, FORS, the hypertree and hash addresses.
+//!
+//! # ⚠️ Warning: Hazmat!
+//!
+//! These are the building blocks described in sections 4 to 8 of FIPS-205, exposed for research
+//! on hash-based signatures. They are easy to misuse. WOTS+ is a one-time signature and FORS a
+//! few-time signature: signing a second message with the same key pair (the same seeds and
+//! address) reveals secret values and allows forgeries. XMSS on its own is only secure if each
+//! leaf is used at most once, and nothing here keeps track of which leaves have been used.
+//! Use [`SigningKey`](crate::SigningKey) for signatures.
+//!
+//! # Stability
+//!
+//! This module mirrors the internals of the crate and is exempt from semantic versioning. Any
+//! item in it may change or be removed in a minor or patch release. In particular, the address
+//! fields are `zerocopy` big-endian integers and the hash values are `hybrid-array` arrays, so
+//! breaking releases of those crates are breaking changes for this module.
+//!
+//! # Example
+//!
+//! ```
+//! use slh_dsa::hazmat::{address::WotsHash, wots::WotsParams, PkSeed, SkSeed};
+//! use slh_dsa::Shake128f;
+//!
+//! let sk_seed = SkSeed::try_from(&[1u8; 16][..]).unwrap();
+//! let pk_seed = PkSeed::try_from(&[2u8; 16][..]).unwrap().into();
+//!
+//! let mut adrs = WotsHash::default();
+//! adrs.key_pair_adrs.set(5);
+//!
+//! let msg = [3u8; 16].into();
+//! let pk = Shake128f::wots_pk_gen(&sk_seed, &pk_seed, &adrs);
+//! let sig = Shake128f::wots_sign(&msg, &sk_seed, &pk_seed, &adrs);
+//! assert_eq!(Shake128f::wots_pk_from_sig(&sig.0, &msg, &pk_seed, &adrs), pk);
+//! ```
+
+pub use crate::hashes::{HashSuite, Sha2L1Seed, Sha2L35Seed};
+pub use crate::signing_key::{SkPrf, SkSeed};
+pub use crate::verifying_key::PkSeed;
+
+/// Hash addresses (FIPS-205 section 4.2)
+pub mod address {
+    pub use crate::address::{
+        Address, ForsPrf, ForsRoots, ForsTree, HashTree, WotsHash, WotsPk, WotsPrf,
+    };
+}
+
+/// The WOTS+ one-time signature scheme (FIPS-205 section 5)
+pub mod wots {
+    pub use crate::wots::{WotsParams, WotsSig};
+}
+
+/// The XMSS many-time signature scheme (FIPS-205 section 6)
+pub mod xmss {
+    pub use crate::xmss::{XmssParams, XmssSig, XmssSigRef};
+}
+
+/// The SLH-DSA hypertree of XMSS trees (FIPS-205 section 7)
+pub mod hypertree {
+    pub use crate::hypertree::{HypertreeParams, HypertreeSig, HypertreeSigRef, XmssTreeCache};
+}
+
+/// The FORS few-time signature scheme (FIPS-205 section 8)
+pub mod fors {
+    pub use crate::fors::{ForsMTSig, ForsMTSigRef, ForsParams, ForsSignature, ForsSignatureRef};
+}
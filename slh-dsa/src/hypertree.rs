@@ -1,19 +1,26 @@
-use crate::{signing_key::SkSeed, PkSeed};
+use crate::signing_key::SkSeed;
 use core::fmt::Debug;
 use hybrid_array::{Array, ArraySize};
 use typenum::Unsigned;
 
 use crate::{
     address::WotsHash,
-    xmss::{XmssParams, XmssSig},
+    xmss::{XmssParams, XmssSig, XmssSigRef},
 };
 
+#[cfg(feature = "alloc")]
+use crate::xmss::XmssTree;
+
+/// A hypertree signature, made of an XMSS signature for each of the `D` layers, starting at the
+/// bottom
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct HypertreeSig<P: HypertreeParams>(Array<XmssSig<P>, P::D>);
+pub struct HypertreeSig<P: HypertreeParams>(pub Array<XmssSig<P>, P::D>);
 
 impl<P: HypertreeParams> HypertreeSig<P> {
+    /// The length of a serialized signature in bytes
     pub const SIZE: usize = XmssSig::<P>::SIZE * P::D::USIZE;
 
+    /// Serialize the signature to `buf`, which must be `SIZE` bytes long
     pub fn write_to(&self, buf: &mut [u8]) {
         debug_assert!(
             buf.len() == Self::SIZE,
@@ -27,6 +34,7 @@ impl<P: HypertreeParams> HypertreeSig<P> {
             .for_each(|(buf, sig)| sig.write_to(buf));
     }
 
+    /// Serialize the signature to a new `Vec`
     #[cfg(feature = "alloc")]
     pub fn to_vec(&self) -> Vec<u8> {
         let mut buf = vec![0u8; Self::SIZE];
@@ -50,80 +58,268 @@ impl<P: HypertreeParams> TryFrom<&[u8]> for HypertreeSig<P> {
     }
 }
 
+/// A borrowed view of a hypertree signature, either from a [`HypertreeSig`] or directly from bytes
+pub struct HypertreeSigRef<'a, P: HypertreeParams>(pub Array<XmssSigRef<'a, P>, P::D>);
+
+impl<P: HypertreeParams> Clone for HypertreeSigRef<'_, P> {
+    fn clone(&self) -> Self {
+        HypertreeSigRef(self.0.clone())
+    }
+}
+
+impl<'a, P: HypertreeParams> TryFrom<&'a [u8]> for HypertreeSigRef<'a, P> {
+    type Error = ();
+
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        if value.len() != HypertreeSig::<P>::SIZE {
+            return Err(());
+        }
+        let (chunks, _) = Array::<u8, P::N>::slice_as_chunks(value);
+        let sig = chunks
+            .chunks_exact(P::WotsSigLen::USIZE + P::HPrime::USIZE)
+            .map(XmssSigRef::from_chunks)
+            .collect();
+        Ok(HypertreeSigRef(sig))
+    }
+}
+
+impl<'a, P: HypertreeParams> From<&'a HypertreeSig<P>> for HypertreeSigRef<'a, P> {
+    fn from(sig: &'a HypertreeSig<P>) -> Self {
+        HypertreeSigRef(sig.0.iter().map(XmssSigRef::from).collect())
+    }
+}
+
+/// Authentication paths and roots of XMSS trees that were computed ahead of time
+pub trait XmssTreeCache<P: XmssParams>: Sync {
+    /// The authentication path of leaf `idx_leaf` of the tree at `adrs`, and the root of that
+    /// tree, if the tree is cached
+    #[allow(clippy::type_complexity)]
+    fn get(
+        &self,
+        adrs: &WotsHash,
+        idx_leaf: u32,
+    ) -> Option<(Array<Array<u8, P::N>, P::HPrime>, Array<u8, P::N>)>;
+}
+
+/// No trees are cached
+impl<P: XmssParams> XmssTreeCache<P> for () {
+    fn get(
+        &self,
+        _adrs: &WotsHash,
+        _idx_leaf: u32,
+    ) -> Option<(Array<Array<u8, P::N>, P::HPrime>, Array<u8, P::N>)> {
+        None
+    }
+}
+
+/// The XMSS trees of the top layers of a hypertree
+#[cfg(feature = "alloc")]
+pub(crate) struct HypertreeCache<P: HypertreeParams> {
+    // `layers[k]` holds the 2^(k * H') trees of layer `D - 1 - k`, ordered by tree address
+    layers: Vec<Vec<XmssTree<P>>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<P: HypertreeParams> HypertreeCache<P> {
+    /// Compute every tree on the top `layers` layers of the hypertree
+    pub(crate) fn new(sk_seed: &SkSeed<P::N>, pk_seed: &P::PkSeedState, layers: u32) -> Self {
+        debug_assert!(layers < P::D::U32, "the bottom layer is never cached");
+        let layers = (0..layers)
+            .map(|k| {
+                let tree = |idx_tree: u64| {
+                    let mut adrs = WotsHash::default();
+                    adrs.layer_adrs.set(P::D::U32 - 1 - k);
+                    adrs.tree_adrs_low.set(idx_tree);
+                    XmssTree::new(sk_seed, pk_seed, &adrs)
+                };
+                let trees = 0..1u64 << (k * P::HPrime::U32);
+
+                #[cfg(feature = "rayon")]
+                {
+                    use rayon::prelude::*;
+                    trees.into_par_iter().map(tree).collect()
+                }
+                #[cfg(not(feature = "rayon"))]
+                trees.map(tree).collect()
+            })
+            .collect();
+        HypertreeCache { layers }
+    }
+
+    /// The root of the top layer tree, which is the public key root
+    pub(crate) fn root(&self) -> Option<&Array<u8, P::N>> {
+        Some(self.layers.first()?.first()?.root())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<P: HypertreeParams> XmssTreeCache<P> for HypertreeCache<P> {
+    fn get(
+        &self,
+        adrs: &WotsHash,
+        idx_leaf: u32,
+    ) -> Option<(Array<Array<u8, P::N>, P::HPrime>, Array<u8, P::N>)> {
+        let k = P::D::U32 - 1 - adrs.layer_adrs.get();
+        let idx_tree = usize::try_from(adrs.tree_adrs_low.get()).ok()?;
+        let tree = self.layers.get(k as usize)?.get(idx_tree)?;
+        Some((tree.auth(idx_leaf), tree.root().clone()))
+    }
+}
+
+/// The hypertree of `D` layers of XMSS trees, where each tree signs the root of a tree on the
+/// layer below
 pub trait HypertreeParams: XmssParams + Sized {
+    /// The number of layers
     type D: ArraySize + Debug + Eq;
-    type H: ArraySize; // HPrime * D
-
+    /// The total height of the hypertree, `H' * D`
+    type H: ArraySize;
+
+    /// Algorithm 12: sign `m` with leaf `idx_leaf` of the XMSS tree `idx_tree` on the bottom
+    /// layer. Pass `&()` as `cache` to compute every tree.
+    ///
+    /// With the `rayon` feature the trees of all layers are built in parallel. The signature is
+    /// identical.
     fn ht_sign(
         m: &Array<u8, Self::N>,
         sk_seed: &SkSeed<Self::N>,
-        pk_seed: &PkSeed<Self::N>,
-        mut idx_tree: u64,
-        mut idx_leaf: u32,
+        pk_seed: &Self::PkSeedState,
+        idx_tree: u64,
+        idx_leaf: u32,
+        cache: &impl XmssTreeCache<Self>,
     ) -> HypertreeSig<Self> {
-        let mut adrs = WotsHash::default();
-        // Currently no parameter set supports more than 2^64 trees
-        // So tree_adrs_high is always unset
-        adrs.tree_adrs_low.set(idx_tree);
-
         // Pre-allocate the array - Option should have no overhead after optimization
-        let mut sig = Array::<_, Self::D>::default();
-
-        sig[0] = Some(Self::xmss_sign(m, sk_seed, pk_seed, idx_leaf, &adrs));
-        let mut root =
-            Self::xmss_pk_from_sig(idx_leaf, sig[0].as_ref().unwrap(), m, pk_seed, &adrs);
+        let mut sig = Array::<Option<XmssSig<Self>>, Self::D>::default();
+        sign_layers(
+            m,
+            sk_seed,
+            pk_seed,
+            idx_tree,
+            idx_leaf,
+            cache,
+            |j, layer_sig| {
+                sig[j] = Some(layer_sig);
+            },
+        );
+        HypertreeSig(sig.into_iter().map(Option::unwrap).collect())
+    }
 
-        for j in 1..Self::D::U32 {
-            // H' least significant bits of idx_leaf. H' is always less than 32 in FIPS-205 parameter sets
-            idx_leaf = (idx_tree & ((1 << Self::HPrime::U32) - 1))
-                .try_into()
-                .expect("H' is less than 32");
-            idx_tree >>= Self::HPrime::U64;
-
-            adrs.layer_adrs.set(j);
-            adrs.tree_adrs_low.set(idx_tree);
-
-            sig[j as usize] = Some(Self::xmss_sign(&root, sk_seed, pk_seed, idx_leaf, &adrs));
-            if j != Self::D::U32 - 1 {
-                root = Self::xmss_pk_from_sig(
-                    idx_leaf,
-                    sig[j as usize].as_ref().unwrap(),
-                    &root,
-                    pk_seed,
-                    &adrs,
-                );
-            }
-        }
-        // TODO: Validate that these clones get optimized away
-        HypertreeSig(sig.iter().cloned().map(Option::unwrap).collect())
+    /// Equivalent to `ht_sign`, but the XMSS signature of each layer is written to `buf` as soon
+    /// as it is computed, so the whole `HypertreeSig` is never held in memory.
+    #[doc(hidden)]
+    fn ht_sign_to(
+        m: &Array<u8, Self::N>,
+        sk_seed: &SkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
+        idx_tree: u64,
+        idx_leaf: u32,
+        cache: &impl XmssTreeCache<Self>,
+        buf: &mut [u8],
+    ) {
+        debug_assert!(
+            buf.len() == HypertreeSig::<Self>::SIZE,
+            "HT serialize length mismatch"
+        );
+        let mut bufs = buf.chunks_exact_mut(XmssSig::<Self>::SIZE);
+        sign_layers(
+            m,
+            sk_seed,
+            pk_seed,
+            idx_tree,
+            idx_leaf,
+            cache,
+            |_, layer_sig| {
+                layer_sig.write_to(bufs.next().expect("one buffer per layer"));
+            },
+        );
     }
 
+    /// Algorithm 13: verify the signature `sig` of `m` by leaf `idx_leaf` of the XMSS tree
+    /// `idx_tree` on the bottom layer, against the hypertree root `pk_root`
     fn ht_verify(
         m: &Array<u8, Self::N>,
-        sig: &HypertreeSig<Self>,
-        pk_seed: &PkSeed<Self::N>,
-        mut idx_tree: u64,
-        mut idx_leaf: u32,
+        sig: &HypertreeSigRef<'_, Self>,
+        pk_seed: &Self::PkSeedState,
+        idx_tree: u64,
+        idx_leaf: u32,
         pk_root: &Array<u8, Self::N>,
     ) -> bool {
+        let mut root = m.clone();
+        for ((adrs, idx_leaf), sig) in layers::<Self>(idx_tree, idx_leaf).zip(sig.0.iter()) {
+            root = Self::xmss_pk_from_sig(idx_leaf, *sig, &root, pk_seed, &adrs);
+        }
+        &root == pk_root
+    }
+}
+
+/// The address of the XMSS tree used on each layer of the hypertree, bottom first, along with
+/// the index of the leaf used in that tree
+fn layers<P: HypertreeParams>(
+    idx_tree: u64,
+    idx_leaf: u32,
+) -> impl Iterator<Item = (WotsHash, u32)> {
+    let mut next = (idx_tree, idx_leaf);
+    (0..P::D::U32).map(move |j| {
+        let (idx_tree, idx_leaf) = next;
         let mut adrs = WotsHash::default();
+        adrs.layer_adrs.set(j);
+        // Currently no parameter set supports more than 2^64 trees
+        // So tree_adrs_high is always unset
         adrs.tree_adrs_low.set(idx_tree);
 
-        let mut root = Self::xmss_pk_from_sig(idx_leaf, &sig.0[0], m, pk_seed, &adrs);
-
-        for j in 1..Self::D::U32 {
-            // H' least significant bits of idx_leaf. H' is always less than 32 in FIPS-205 parameter sets
-            idx_leaf = (idx_tree & ((1 << Self::HPrime::U32) - 1))
+        // The tree on the layer above signs with the leaf given by the H' least significant bits
+        // of idx_tree. H' is always less than 32 in FIPS-205 parameter sets
+        next = (
+            idx_tree >> P::HPrime::U64,
+            (idx_tree & ((1 << P::HPrime::U32) - 1))
                 .try_into()
-                .expect("H' is less than 32");
-            idx_tree >>= Self::HPrime::U64;
-
-            adrs.layer_adrs.set(j);
-            adrs.tree_adrs_low.set(idx_tree);
+                .expect("H' is less than 32"),
+        );
+        (adrs, idx_leaf)
+    })
+}
 
-            root = Self::xmss_pk_from_sig(idx_leaf, &sig.0[j as usize], &root, pk_seed, &adrs);
-        }
-        &root == pk_root
+/// Compute the XMSS signature of each layer of the hypertree in turn, bottom first, and pass it
+/// to `emit` along with the layer index.
+///
+/// The trees do not depend on the messages they sign, so each one is built, or found in `cache`,
+/// on its own, in parallel with the `rayon` feature. Only the WOTS+ signatures are chained from
+/// one layer to the next.
+fn sign_layers<P: HypertreeParams>(
+    m: &Array<u8, P::N>,
+    sk_seed: &SkSeed<P::N>,
+    pk_seed: &P::PkSeedState,
+    idx_tree: u64,
+    idx_leaf: u32,
+    cache: &impl XmssTreeCache<P>,
+    mut emit: impl FnMut(usize, XmssSig<P>),
+) {
+    // The authentication path of the leaf and the root of the tree, with the key pair address
+    // of the leaf set in `adrs`
+    let tree = |(mut adrs, idx_leaf): (WotsHash, u32)| {
+        adrs.key_pair_adrs.set(idx_leaf);
+        let (auth, root) = cache.get(&adrs, idx_leaf).unwrap_or_else(|| {
+            let auth = P::xmss_auth(sk_seed, pk_seed, idx_leaf, &adrs);
+            let leaf = P::xmss_node(sk_seed, idx_leaf, 0, pk_seed, &adrs);
+            let root = P::xmss_root(idx_leaf, leaf, &auth, pk_seed, &adrs);
+            (auth, root)
+        });
+        (adrs, auth, root)
+    };
+
+    #[cfg(feature = "rayon")]
+    let trees = {
+        use rayon::prelude::*;
+        let layers: Vec<_> = layers::<P>(idx_tree, idx_leaf).collect();
+        layers.into_par_iter().map(tree).collect::<Vec<_>>()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let trees = layers::<P>(idx_tree, idx_leaf).map(tree);
+
+    let mut m = m.clone();
+    for (j, (adrs, auth, root)) in trees.into_iter().enumerate() {
+        let sig = P::wots_sign(&m, sk_seed, pk_seed, &adrs);
+        emit(j, XmssSig { sig, auth });
+        m = root;
     }
 }
 
@@ -139,7 +335,7 @@ mod tests {
 
         let sk_seed = SkSeed::new(&mut rng);
 
-        let pk_seed = PkSeed::new(&mut rng);
+        let pk_seed = PkSeed::new(&mut rng).into();
 
         let mut m = Array::<u8, HTMode::N>::default();
         rng.fill(m.as_mut_slice());
@@ -157,10 +353,15 @@ mod tests {
 
         let pk_root = HTMode::xmss_node(&sk_seed, 0, HTMode::HPrime::U32, &pk_seed, &adrs);
 
-        let sig = HTMode::ht_sign(&m, &sk_seed, &pk_seed, idx_tree, idx_leaf);
+        let sig = HTMode::ht_sign(&m, &sk_seed, &pk_seed, idx_tree, idx_leaf, &());
 
         assert!(HTMode::ht_verify(
-            &m, &sig, &pk_seed, idx_tree, idx_leaf, &pk_root
+            &m,
+            &(&sig).into(),
+            &pk_seed,
+            idx_tree,
+            idx_leaf,
+            &pk_root
         ));
     }
 
@@ -171,7 +372,7 @@ mod tests {
 
         let sk_seed = SkSeed::new(&mut rng);
 
-        let pk_seed = PkSeed::new(&mut rng);
+        let pk_seed = PkSeed::new(&mut rng).into();
 
         let mut m = Array::<u8, HTMode::N>::default();
         rng.fill(m.as_mut_slice());
@@ -189,19 +390,69 @@ mod tests {
 
         let pk_root = HTMode::xmss_node(&sk_seed, 0, HTMode::HPrime::U32, &pk_seed, &adrs);
 
-        let sig = HTMode::ht_sign(&m, &sk_seed, &pk_seed, idx_tree, idx_leaf);
+        let sig = HTMode::ht_sign(&m, &sk_seed, &pk_seed, idx_tree, idx_leaf, &());
 
         // Tweak the message to ensure verification fails
         m[0] ^= 0xff; // Invert the first byte of the message
 
         // Verification should fail since the message was tweaked
         assert!(!HTMode::ht_verify(
-            &m, &sig, &pk_seed, idx_tree, idx_leaf, &pk_root
+            &m,
+            &(&sig).into(),
+            &pk_seed,
+            idx_tree,
+            idx_leaf,
+            &pk_root
         ));
     }
 
     test_parameter_sets!(test_ht_sign_verify_fail);
 
+    #[cfg(feature = "alloc")]
+    fn test_ht_sign_cached<HTMode: HypertreeParams>(layers: u32) {
+        let mut rng = thread_rng();
+
+        let sk_seed = SkSeed::new(&mut rng);
+
+        let pk_seed = PkSeed::new(&mut rng).into();
+
+        let mut m = Array::<u8, HTMode::N>::default();
+        rng.fill(m.as_mut_slice());
+
+        let idx_tree = rng.gen_range(
+            0..=(1u64
+                .wrapping_shl(HTMode::H::U32 - HTMode::HPrime::U32)
+                .wrapping_sub(1)),
+        );
+        let idx_leaf = rng.gen_range(0..(1 << (HTMode::HPrime::USIZE)));
+
+        let cache = HypertreeCache::<HTMode>::new(&sk_seed, &pk_seed, layers);
+
+        let mut adrs = WotsHash::default();
+        adrs.layer_adrs.set(HTMode::D::U32 - 1);
+        let pk_root = HTMode::xmss_node(&sk_seed, 0, HTMode::HPrime::U32, &pk_seed, &adrs);
+        assert_eq!(cache.root(), Some(&pk_root));
+
+        assert_eq!(
+            HTMode::ht_sign(&m, &sk_seed, &pk_seed, idx_tree, idx_leaf, &cache),
+            HTMode::ht_sign(&m, &sk_seed, &pk_seed, idx_tree, idx_leaf, &())
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    fn test_ht_sign_cached_top_layer<HTMode: HypertreeParams>() {
+        test_ht_sign_cached::<HTMode>(1);
+    }
+
+    #[cfg(feature = "alloc")]
+    test_parameter_sets!(test_ht_sign_cached_top_layer);
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ht_sign_cached_two_layers() {
+        test_ht_sign_cached::<Shake128f>(2);
+    }
+
     #[test]
     #[cfg(feature = "alloc")]
     fn test_ht_sign_kat() {
@@ -212,7 +463,7 @@ mod tests {
         let pk_seed = PkSeed(Array([2; 16]));
         let m = Array([3; 16]);
 
-        let sig = <Shake128f as HypertreeParams>::ht_sign(&m, &sk_seed, &pk_seed, 3, 5);
+        let sig = <Shake128f as HypertreeParams>::ht_sign(&m, &sk_seed, &pk_seed, 3, 5, &());
 
         let sig_flattened = sig.to_vec();
 
@@ -16,8 +16,11 @@
 //! to note that the signatures produced by the algorithm are much larger than classical schemes like EdDSA,
 //! ranging from over 7KB for the smallest parameter set to nearly 50KB at the largest
 //!
-//! This crate currently allocates signatures and intermediate values on the stack, which may cause problems for
-//! environments with limited stack space.
+//! [`SigningKey::try_sign_with_context`] and the [`signature`] traits return signatures by value,
+//! so they are allocated on the stack, which may cause problems for environments with limited
+//! stack space. [`SigningKey::try_sign_with_context_to`] writes the signature to a caller-provided
+//! buffer instead, using a small bounded amount of stack. Likewise, a [`SignatureRef`] borrows a
+//! serialized signature in place, so it can be verified without copying it.
 //!
 //!
 //! ```
@@ -45,13 +48,39 @@
 //!
 //! assert!(vk_deserialized.verify(message, &sig).is_ok())
 //! ```
+//!
+//! The pre-hash variant HashSLH-DSA is available through the [`signature::DigestSigner`] and
+//! [`signature::DigestVerifier`] traits, or with any [`PreHash`] function via
+//! [`SigningKey::try_sign_prehash_with_context`] and
+//! [`VerifyingKey::try_verify_prehash_with_context`].
+//!
+//! With the `pkcs8` feature, keys can be encoded as PKCS#8 private keys and SPKI public keys
+//! (and as PEM with the `pem` feature) using the traits re-exported from the `pkcs8` module.
+//!
+//! The `rayon` feature signs the independent FORS trees and the XMSS trees of each hypertree
+//! layer in parallel. Signatures are identical to those produced without it.
+//!
+//! A [`CachedSigningKey`] (with the `alloc` feature) stores the XMSS trees of the top hypertree
+//! layers, which are recomputed for every signature by a [`SigningKey`], to sign faster.
+//!
+//! The `zeroize` feature wipes the secret seeds of a [`SigningKey`] when it is dropped.
+//!
+//! The `hazmat` feature exposes the WOTS+, XMSS, FORS and hypertree components along with hash
+//! addresses in the `hazmat` module. It is not covered by semantic versioning.
 
 pub use signature;
 
 mod address;
+#[cfg(feature = "alloc")]
+mod cached_signing_key;
 mod fors;
 mod hashes;
+#[cfg(feature = "hazmat")]
+pub mod hazmat;
 mod hypertree;
+#[cfg(feature = "pkcs8")]
+pub mod pkcs8;
+mod prehash;
 mod signature_encoding;
 mod signing_key;
 mod util;
@@ -59,17 +88,31 @@ mod verifying_key;
 mod wots;
 mod xmss;
 
+#[cfg(feature = "alloc")]
+pub use cached_signing_key::*;
+pub use prehash::PreHash;
 pub use signature_encoding::*;
-pub use signing_key::*;
-pub use verifying_key::*;
+pub use signing_key::{SigningKey, SigningKeyLen};
+pub use verifying_key::{VerifyingKey, VerifyingKeyLen};
 
 use fors::ForsParams;
-pub use hashes::*;
+pub use hashes::{
+    Sha2L1, Sha2L35, Sha2_128f, Sha2_128s, Sha2_192f, Sha2_192s, Sha2_256f, Sha2_256s, Shake,
+    Shake128f, Shake128s, Shake192f, Shake192s, Shake256f, Shake256s,
+};
+use signing_key::{SkPrf, SkSeed};
+use verifying_key::PkSeed;
+
+mod sealed {
+    /// Prevents [`ParameterSet`](super::ParameterSet) from being implemented outside this crate,
+    /// as the `hazmat` feature makes its other supertraits public
+    pub trait Sealed {}
+}
 
 /// Specific parameters for each of the 12 FIPS parameter sets
 #[allow(private_bounds)] // Intentionally un-usable type
 pub trait ParameterSet:
-    ForsParams + SigningKeyLen + VerifyingKeyLen + SignatureLen + PartialEq + Eq
+    sealed::Sealed + ForsParams + SigningKeyLen + VerifyingKeyLen + SignatureLen + PartialEq + Eq
 {
     /// Human-readable name for parameter set, matching the FIPS-205 designations
     const NAME: &'static str;
@@ -177,4 +220,64 @@ mod tests {
         let sig = sk.try_sign_with_context(msg, ctx, None).unwrap();
         assert!(vk.try_verify_with_context(msg, wrong_ctx, &sig).is_err());
     }
+
+    #[test]
+    fn test_sign_verify_digest() {
+        use sha2::Digest;
+
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<Shake128f>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+
+        let sig: Signature<Shake128f> = sk.sign_digest(sha2::Sha256::new_with_prefix(msg));
+        vk.verify_digest(sha2::Sha256::new_with_prefix(msg), &sig)
+            .unwrap();
+        assert!(vk.verify(msg, &sig).is_err());
+        assert!(vk
+            .verify_digest(sha3::Sha3_256::new_with_prefix(msg), &sig)
+            .is_err());
+
+        let sig: Signature<Shake128f> =
+            sk.sign_digest_with_rng(&mut rng, sha2::Sha512::new_with_prefix(msg));
+        vk.verify_digest(sha2::Sha512::new_with_prefix(msg), &sig)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sign_verify_shake_prehash() {
+        use sha3::digest::{ExtendableOutput, Update};
+
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<Sha2_128f>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let ctx = b"Test context";
+
+        let mut digest = [0u8; 32];
+        sha3::Shake128::default()
+            .chain(b"Hello, world!")
+            .finalize_xof_into(&mut digest);
+        let sig = sk
+            .try_sign_prehash_with_context::<sha3::Shake128>(&digest, ctx, None)
+            .unwrap();
+        vk.try_verify_prehash_with_context::<sha3::Shake128>(&digest, ctx, &sig)
+            .unwrap();
+        assert!(vk
+            .try_verify_prehash_with_context::<sha3::Shake128>(&digest, b"Wrong context", &sig)
+            .is_err());
+        assert!(vk
+            .try_verify_prehash_with_context::<sha2::Sha256>(&digest, ctx, &sig)
+            .is_err());
+
+        // Digests of the wrong length and long contexts are rejected
+        assert!(sk
+            .try_sign_prehash_with_context::<sha3::Shake128>(&digest[1..], ctx, None)
+            .is_err());
+        assert!(vk
+            .try_verify_prehash_with_context::<sha3::Shake128>(&digest[1..], ctx, &sig)
+            .is_err());
+        assert!(sk
+            .try_sign_prehash_with_context::<sha3::Shake128>(&digest, &[0; 256], None)
+            .is_err());
+    }
 }
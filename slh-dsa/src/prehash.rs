@@ -0,0 +1,119 @@
+//! Pre-hash functions for HashML-DSA and HashSLH-DSA
+//!
+//! HashML-DSA (section 5.4 of FIPS-204) and HashSLH-DSA (section 10.2.2 of FIPS-205) sign the
+//! digest of a message rather than the message itself. The DER encoding of the hash function's
+//! OID is included in the signed message `M'`, so a signature produced with one hash function
+//! will not verify with another. Both standards approve the same hash functions and encode `M'`
+//! the same way.
+//!
+//! This file is shared by the `ml-dsa` and `slh-dsa` crates. The copy in `ml-dsa` is the
+//! original, and the copy in `slh-dsa` must be kept identical to it, which CI checks.
+use const_oid::db::{fips202, rfc5912};
+use const_oid::ObjectIdentifier;
+use hybrid_array::ArraySize;
+use typenum::{U28, U32, U48, U64};
+
+/// A hash function approved for use as a HashML-DSA or HashSLH-DSA pre-hash
+pub trait PreHash {
+    /// The object identifier of the hash function
+    const OID: ObjectIdentifier;
+
+    /// The length of the digest in bytes.
+    ///
+    /// For the SHAKE extendable-output functions this is fixed to the output length required by
+    /// FIPS-204 and FIPS-205: 256 bits for SHAKE128 and 512 bits for SHAKE256.
+    type OutputSize: ArraySize;
+}
+
+const ID_SHA_512_224: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.5");
+const ID_SHA_512_256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.6");
+
+/// The maximum length of a DER-encoded OID: tag, length and up to 39 bytes of content
+const MAX_OID_LEN: usize = 41;
+
+macro_rules! impl_pre_hash {
+    ($hash:ty, $oid:expr, $size:ty) => {
+        impl PreHash for $hash {
+            const OID: ObjectIdentifier = $oid;
+            type OutputSize = $size;
+        }
+    };
+}
+
+impl_pre_hash!(sha2::Sha224, rfc5912::ID_SHA_224, U28);
+impl_pre_hash!(sha2::Sha256, rfc5912::ID_SHA_256, U32);
+impl_pre_hash!(sha2::Sha384, rfc5912::ID_SHA_384, U48);
+impl_pre_hash!(sha2::Sha512, rfc5912::ID_SHA_512, U64);
+impl_pre_hash!(sha2::Sha512_224, ID_SHA_512_224, U28);
+impl_pre_hash!(sha2::Sha512_256, ID_SHA_512_256, U32);
+impl_pre_hash!(sha3::Sha3_224, fips202::ID_SHA_3_224, U28);
+impl_pre_hash!(sha3::Sha3_256, fips202::ID_SHA_3_256, U32);
+impl_pre_hash!(sha3::Sha3_384, fips202::ID_SHA_3_384, U48);
+impl_pre_hash!(sha3::Sha3_512, fips202::ID_SHA_3_512, U64);
+impl_pre_hash!(sha3::Shake128, fips202::ID_SHAKE_128, U32);
+impl_pre_hash!(sha3::Shake256, fips202::ID_SHAKE_256, U64);
+
+/// The header of the pre-hash message `M'`, excluding the context string itself
+pub(crate) struct PrehashHeader {
+    ctx_len: [u8; 2],
+    oid: [u8; MAX_OID_LEN],
+    oid_len: usize,
+}
+
+impl PrehashHeader {
+    /// Construct the domain separator and encoded OID for the hash function `H`.
+    ///
+    /// Returns `None` if the context string is longer than 255 bytes.
+    pub(crate) fn new<H: PreHash>(ctx: &[u8]) -> Option<Self> {
+        let ctx_len = u8::try_from(ctx.len()).ok()?;
+        let oid_bytes = H::OID.as_bytes();
+        let oid_len = 2 + oid_bytes.len();
+
+        // DER encoding of the OID: tag, short-form length, value
+        let mut oid = [0; MAX_OID_LEN];
+        oid[0] = 0x06;
+        oid[1] = u8::try_from(oid_bytes.len()).ok()?;
+        oid[2..oid_len].copy_from_slice(oid_bytes);
+
+        Some(PrehashHeader {
+            ctx_len: [1, ctx_len],
+            oid,
+            oid_len,
+        })
+    }
+
+    /// The leading domain separator byte and context length
+    pub(crate) fn ctx_len(&self) -> &[u8] {
+        &self.ctx_len
+    }
+
+    /// The DER-encoded OID of the hash function
+    pub(crate) fn oid(&self) -> &[u8] {
+        &self.oid[..self.oid_len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_encoded_oids() {
+        // Encodings from section 5.4.1 of FIPS-204 and section 10.2.2 of FIPS-205
+        let header = PrehashHeader::new::<sha2::Sha256>(&[]).unwrap();
+        assert_eq!(header.oid(), hex!("0609608648016503040201"));
+        let header = PrehashHeader::new::<sha2::Sha512>(&[]).unwrap();
+        assert_eq!(header.oid(), hex!("0609608648016503040203"));
+        let header = PrehashHeader::new::<sha3::Shake128>(b"ctx").unwrap();
+        assert_eq!(header.oid(), hex!("060960864801650304020B"));
+        assert_eq!(header.ctx_len(), [1, 3]);
+        let header = PrehashHeader::new::<sha3::Shake256>(&[]).unwrap();
+        assert_eq!(header.oid(), hex!("060960864801650304020C"));
+    }
+
+    #[test]
+    fn test_context_too_long() {
+        assert!(PrehashHeader::new::<sha2::Sha256>(&[0; 256]).is_none());
+    }
+}
@@ -1,10 +1,11 @@
+use crate::fors::{ForsSignature, ForsSignatureRef};
 use crate::hashes::{
     Sha2_128f, Sha2_128s, Sha2_192f, Sha2_192s, Sha2_256f, Sha2_256s, Shake128f, Shake192f,
     Shake192s, Shake256f, Shake256s,
 };
-use crate::hypertree::HypertreeSig;
+use crate::hypertree::{HypertreeSig, HypertreeSigRef};
 use crate::ParameterSet;
-use crate::{fors::ForsSignature, Shake128s};
+use crate::Shake128s;
 use ::signature::{Error, SignatureEncoding};
 use hybrid_array::sizes::{U16224, U17088, U29792, U35664, U49856, U7856};
 use hybrid_array::{Array, ArraySize};
@@ -81,6 +82,66 @@ impl<P: ParameterSet> From<&Signature<P>> for Vec<u8> {
     }
 }
 
+/// A borrowed view of a serialized SLH-DSA signature for a given parameter set
+///
+/// Parsing a `SignatureRef` from bytes only checks their length: the hash values are
+/// borrowed in place rather than copied, so large signatures can be verified directly
+/// from an input buffer. A `SignatureRef` can also borrow from a parsed [`Signature`].
+pub struct SignatureRef<'a, P: ParameterSet> {
+    pub(crate) randomizer: &'a Array<u8, P::N>,
+    pub(crate) fors_sig: ForsSignatureRef<'a, P>,
+    pub(crate) ht_sig: HypertreeSigRef<'a, P>,
+}
+
+impl<P: ParameterSet> Clone for SignatureRef<'_, P> {
+    fn clone(&self) -> Self {
+        SignatureRef {
+            randomizer: self.randomizer,
+            fors_sig: self.fors_sig.clone(),
+            ht_sig: self.ht_sig.clone(),
+        }
+    }
+}
+
+impl<'a, P: ParameterSet> TryFrom<&'a [u8]> for SignatureRef<'a, P> {
+    type Error = Error;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != P::SigLen::USIZE {
+            return Err(Error::new()); // TODO: Real error
+        }
+
+        let (rand_bytes, rest) = bytes.split_at(P::N::USIZE);
+        let randomizer = rand_bytes.try_into().map_err(|_| Error::new())?;
+
+        let (fors_bytes, ht_bytes) = rest.split_at(ForsSignature::<P>::SIZE);
+        let fors_sig = ForsSignatureRef::try_from(fors_bytes).map_err(|()| Error::new())?;
+        let ht_sig = HypertreeSigRef::try_from(ht_bytes).map_err(|()| Error::new())?;
+
+        Ok(SignatureRef {
+            randomizer,
+            fors_sig,
+            ht_sig,
+        })
+    }
+}
+
+impl<'a, P: ParameterSet> From<&'a Array<u8, P::SigLen>> for SignatureRef<'a, P> {
+    fn from(bytes: &'a Array<u8, P::SigLen>) -> SignatureRef<'a, P> {
+        SignatureRef::try_from(bytes.as_slice()).unwrap()
+    }
+}
+
+impl<'a, P: ParameterSet> From<&'a Signature<P>> for SignatureRef<'a, P> {
+    fn from(sig: &'a Signature<P>) -> SignatureRef<'a, P> {
+        SignatureRef {
+            randomizer: &sig.randomizer,
+            fors_sig: (&sig.fors_sig).into(),
+            ht_sig: (&sig.ht_sig).into(),
+        }
+    }
+}
+
 /// A trait specifying the length of a serialized signature for a given parameter set
 pub trait SignatureLen {
     /// The length of the signature in bytes
@@ -157,12 +218,12 @@ impl SignatureLen for Sha2_256f {
 
 #[cfg(test)]
 mod tests {
-    use crate::signature_encoding::Signature;
+    use crate::signature_encoding::{Signature, SignatureRef};
     use crate::util::macros::test_parameter_sets;
     use crate::SigningKey;
     use crate::{hashes::*, ParameterSet};
     use hybrid_array::Array;
-    use signature::{SignatureEncoding, Signer};
+    use signature::{Keypair, SignatureEncoding, Signer};
 
     fn test_serialize_deserialize<P: ParameterSet>() {
         let mut rng = rand::thread_rng();
@@ -214,4 +275,28 @@ mod tests {
             "Deserialization should fail on incorrect length"
         );
     }
+
+    fn test_verify_borrowed<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<P>::new(&mut rng);
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let sig_bytes = sk.try_sign(msg).unwrap().to_bytes();
+        let sig = SignatureRef::<P>::try_from(sig_bytes.as_slice()).unwrap();
+        assert!(vk.try_verify_ref_with_context(msg, &[], &sig).is_ok());
+        assert!(vk
+            .try_verify_ref_with_context(b"Goodbye, world!", &[], &sig)
+            .is_err());
+    }
+
+    test_parameter_sets!(test_verify_borrowed);
+
+    #[test]
+    fn test_borrow_fail_on_incorrect_length() {
+        let mut rng = rand::thread_rng();
+        let sk = SigningKey::<Shake128f>::new(&mut rng);
+        let sig_bytes = sk.try_sign(b"Hello, world!").unwrap().to_bytes();
+        assert!(SignatureRef::<Shake128f>::try_from(&sig_bytes[..sig_bytes.len() - 1]).is_err());
+        assert!(SignatureRef::<Shake128f>::try_from(&[0u8; 0][..]).is_err());
+    }
 }
@@ -1,24 +1,36 @@
 use crate::address::{ForsTree, WotsHash};
+use crate::fors::ForsSignature;
+use crate::hypertree::XmssTreeCache;
+use crate::prehash::{PreHash, PrehashHeader};
 use crate::signature_encoding::Signature;
 use crate::util::split_digest;
 use crate::verifying_key::VerifyingKey;
 use crate::{ParameterSet, PkSeed, Sha2L1, Sha2L35, Shake, VerifyingKeyLen};
-use ::signature::{Error, KeypairRef, RandomizedSigner, Signer};
+use ::signature::{
+    digest::Digest, DigestSigner, Error, KeypairRef, RandomizedDigestSigner, RandomizedSigner,
+    Signer,
+};
+use core::fmt::{self, Debug};
 use hybrid_array::{Array, ArraySize};
+use subtle::{Choice, ConstantTimeEq};
 use typenum::{Unsigned, U, U16, U24, U32};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 // NewTypes for ensuring hash argument order correctness
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub(crate) struct SkSeed<N: ArraySize>(pub(crate) Array<u8, N>);
+/// `SK.seed`, the secret seed from which all WOTS+ and FORS secret values are derived
+#[derive(Clone)]
+pub struct SkSeed<N: ArraySize>(pub(crate) Array<u8, N>);
 impl<N: ArraySize> AsRef<[u8]> for SkSeed<N> {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
     }
 }
-impl<N: ArraySize> From<&[u8]> for SkSeed<N> {
-    fn from(slice: &[u8]) -> Self {
-        #[allow(deprecated)]
-        Self(Array::clone_from_slice(slice))
+impl<N: ArraySize> TryFrom<&[u8]> for SkSeed<N> {
+    type Error = Error;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        Array::try_from(slice).map(Self).map_err(|_| Error::new())
     }
 }
 impl<N: ArraySize> SkSeed<N> {
@@ -28,18 +40,45 @@ impl<N: ArraySize> SkSeed<N> {
         Self(bytes)
     }
 }
+impl<N: ArraySize> ConstantTimeEq for SkSeed<N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.as_slice().ct_eq(other.0.as_slice())
+    }
+}
+/// Constant-time comparison
+impl<N: ArraySize> PartialEq for SkSeed<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+impl<N: ArraySize> Eq for SkSeed<N> {}
+impl<N: ArraySize> Debug for SkSeed<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SkSeed").finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<N: ArraySize> Drop for SkSeed<N> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<N: ArraySize> ZeroizeOnDrop for SkSeed<N> {}
 
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub(crate) struct SkPrf<N: ArraySize>(pub(crate) Array<u8, N>);
+/// `SK.prf`, the secret key of the PRF that generates the message randomizer
+#[derive(Clone)]
+pub struct SkPrf<N: ArraySize>(pub(crate) Array<u8, N>);
 impl<N: ArraySize> AsRef<[u8]> for SkPrf<N> {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
     }
 }
-impl<N: ArraySize> From<&[u8]> for SkPrf<N> {
-    fn from(slice: &[u8]) -> Self {
-        #[allow(deprecated)]
-        Self(Array::clone_from_slice(slice))
+impl<N: ArraySize> TryFrom<&[u8]> for SkPrf<N> {
+    type Error = Error;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        Array::try_from(slice).map(Self).map_err(|_| Error::new())
     }
 }
 impl<N: ArraySize> SkPrf<N> {
@@ -49,9 +88,37 @@ impl<N: ArraySize> SkPrf<N> {
         Self(bytes)
     }
 }
+impl<N: ArraySize> ConstantTimeEq for SkPrf<N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.as_slice().ct_eq(other.0.as_slice())
+    }
+}
+/// Constant-time comparison
+impl<N: ArraySize> PartialEq for SkPrf<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+impl<N: ArraySize> Eq for SkPrf<N> {}
+impl<N: ArraySize> Debug for SkPrf<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SkPrf").finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<N: ArraySize> Drop for SkPrf<N> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<N: ArraySize> ZeroizeOnDrop for SkPrf<N> {}
 
 /// A `SigningKey` allows signing messages with a fixed parameter set
-#[derive(Clone, PartialEq, Eq, Debug)]
+///
+/// Equality comparisons of the secret seeds are constant time, and the `Debug` output omits them.
+/// With the `zeroize` feature, the secret seeds are zeroized when the key is dropped.
+#[derive(Clone)]
 pub struct SigningKey<P: ParameterSet> {
     pub(crate) sk_seed: SkSeed<P::N>,
     pub(crate) sk_prf: SkPrf<P::N>,
@@ -77,6 +144,7 @@ impl<P: ParameterSet> SigningKey<P> {
         let mut adrs = WotsHash::default();
         adrs.layer_adrs.set(P::D::U32 - 1);
 
+        let pk_seed = P::PkSeedState::from(pk_seed);
         let pk_root = P::xmss_node(&sk_seed, 0, P::HPrime::U32, &pk_seed, &adrs);
         let verifying_key = VerifyingKey { pk_seed, pk_root };
         SigningKey {
@@ -91,10 +159,12 @@ impl<P: ParameterSet> SigningKey<P> {
     /// Construct a new SigningKey from pre-chosen seeds.
     /// Implements [slh_keygen_internal] as defined in FIPS-205.
     /// Published for KAT validation purposes but not intended for general use.
+    /// # Panics
+    /// Panics if any of the seeds is not `N` bytes long.
     pub fn slh_keygen_internal(sk_seed: &[u8], sk_prf: &[u8], pk_seed: &[u8]) -> Self {
-        let sk_seed = SkSeed::from(sk_seed);
-        let sk_prf = SkPrf::from(sk_prf);
-        let pk_seed = PkSeed::from(pk_seed);
+        let sk_seed = SkSeed::try_from(sk_seed).expect("SK.seed must be N bytes long");
+        let sk_prf = SkPrf::try_from(sk_prf).expect("SK.prf must be N bytes long");
+        let pk_seed = PkSeed::try_from(pk_seed).expect("PK.seed must be N bytes long");
         Self::from_seed(sk_seed, sk_prf, pk_seed)
     }
 
@@ -104,8 +174,18 @@ impl<P: ParameterSet> SigningKey<P> {
     /// Published for KAT validation purposes but not intended for general use.
     /// opt_rand must be a P::N length slice, panics otherwise.
     pub fn slh_sign_internal(&self, msg: &[&[u8]], opt_rand: Option<&[u8]>) -> Signature<P> {
+        self.sign_internal(msg, opt_rand, &())
+    }
+
+    /// `slh_sign_internal`, reading the XMSS trees found in `cache` rather than computing them
+    pub(crate) fn sign_internal(
+        &self,
+        msg: &[&[u8]],
+        opt_rand: Option<&[u8]>,
+        cache: &impl XmssTreeCache<P>,
+    ) -> Signature<P> {
         let rand = opt_rand
-            .unwrap_or(&self.verifying_key.pk_seed.0)
+            .unwrap_or(&self.verifying_key.pk_seed.as_ref().0)
             .try_into()
             .unwrap();
 
@@ -114,13 +194,18 @@ impl<P: ParameterSet> SigningKey<P> {
 
         let randomizer = P::prf_msg(&self.sk_prf, rand, msg);
 
-        let digest = P::h_msg(&randomizer, pk_seed, &self.verifying_key.pk_root, msg);
+        let digest = P::h_msg(
+            &randomizer,
+            pk_seed.as_ref(),
+            &self.verifying_key.pk_root,
+            msg,
+        );
         let (md, idx_tree, idx_leaf) = split_digest::<P>(&digest);
         let adrs = ForsTree::new(idx_tree, idx_leaf);
         let fors_sig = P::fors_sign(md, sk_seed, pk_seed, &adrs);
 
-        let fors_pk = P::fors_pk_from_sig(&fors_sig, md, pk_seed, &adrs);
-        let ht_sig = P::ht_sign(&fors_pk, sk_seed, pk_seed, idx_tree, idx_leaf);
+        let fors_pk = P::fors_pk_from_sig(&(&fors_sig).into(), md, pk_seed, &adrs);
+        let ht_sig = P::ht_sign(&fors_pk, sk_seed, pk_seed, idx_tree, idx_leaf, cache);
 
         Signature {
             randomizer,
@@ -129,6 +214,55 @@ impl<P: ParameterSet> SigningKey<P> {
         }
     }
 
+    #[doc(hidden)]
+    /// Sign a message with a pre-chosen randomizer, writing the signature to `sig`.
+    /// Produces the same signature as [`Self::slh_sign_internal`] without holding it in memory.
+    /// Published for KAT validation purposes but not intended for general use.
+    /// opt_rand must be a P::N length slice, and sig a P::SigLen length slice, panics otherwise.
+    pub fn slh_sign_internal_to(&self, msg: &[&[u8]], opt_rand: Option<&[u8]>, sig: &mut [u8]) {
+        self.sign_internal_to(msg, opt_rand, &(), sig);
+    }
+
+    /// `slh_sign_internal_to`, reading the XMSS trees found in `cache` rather than computing them
+    pub(crate) fn sign_internal_to(
+        &self,
+        msg: &[&[u8]],
+        opt_rand: Option<&[u8]>,
+        cache: &impl XmssTreeCache<P>,
+        sig: &mut [u8],
+    ) {
+        assert_eq!(
+            sig.len(),
+            P::SigLen::USIZE,
+            "signature buffer length mismatch"
+        );
+        let rand = opt_rand
+            .unwrap_or(&self.verifying_key.pk_seed.as_ref().0)
+            .try_into()
+            .unwrap();
+
+        let sk_seed = &self.sk_seed;
+        let pk_seed = &self.verifying_key.pk_seed;
+
+        let randomizer = P::prf_msg(&self.sk_prf, rand, msg);
+        let (randomizer_bytes, rest) = sig.split_at_mut(P::N::USIZE);
+        let (fors_sig, ht_sig) = rest.split_at_mut(ForsSignature::<P>::SIZE);
+        randomizer_bytes.copy_from_slice(&randomizer);
+
+        let digest = P::h_msg(
+            &randomizer,
+            pk_seed.as_ref(),
+            &self.verifying_key.pk_root,
+            msg,
+        );
+        let (md, idx_tree, idx_leaf) = split_digest::<P>(&digest);
+        let adrs = ForsTree::new(idx_tree, idx_leaf);
+        let fors_pk = P::fors_sign_to(md, sk_seed, pk_seed, &adrs, fors_sig);
+        P::ht_sign_to(
+            &fors_pk, sk_seed, pk_seed, idx_tree, idx_leaf, cache, ht_sig,
+        );
+    }
+
     /// Implements [slh-sign] as defined in FIPS-205, using a context string.
     /// Context strings must be 255 bytes or less.
     /// # Errors
@@ -138,12 +272,168 @@ impl<P: ParameterSet> SigningKey<P> {
         msg: &[u8],
         ctx: &[u8],
         opt_rand: Option<&[u8]>,
+    ) -> Result<Signature<P>, Error> {
+        self.try_sign_with_context_cached(msg, ctx, opt_rand, &())
+    }
+
+    /// `try_sign_with_context`, reading the XMSS trees found in `cache` rather than computing
+    /// them
+    pub(crate) fn try_sign_with_context_cached(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+        cache: &impl XmssTreeCache<P>,
     ) -> Result<Signature<P>, Error> {
         let ctx_len = u8::try_from(ctx.len()).map_err(|_| Error::new())?;
         let ctx_len_bytes = ctx_len.to_be_bytes();
 
         let ctx_msg = [&[0], &ctx_len_bytes, ctx, msg];
-        Ok(self.slh_sign_internal(&ctx_msg, opt_rand))
+        Ok(self.sign_internal(&ctx_msg, opt_rand, cache))
+    }
+
+    /// Implements [slh-sign] as defined in FIPS-205, using a context string, and writes the
+    /// signature to `sig` rather than returning it.
+    ///
+    /// Signatures range from about 8 to 50 KB, and [`Self::try_sign_with_context`] keeps both the
+    /// signature and its components on the stack. This method instead writes the signature of
+    /// each FORS tree and each hypertree layer to `sig` as soon as it is computed, so `sig` can
+    /// live on the heap and the stack use is bounded by a small constant. In optimized builds it
+    /// is under 24 KiB for every parameter set with the `alloc` feature. Without it, the XMSS
+    /// trees are built with the WOTS+ chain ends of four key pairs (`4 * 67 * 32` bytes at most)
+    /// on the stack, and the bound is 32 KiB. Unoptimized builds need more.
+    ///
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context string is too long, or if `sig` is not exactly
+    /// `P::SigLen` bytes long.
+    pub fn try_sign_with_context_to(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+        sig: &mut [u8],
+    ) -> Result<(), Error> {
+        self.try_sign_with_context_to_cached(msg, ctx, opt_rand, &(), sig)
+    }
+
+    /// `try_sign_with_context_to`, reading the XMSS trees found in `cache` rather than computing
+    /// them
+    pub(crate) fn try_sign_with_context_to_cached(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+        cache: &impl XmssTreeCache<P>,
+        sig: &mut [u8],
+    ) -> Result<(), Error> {
+        let ctx_len = u8::try_from(ctx.len()).map_err(|_| Error::new())?;
+        let ctx_len_bytes = ctx_len.to_be_bytes();
+        if sig.len() != P::SigLen::USIZE {
+            return Err(Error::new());
+        }
+
+        let ctx_msg = [&[0], &ctx_len_bytes, ctx, msg];
+        self.sign_internal_to(&ctx_msg, opt_rand, cache, sig);
+        Ok(())
+    }
+
+    /// Implements `hash_slh_sign` as defined in FIPS-205, using a context string.
+    /// `ph_msg` must be the digest of the message computed with the pre-hash function `H`.
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context string is too long or the digest has the wrong length.
+    pub fn try_sign_prehash_with_context<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+    ) -> Result<Signature<P>, Error> {
+        self.try_sign_prehash_with_context_cached::<H>(ph_msg, ctx, opt_rand, &())
+    }
+
+    /// `try_sign_prehash_with_context`, reading the XMSS trees found in `cache` rather than
+    /// computing them
+    pub(crate) fn try_sign_prehash_with_context_cached<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+        cache: &impl XmssTreeCache<P>,
+    ) -> Result<Signature<P>, Error> {
+        let header = PrehashHeader::new::<H>(ctx).ok_or(Error::new())?;
+        if ph_msg.len() != H::OutputSize::USIZE {
+            return Err(Error::new());
+        }
+
+        let ctx_msg = [header.ctx_len(), ctx, header.oid(), ph_msg];
+        Ok(self.sign_internal(&ctx_msg, opt_rand, cache))
+    }
+
+    /// Implements `hash_slh_sign` as defined in FIPS-205, using a context string, and writes the
+    /// signature to `sig` rather than returning it. See [`Self::try_sign_with_context_to`].
+    /// # Errors
+    /// Returns an error if the context string is too long, the digest has the wrong length, or
+    /// `sig` is not exactly `P::SigLen` bytes long.
+    pub fn try_sign_prehash_with_context_to<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+        sig: &mut [u8],
+    ) -> Result<(), Error> {
+        self.try_sign_prehash_with_context_to_cached::<H>(ph_msg, ctx, opt_rand, &(), sig)
+    }
+
+    /// `try_sign_prehash_with_context_to`, reading the XMSS trees found in `cache` rather than
+    /// computing them
+    pub(crate) fn try_sign_prehash_with_context_to_cached<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        opt_rand: Option<&[u8]>,
+        cache: &impl XmssTreeCache<P>,
+        sig: &mut [u8],
+    ) -> Result<(), Error> {
+        let header = PrehashHeader::new::<H>(ctx).ok_or(Error::new())?;
+        if ph_msg.len() != H::OutputSize::USIZE || sig.len() != P::SigLen::USIZE {
+            return Err(Error::new());
+        }
+
+        let ctx_msg = [header.ctx_len(), ctx, header.oid(), ph_msg];
+        self.sign_internal_to(&ctx_msg, opt_rand, cache, sig);
+        Ok(())
+    }
+
+    /// `RandomizedSigner::try_sign_with_rng`, reading the XMSS trees found in `cache` rather than
+    /// computing them
+    pub(crate) fn try_sign_with_rng_cached(
+        &self,
+        rng: &mut impl signature::rand_core::CryptoRngCore,
+        msg: &[u8],
+        cache: &impl XmssTreeCache<P>,
+    ) -> Result<Signature<P>, Error> {
+        let mut randomizer = Array::<u8, P::N>::default();
+        rng.try_fill_bytes(randomizer.as_mut_slice())?;
+        self.try_sign_with_context_cached(msg, &[], Some(&randomizer), cache)
+    }
+
+    /// `RandomizedDigestSigner::try_sign_digest_with_rng`, reading the XMSS trees found in
+    /// `cache` rather than computing them
+    pub(crate) fn try_sign_digest_with_rng_cached<D: Digest + PreHash>(
+        &self,
+        rng: &mut impl signature::rand_core::CryptoRngCore,
+        digest: D,
+        cache: &impl XmssTreeCache<P>,
+    ) -> Result<Signature<P>, Error> {
+        let mut randomizer = Array::<u8, P::N>::default();
+        rng.try_fill_bytes(randomizer.as_mut_slice())?;
+        self.try_sign_prehash_with_context_cached::<D>(
+            &digest.finalize(),
+            &[],
+            Some(&randomizer),
+            cache,
+        )
     }
 
     /// Serialize the signing key to a new stack-allocated array
@@ -180,8 +470,8 @@ impl<P: ParameterSet> TryFrom<&[u8]> for SigningKey<P> {
         let verifying_key = VerifyingKey::try_from(verifying_key_bytes)?;
 
         Ok(SigningKey {
-            sk_seed: SkSeed::from(sk_seed_bytes),
-            sk_prf: SkPrf::from(sk_prf_bytes),
+            sk_seed: SkSeed::try_from(sk_seed_bytes)?,
+            sk_prf: SkPrf::try_from(sk_prf_bytes)?,
             verifying_key,
         })
     }
@@ -199,9 +489,33 @@ impl<P: ParameterSet> RandomizedSigner<Signature<P>> for SigningKey<P> {
         rng: &mut impl signature::rand_core::CryptoRngCore,
         msg: &[u8],
     ) -> Result<Signature<P>, signature::Error> {
-        let mut randomizer = Array::<u8, P::N>::default();
-        rng.fill_bytes(randomizer.as_mut_slice());
-        self.try_sign_with_context(msg, &[], Some(&randomizer))
+        self.try_sign_with_rng_cached(rng, msg, &())
+    }
+}
+
+/// Deterministic HashSLH-DSA signing, with an empty context string
+impl<P, D> DigestSigner<D, Signature<P>> for SigningKey<P>
+where
+    P: ParameterSet,
+    D: Digest + PreHash,
+{
+    fn try_sign_digest(&self, digest: D) -> Result<Signature<P>, Error> {
+        self.try_sign_prehash_with_context::<D>(&digest.finalize(), &[], None)
+    }
+}
+
+/// Randomized HashSLH-DSA signing, with an empty context string
+impl<P, D> RandomizedDigestSigner<D, Signature<P>> for SigningKey<P>
+where
+    P: ParameterSet,
+    D: Digest + PreHash,
+{
+    fn try_sign_digest_with_rng(
+        &self,
+        rng: &mut impl signature::rand_core::CryptoRngCore,
+        digest: D,
+    ) -> Result<Signature<P>, Error> {
+        self.try_sign_digest_with_rng_cached(rng, digest, &())
     }
 }
 
@@ -211,6 +525,34 @@ impl<P: ParameterSet> AsRef<VerifyingKey<P>> for SigningKey<P> {
     }
 }
 
+impl<P: ParameterSet> ConstantTimeEq for SigningKey<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.sk_seed.ct_eq(&other.sk_seed)
+            & self.sk_prf.ct_eq(&other.sk_prf)
+            & Choice::from(u8::from(self.verifying_key == other.verifying_key))
+    }
+}
+
+/// Constant-time comparison
+impl<P: ParameterSet> PartialEq for SigningKey<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<P: ParameterSet> Eq for SigningKey<P> {}
+
+impl<P: ParameterSet> Debug for SigningKey<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("verifying_key", &self.verifying_key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P: ParameterSet> ZeroizeOnDrop for SigningKey<P> {}
+
 impl<P: ParameterSet> KeypairRef for SigningKey<P> {
     type VerifyingKey = VerifyingKey<P>;
 }
@@ -268,4 +610,81 @@ mod tests {
         let incorrect_bytes = &bytes[..bytes.len() - 1];
         assert!(SigningKey::<Shake128f>::try_from(incorrect_bytes).is_err());
     }
+
+    #[cfg(feature = "alloc")]
+    fn test_sign_prehash_to<P: ParameterSet, H: crate::PreHash>() {
+        use typenum::Unsigned;
+
+        let sk = SigningKey::<P>::new(&mut rand::thread_rng());
+        let ph_msg = vec![0x5a; H::OutputSize::USIZE];
+        let opt_rand = vec![0xa5; P::N::USIZE];
+        for opt_rand in [None, Some(opt_rand.as_slice())] {
+            let expected = sk
+                .try_sign_prehash_with_context::<H>(&ph_msg, b"ctx", opt_rand)
+                .unwrap();
+            let mut sig = vec![0u8; P::SigLen::USIZE];
+            sk.try_sign_prehash_with_context_to::<H>(&ph_msg, b"ctx", opt_rand, &mut sig)
+                .unwrap();
+            assert_eq!(sig, expected.to_vec());
+        }
+
+        // The digest must have the output size of `H`
+        let mut sig = vec![0u8; P::SigLen::USIZE];
+        assert!(sk
+            .try_sign_prehash_with_context_to::<H>(&ph_msg[1..], b"ctx", None, &mut sig)
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_sign_prehash_to_sha2() {
+        test_sign_prehash_to::<crate::Sha2_128f, sha2::Sha256>();
+        test_sign_prehash_to::<crate::Shake128f, sha2::Sha512>();
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_sign_prehash_to_shake() {
+        test_sign_prehash_to::<crate::Shake128f, sha3::Shake128>();
+        test_sign_prehash_to::<crate::Sha2_128f, sha3::Shake256>();
+    }
+
+    #[test]
+    fn test_sign_to_wrong_length() {
+        let sk = SigningKey::<Shake128f>::new(&mut rand::thread_rng());
+        let mut sig = [0u8; 100];
+        assert!(sk
+            .try_sign_with_context_to(b"msg", b"", None, &mut sig)
+            .is_err());
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        use subtle::ConstantTimeEq;
+
+        let mut rng: rand::prelude::ThreadRng = rand::thread_rng();
+        let sk = SigningKey::<Shake128f>::new(&mut rng);
+        let mut bytes = sk.to_bytes();
+        assert!(bool::from(sk.ct_eq(&sk.clone())));
+
+        // Differs only in SK.prf
+        bytes[16] ^= 1;
+        let sk2 = SigningKey::<Shake128f>::try_from(bytes.as_slice()).unwrap();
+        assert!(!bool::from(sk.ct_eq(&sk2)));
+        assert_ne!(sk, sk2);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_debug_redacts_secrets() {
+        let mut rng: rand::prelude::ThreadRng = rand::thread_rng();
+        let sk = SigningKey::<Shake128f>::new(&mut rng);
+        let debug = format!("{sk:?}");
+        let expected_vk = format!("{:?}", sk.verifying_key);
+        assert_eq!(
+            debug,
+            format!("SigningKey {{ verifying_key: {expected_vk}, .. }}")
+        );
+        assert_eq!(format!("{:?}", sk.sk_seed), "SkSeed { .. }");
+    }
 }
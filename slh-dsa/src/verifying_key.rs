@@ -1,11 +1,12 @@
 use crate::address::ForsTree;
-use crate::signature_encoding::Signature;
+use crate::prehash::{PreHash, PrehashHeader};
+use crate::signature_encoding::{Signature, SignatureRef};
 use crate::util::split_digest;
 use crate::ParameterSet;
 use crate::Sha2L1;
 use crate::Sha2L35;
 use crate::Shake;
-use ::signature::{Error, Verifier};
+use ::signature::{digest::Digest, DigestVerifier, Error, Verifier};
 use hybrid_array::{Array, ArraySize};
 use typenum::{Unsigned, U, U16, U24, U32};
 
@@ -15,17 +16,24 @@ pub trait VerifyingKeyLen {
     type VkLen: ArraySize;
 }
 
+/// `PK.seed`, the public seed that separates the hashes of different key pairs
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub(crate) struct PkSeed<N: ArraySize>(pub(crate) Array<u8, N>);
+pub struct PkSeed<N: ArraySize>(pub(crate) Array<u8, N>);
 impl<N: ArraySize> AsRef<[u8]> for PkSeed<N> {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
     }
 }
-impl<N: ArraySize> From<&[u8]> for PkSeed<N> {
-    fn from(slice: &[u8]) -> Self {
-        #[allow(deprecated)]
-        Self(Array::clone_from_slice(slice))
+impl<N: ArraySize> AsRef<PkSeed<N>> for PkSeed<N> {
+    fn as_ref(&self) -> &PkSeed<N> {
+        self
+    }
+}
+impl<N: ArraySize> TryFrom<&[u8]> for PkSeed<N> {
+    type Error = Error;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        Array::try_from(slice).map(Self).map_err(|_| Error::new())
     }
 }
 impl<N: ArraySize> PkSeed<N> {
@@ -41,7 +49,7 @@ impl<N: ArraySize> PkSeed<N> {
 /// `SigningKey`
 #[derive(Debug, PartialEq, Eq)]
 pub struct VerifyingKey<P: ParameterSet> {
-    pub(crate) pk_seed: PkSeed<P::N>,
+    pub(crate) pk_seed: P::PkSeedState,
     pub(crate) pk_root: Array<u8, P::N>,
 }
 
@@ -54,13 +62,21 @@ impl<P: ParameterSet + VerifyingKeyLen> VerifyingKey<P> {
         &self,
         msg: &[&[u8]],
         signature: &Signature<P>,
+    ) -> Result<(), Error> {
+        self.slh_verify_internal_ref(msg, &signature.into())
+    }
+
+    fn slh_verify_internal_ref(
+        &self,
+        msg: &[&[u8]],
+        signature: &SignatureRef<'_, P>,
     ) -> Result<(), Error> {
         let pk_seed = &self.pk_seed;
-        let randomizer = &signature.randomizer;
+        let randomizer = signature.randomizer;
         let fors_sig = &signature.fors_sig;
         let ht_sig = &signature.ht_sig;
 
-        let digest = P::h_msg(randomizer, pk_seed, &self.pk_root, msg);
+        let digest = P::h_msg(randomizer, pk_seed.as_ref(), &self.pk_root, msg);
         let (md, idx_tree, idx_leaf) = split_digest::<P>(&digest);
 
         let adrs = ForsTree::new(idx_tree, idx_leaf);
@@ -79,12 +95,63 @@ impl<P: ParameterSet + VerifyingKeyLen> VerifyingKey<P> {
         msg: &[u8],
         ctx: &[u8],
         signature: &Signature<P>,
+    ) -> Result<(), Error> {
+        self.try_verify_ref_with_context(msg, ctx, &signature.into())
+    }
+
+    /// Implements [slh-verify] as defined in FIPS-205, using a context string, for a
+    /// signature borrowed in place with [`SignatureRef`].
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context is too long or if the signature is invalid
+    pub fn try_verify_ref_with_context(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        signature: &SignatureRef<'_, P>,
     ) -> Result<(), Error> {
         let ctx_len = u8::try_from(ctx.len()).map_err(|_| Error::new())?;
         let ctx_len_bytes = ctx_len.to_be_bytes();
 
         let ctx_msg = [&[0], &ctx_len_bytes, ctx, msg];
-        self.slh_verify_internal(&ctx_msg, signature) // TODO - context processing
+        self.slh_verify_internal_ref(&ctx_msg, signature) // TODO - context processing
+    }
+
+    /// Implements `hash_slh_verify` as defined in FIPS-205, using a context string.
+    /// `ph_msg` must be the digest of the message computed with the pre-hash function `H`.
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context is too long, the digest has the wrong length or if the
+    /// signature is invalid
+    pub fn try_verify_prehash_with_context<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), Error> {
+        self.try_verify_prehash_ref_with_context::<H>(ph_msg, ctx, &signature.into())
+    }
+
+    /// Implements `hash_slh_verify` as defined in FIPS-205, using a context string, for a
+    /// signature borrowed in place with [`SignatureRef`].
+    /// `ph_msg` must be the digest of the message computed with the pre-hash function `H`.
+    /// Context strings must be 255 bytes or less.
+    /// # Errors
+    /// Returns an error if the context is too long, the digest has the wrong length or if the
+    /// signature is invalid
+    pub fn try_verify_prehash_ref_with_context<H: PreHash>(
+        &self,
+        ph_msg: &[u8],
+        ctx: &[u8],
+        signature: &SignatureRef<'_, P>,
+    ) -> Result<(), Error> {
+        let header = PrehashHeader::new::<H>(ctx).ok_or(Error::new())?;
+        if ph_msg.len() != H::OutputSize::USIZE {
+            return Err(Error::new());
+        }
+
+        let ctx_msg = [header.ctx_len(), ctx, header.oid(), ph_msg];
+        self.slh_verify_internal_ref(&ctx_msg, signature)
     }
 
     /// Serialize the verifying key to a new stack-allocated array
@@ -93,7 +160,7 @@ impl<P: ParameterSet + VerifyingKeyLen> VerifyingKey<P> {
     pub fn to_bytes(&self) -> Array<u8, P::VkLen> {
         let mut bytes = Array::<u8, P::VkLen>::default();
         debug_assert!(P::N::USIZE * 2 == P::VkLen::USIZE);
-        bytes[..P::N::USIZE].copy_from_slice(&self.pk_seed.0);
+        bytes[..P::N::USIZE].copy_from_slice(&self.pk_seed.as_ref().0);
         bytes[P::N::USIZE..].copy_from_slice(&self.pk_root);
         bytes
     }
@@ -126,7 +193,10 @@ impl<P: ParameterSet> From<Array<u8, P::VkLen>> for VerifyingKey<P> {
         debug_assert!(P::VkLen::USIZE == 2 * P::N::USIZE);
         let pk_seed = PkSeed(Array::clone_from_slice(&bytes[..P::N::USIZE]));
         let pk_root = Array::clone_from_slice(&bytes[P::N::USIZE..]);
-        VerifyingKey { pk_seed, pk_root }
+        VerifyingKey {
+            pk_seed: pk_seed.into(),
+            pk_root,
+        }
     }
 }
 
@@ -140,7 +210,10 @@ impl<P: ParameterSet> TryFrom<&[u8]> for VerifyingKey<P> {
         }
         let pk_seed = PkSeed(Array::clone_from_slice(&bytes[..P::N::USIZE]));
         let pk_root = Array::clone_from_slice(&bytes[P::N::USIZE..]);
-        Ok(VerifyingKey { pk_seed, pk_root })
+        Ok(VerifyingKey {
+            pk_seed: pk_seed.into(),
+            pk_root,
+        })
     }
 }
 
@@ -150,6 +223,17 @@ impl<P: ParameterSet> Verifier<Signature<P>> for VerifyingKey<P> {
     }
 }
 
+/// HashSLH-DSA verification, with an empty context string
+impl<P, D> DigestVerifier<D, Signature<P>> for VerifyingKey<P>
+where
+    P: ParameterSet,
+    D: Digest + PreHash,
+{
+    fn verify_digest(&self, digest: D, signature: &Signature<P>) -> Result<(), Error> {
+        self.try_verify_prehash_with_context::<D>(&digest.finalize(), &[], signature)
+    }
+}
+
 impl<M> VerifyingKeyLen for Sha2L1<U16, M> {
     type VkLen = U<32>;
 }
@@ -4,7 +4,7 @@ use typenum::Unsigned;
 
 use crate::hashes::HashSuite;
 use crate::util::base_2b;
-use crate::{address, PkSeed, SkSeed};
+use crate::{address, SkSeed};
 use core::fmt::Debug;
 
 // WOTS+ in general is parameterized on these values
@@ -14,12 +14,15 @@ const LOG_W: usize = 4;
 const W: u32 = 16;
 const CK_LEN: usize = 3; // Length of a checksum in chunks
 
+/// A WOTS+ signature, made of one value from each hash chain
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct WotsSig<P: WotsParams>(Array<Array<u8, P::N>, P::WotsSigLen>);
+pub struct WotsSig<P: WotsParams>(pub Array<Array<u8, P::N>, P::WotsSigLen>);
 
 impl<P: WotsParams> WotsSig<P> {
+    /// The length of a serialized signature in bytes
     pub const SIZE: usize = P::N::USIZE * P::WotsSigLen::USIZE;
 
+    /// Serialize the signature to `buf`, which must be `SIZE` bytes long
     pub fn write_to(&self, buf: &mut [u8]) {
         debug_assert!(buf.len() == Self::SIZE, "WOTS+ serialize length mismatch");
 
@@ -28,6 +31,7 @@ impl<P: WotsParams> WotsSig<P> {
             .for_each(|(buf, sig)| buf.copy_from_slice(sig.as_slice()));
     }
 
+    /// Serialize the signature to a new `Vec`
     #[cfg(feature = "alloc")]
     #[cfg(test)]
     pub fn to_vec(&self) -> Vec<u8> {
@@ -52,16 +56,21 @@ impl<P: WotsParams> TryFrom<&[u8]> for WotsSig<P> {
     }
 }
 
-pub(crate) trait WotsParams: HashSuite {
-    type WotsMsgLen: ArraySize; // Number of chunks in a WOTS message. Must equal 2 * Self::N
-    type WotsSigLen: ArraySize + Debug + Eq; // Number of chunks in a WOTS signature. Must equal WotsSigLen + CK_LEN;
+/// The WOTS+ one-time signature scheme, with the Winternitz parameter `w = 16` used by every
+/// FIPS-205 parameter set
+pub trait WotsParams: HashSuite {
+    /// Number of chunks in a WOTS+ message. Must equal `2 * N`
+    type WotsMsgLen: ArraySize;
+    /// Number of chunks in a WOTS+ signature. Must equal `WotsMsgLen + 3`, for the checksum
+    type WotsSigLen: ArraySize + Debug + Eq;
 
-    /// Algorithm 4
+    /// Algorithm 4: apply the chaining function `s` times to `x`, which is at position `i` of
+    /// the chain at `adrs`
     fn wots_chain(
         x: &Array<u8, Self::N>,
         i: u32,
         s: u32,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::WotsHash,
     ) -> Array<u8, Self::N> {
         debug_assert!(i + s < 1 << LOG_W, "Invalid wots_chain index");
@@ -75,10 +84,10 @@ pub(crate) trait WotsParams: HashSuite {
         tmp
     }
 
-    /// Algorithm 5
+    /// Algorithm 5: the public key of the WOTS+ key pair at `adrs`
     fn wots_pk_gen(
         sk_seed: &SkSeed<Self::N>,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::WotsHash,
     ) -> Array<u8, Self::N> {
         let mut adrs = adrs.clone();
@@ -95,54 +104,163 @@ pub(crate) trait WotsParams: HashSuite {
         Self::t(pk_seed, &pk_adrs, &tmp)
     }
 
-    // Algorithm 6
-    fn wots_sign(
-        m: &Array<u8, Self::N>,
+    /// Four independent instances of `wots_chain`, computed with `f_x4`
+    #[doc(hidden)]
+    fn wots_chain_x4(
+        x: [Array<u8, Self::N>; 4],
+        i: u32,
+        s: u32,
+        pk_seed: &Self::PkSeedState,
+        adrs: &[address::WotsHash; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        debug_assert!(i + s < 1 << LOG_W, "Invalid wots_chain index");
+
+        let mut tmp = x;
+        let mut adrs = adrs.clone();
+        for j in i..(i + s) {
+            for adrs in &mut adrs {
+                adrs.hash_adrs.set(j);
+            }
+            tmp = Self::f_x4(pk_seed, &adrs, &tmp);
+        }
+        tmp
+    }
+
+    /// Four independent instances of `wots_pk_gen`, computing the chains of all four key
+    /// pairs together
+    #[doc(hidden)]
+    fn wots_pk_gen_x4(
         sk_seed: &SkSeed<Self::N>,
-        pk_seed: &PkSeed<Self::N>,
-        adrs: &address::WotsHash,
-    ) -> WotsSig<Self> {
+        pk_seed: &Self::PkSeedState,
+        adrs: &[address::WotsHash; 4],
+    ) -> [Array<u8, Self::N>; 4] {
+        let mut adrs = adrs.clone();
+        let mut sk_adrs = adrs.each_ref().map(address::WotsHash::prf_adrs);
+
+        // The four sets of chain ends are the largest intermediate value in signing, so they
+        // are kept on the heap when possible
+        #[cfg(feature = "alloc")]
+        let mut tmp = vec![Array::<Array<u8, Self::N>, Self::WotsSigLen>::default(); 4];
+        #[cfg(not(feature = "alloc"))]
+        let mut tmp = [(); 4].map(|()| Array::<Array<u8, Self::N>, Self::WotsSigLen>::default());
+        for i in 0..Self::WotsSigLen::U32 {
+            for (adrs, sk_adrs) in adrs.iter_mut().zip(&mut sk_adrs) {
+                sk_adrs.chain_adrs.set(i);
+                adrs.chain_adrs.set(i);
+            }
+            let sk = core::array::from_fn(|k| Self::prf_sk(pk_seed, sk_seed, &sk_adrs[k]));
+            let pk = Self::wots_chain_x4(sk, 0, (1 << LOG_W) - 1, pk_seed, &adrs);
+            for (tmp, pk) in tmp.iter_mut().zip(pk) {
+                tmp[i as usize] = pk;
+            }
+        }
+        core::array::from_fn(|k| Self::t(pk_seed, &adrs[k].pk_adrs(), &tmp[k]))
+    }
+
+    /// The base-`w` digits of `m` followed by those of its checksum, which give the position of
+    /// each chain value in a signature of `m` (Algorithm 6, lines 2 to 8)
+    #[doc(hidden)]
+    fn wots_digits(m: &Array<u8, Self::N>) -> Array<u16, Self::WotsSigLen> {
         let msg = base_2b::<Self::WotsMsgLen, U<LOG_W>>(m.as_slice());
         let csum = msg.iter().map(|&x| (1 << LOG_W) - 1 - x).sum::<u16>() << 4; // Algorithm 6 Line 9
 
         let csum_bytes = csum.to_be_bytes();
         let csum_chunks = base_2b::<U<CK_LEN>, U<LOG_W>>(&csum_bytes);
-        let mut msg_csum = msg.iter().chain(csum_chunks.iter());
+        msg.iter().chain(csum_chunks.iter()).copied().collect()
+    }
 
-        let mut adrs = adrs.clone();
-        let mut sk_adrs = adrs.prf_adrs();
+    /// Apply the chaining function `steps(i)` times to `init(i)`, the value at position `start(i)`
+    /// of chain `i` of the key pair at `adrs`, for every chain.
+    ///
+    /// The chains are computed four at a time with `f_x4`. They are grouped by length, so that
+    /// few hashes are spent on lanes whose chain is already complete, and the last group is
+    /// padded by repeating one of its chains.
+    ///
+    /// Kept out of line so that its buffers are not part of the frame of the hypertree signer,
+    /// below which the XMSS trees are computed.
+    #[doc(hidden)]
+    #[inline(never)]
+    fn wots_chains(
+        init: impl Fn(usize) -> Array<u8, Self::N>,
+        start: impl Fn(usize) -> u32,
+        steps: impl Fn(usize) -> u32,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::WotsHash,
+    ) -> Array<Array<u8, Self::N>, Self::WotsSigLen> {
+        let mut order = Array::<u8, Self::WotsSigLen>::from_fn(|i| {
+            i.try_into().expect("there are fewer than 256 chains")
+        });
+        order.sort_unstable_by_key(|&i| steps(i.into()));
+
+        let mut out = Array::<Array<u8, Self::N>, Self::WotsSigLen>::default();
+        for group in order.chunks(4) {
+            let lanes: [usize; 4] = core::array::from_fn(|k| group[k.min(group.len() - 1)].into());
+            let mut adrs = lanes.map(|i| {
+                let mut adrs = adrs.clone();
+                adrs.chain_adrs
+                    .set(i.try_into().expect("i is less than 2^32"));
+                adrs
+            });
+            let mut tmp = lanes.map(&init);
+            let max_steps = lanes.map(&steps).into_iter().max().unwrap_or(0);
+
+            for j in 0..max_steps {
+                for (adrs, &i) in adrs.iter_mut().zip(&lanes) {
+                    adrs.hash_adrs.set(start(i) + j);
+                }
+                let next = Self::f_x4(pk_seed, &adrs, &tmp);
+                for ((tmp, next), &i) in tmp.iter_mut().zip(next).zip(&lanes) {
+                    if j < steps(i) {
+                        *tmp = next;
+                    }
+                }
+            }
+
+            for (&i, tmp) in lanes.iter().zip(tmp) {
+                out[i] = tmp;
+            }
+        }
+        out
+    }
 
-        let sig = Array::<Array<u8, Self::N>, Self::WotsSigLen>::from_fn(|i: usize| {
-            let i: u32 = i.try_into().expect("i is less than 2^32");
-            sk_adrs.chain_adrs.set(i);
-            adrs.chain_adrs.set(i);
+    /// Algorithm 6: sign the `N`-byte message `m` with the WOTS+ key pair at `adrs`
+    fn wots_sign(
+        m: &Array<u8, Self::N>,
+        sk_seed: &SkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::WotsHash,
+    ) -> WotsSig<Self> {
+        let digits = Self::wots_digits(m);
 
-            let sk = Self::prf_sk(pk_seed, sk_seed, &sk_adrs);
-            Self::wots_chain(&sk, 0, u32::from(*msg_csum.next().unwrap()), pk_seed, &adrs)
-        });
+        let sk = |i: usize| {
+            let mut sk_adrs = adrs.prf_adrs();
+            sk_adrs
+                .chain_adrs
+                .set(i.try_into().expect("i is less than 2^32"));
+            Self::prf_sk(pk_seed, sk_seed, &sk_adrs)
+        };
+        let sig = Self::wots_chains(sk, |_| 0, |i| u32::from(digits[i]), pk_seed, adrs);
 
         WotsSig(sig)
     }
 
+    /// Algorithm 7: compute a WOTS+ public key from the signature `sig` of `m`.
+    /// The signature is valid if the result is the public key of the key pair at `adrs`.
     fn wots_pk_from_sig(
-        sig: &WotsSig<Self>,
+        sig: &Array<Array<u8, Self::N>, Self::WotsSigLen>,
         m: &Array<u8, Self::N>,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::WotsHash,
     ) -> Array<u8, Self::N> {
-        let msg = base_2b::<Self::WotsMsgLen, U<LOG_W>>(m.as_slice());
-        let csum = msg.iter().map(|&x| (1 << LOG_W) - 1 - x).sum::<u16>() << 4; // TODO: remove magic 4
-        let csum_bytes = csum.to_be_bytes();
-        let csum_chunks = base_2b::<U<CK_LEN>, U<LOG_W>>(&csum_bytes);
-        let mut msg_csum = msg.iter().chain(csum_chunks.iter());
-
-        let mut adrs = adrs.clone();
-        let tmp = Array::<Array<u8, Self::N>, Self::WotsSigLen>::from_fn(|i: usize| {
-            adrs.chain_adrs
-                .set(i.try_into().expect("i is less than 2^32"));
-            let msg_i = u32::from(*msg_csum.next().unwrap());
-            Self::wots_chain(&sig.0[i], msg_i, W - 1 - msg_i, pk_seed, &adrs)
-        });
+        let digits = Self::wots_digits(m);
+
+        let tmp = Self::wots_chains(
+            |i| sig[i].clone(),
+            |i| u32::from(digits[i]),
+            |i| W - 1 - u32::from(digits[i]),
+            pk_seed,
+            adrs,
+        );
         Self::t(pk_seed, &adrs.pk_adrs(), &tmp)
     }
 }
@@ -163,7 +281,7 @@ mod tests {
 
         let sk_seed = SkSeed::new(&mut rng);
 
-        let pk_seed = PkSeed::new(&mut rng);
+        let pk_seed = PkSeed::new(&mut rng).into();
 
         let mut msg = Array::<u8, _>::default();
         rng.fill_bytes(msg.as_mut_slice());
@@ -173,7 +291,7 @@ mod tests {
         let pk = Wots::wots_pk_gen(&sk_seed, &pk_seed, adrs);
 
         let sig = Wots::wots_sign(&msg, &sk_seed, &pk_seed, adrs);
-        let pk_recovered = Wots::wots_pk_from_sig(&sig, &msg, &pk_seed, adrs);
+        let pk_recovered = Wots::wots_pk_from_sig(&sig.0, &msg, &pk_seed, adrs);
 
         assert_eq!(pk, pk_recovered);
     }
@@ -186,7 +304,7 @@ mod tests {
 
         let sk_seed = SkSeed::new(&mut rng);
 
-        let pk_seed = PkSeed::new(&mut rng);
+        let pk_seed = PkSeed::new(&mut rng).into();
 
         let mut msg = Array::<u8, _>::default();
         rng.fill_bytes(msg.as_mut_slice());
@@ -203,7 +321,7 @@ mod tests {
         msg[0] ^= 0xff; // Invert the first byte of the message
 
         // Attempt to recover the public key from the tweaked message and signature
-        let pk_recovered = Wots::wots_pk_from_sig(&sig, &msg, &pk_seed, adrs);
+        let pk_recovered = Wots::wots_pk_from_sig(&sig.0, &msg, &pk_seed, adrs);
 
         // Check that the recovered public key does not match the original public key
         assert_ne!(
@@ -2,19 +2,24 @@ use hybrid_array::{Array, ArraySize};
 use typenum::Unsigned;
 
 use crate::wots::WotsSig;
+use crate::SkSeed;
 use crate::{address, wots::WotsParams};
-use crate::{PkSeed, SkSeed};
 use core::fmt::Debug;
 
+/// An XMSS signature: a WOTS+ signature and the authentication path of its leaf
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct XmssSig<P: XmssParams> {
-    pub(crate) sig: WotsSig<P>,
-    pub(crate) auth: Array<Array<u8, P::N>, P::HPrime>,
+    /// The WOTS+ signature of the message
+    pub sig: WotsSig<P>,
+    /// The sibling of each node on the path from the leaf to the root, starting at the leaf
+    pub auth: Array<Array<u8, P::N>, P::HPrime>,
 }
 
 impl<P: XmssParams> XmssSig<P> {
+    /// The length of a serialized signature in bytes
     pub const SIZE: usize = WotsSig::<P>::SIZE + P::HPrime::USIZE * P::N::USIZE;
 
+    /// Serialize the signature to `buf`, which must be `SIZE` bytes long
     pub fn write_to(&self, buf: &mut [u8]) {
         debug_assert!(buf.len() == Self::SIZE, "Xmss serialize length mismatch");
 
@@ -25,6 +30,7 @@ impl<P: XmssParams> XmssSig<P> {
             .for_each(|(buf, auth)| buf.copy_from_slice(auth.as_slice()));
     }
 
+    /// Serialize the signature to a new `Vec`
     #[cfg(feature = "alloc")]
     #[cfg(test)]
     pub fn to_vec(&self) -> Vec<u8> {
@@ -54,14 +60,121 @@ impl<P: XmssParams> TryFrom<&[u8]> for XmssSig<P> {
     }
 }
 
-pub(crate) trait XmssParams: WotsParams + Sized {
+/// A borrowed view of an XMSS signature, either from an [`XmssSig`] or directly from bytes
+pub struct XmssSigRef<'a, P: XmssParams> {
+    /// The WOTS+ signature of the message
+    pub sig: &'a Array<Array<u8, P::N>, P::WotsSigLen>,
+    /// The authentication path of the leaf
+    pub auth: &'a Array<Array<u8, P::N>, P::HPrime>,
+}
+
+#[allow(clippy::expl_impl_clone_on_copy)] // Deriving would require `P: Clone`
+impl<P: XmssParams> Clone for XmssSigRef<'_, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: XmssParams> Copy for XmssSigRef<'_, P> {}
+
+impl<'a, P: XmssParams> XmssSigRef<'a, P> {
+    /// Borrow a signature from `WotsSigLen + HPrime` consecutive hash values
+    pub(crate) fn from_chunks(chunks: &'a [Array<u8, P::N>]) -> Self {
+        let (sig, auth) = chunks.split_at(P::WotsSigLen::USIZE);
+        XmssSigRef {
+            sig: sig.try_into().expect("chunk count matches WotsSigLen"),
+            auth: auth.try_into().expect("chunk count matches HPrime"),
+        }
+    }
+}
+
+impl<'a, P: XmssParams> From<&'a XmssSig<P>> for XmssSigRef<'a, P> {
+    fn from(sig: &'a XmssSig<P>) -> Self {
+        XmssSigRef {
+            sig: &sig.sig.0,
+            auth: &sig.auth,
+        }
+    }
+}
+
+/// Every node of an XMSS tree, so that authentication paths can be read rather than recomputed
+#[cfg(feature = "alloc")]
+pub(crate) struct XmssTree<P: XmssParams> {
+    // The 2^H' leaves, followed by the nodes of each greater height up to the root
+    nodes: Vec<Array<u8, P::N>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<P: XmssParams> XmssTree<P> {
+    /// Compute every node of the tree at `adrs`
+    pub(crate) fn new(
+        sk_seed: &SkSeed<P::N>,
+        pk_seed: &P::PkSeedState,
+        adrs: &address::WotsHash,
+    ) -> Self {
+        let leaves = 1u32 << P::HPrime::U32;
+        debug_assert!(leaves % 4 == 0, "H' is at least 2");
+
+        let mut nodes = Vec::with_capacity((2 << P::HPrime::USIZE) - 1);
+        for i in (0..leaves).step_by(4) {
+            nodes.extend(P::xmss_node_x4(
+                sk_seed,
+                [i, i + 1, i + 2, i + 3],
+                0,
+                pk_seed,
+                adrs,
+            ));
+        }
+
+        let mut start = 0; // Index of the first node one level down
+        for height in 1..=P::HPrime::U32 {
+            let children = start..nodes.len();
+            for node in 0..leaves >> height {
+                let left = children.start + 2 * node as usize;
+                let parent =
+                    P::xmss_parent(node, height, &nodes[left], &nodes[left + 1], pk_seed, adrs);
+                nodes.push(parent);
+            }
+            start = children.end;
+        }
+        debug_assert_eq!(nodes.len(), (2 << P::HPrime::USIZE) - 1);
+
+        XmssTree { nodes }
+    }
+
+    /// The nodes at `height`
+    fn level(&self, height: u32) -> &[Array<u8, P::N>] {
+        let total = 2 << P::HPrime::USIZE;
+        let start = total - (total >> height);
+        &self.nodes[start..start + (total >> (height + 1))]
+    }
+
+    /// The authentication path of leaf `idx`, as computed by `xmss_auth`
+    pub(crate) fn auth(&self, idx: u32) -> Array<Array<u8, P::N>, P::HPrime> {
+        Array::from_fn(|j| {
+            let height = u32::try_from(j).expect("H' is less than 2^32");
+            self.level(height)[(idx >> height ^ 1) as usize].clone()
+        })
+    }
+
+    /// The root of the tree, as computed by `xmss_node`
+    pub(crate) fn root(&self) -> &Array<u8, P::N> {
+        self.nodes.last().expect("the tree is not empty")
+    }
+}
+
+/// The XMSS many-time signature scheme, with trees of `2^H'` WOTS+ key pairs
+pub trait XmssParams: WotsParams + Sized {
+    /// The height `H'` of each XMSS tree
     type HPrime: ArraySize + Debug + Eq;
 
+    /// Algorithm 9: compute the node at `height` and index `node` of the tree at `adrs`.
+    /// The root is the node at height `H'` and index 0.
     fn xmss_node(
         sk_seed: &SkSeed<Self::N>,
         node: u32,
         height: u32,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::WotsHash,
     ) -> Array<u8, Self::N> {
         debug_assert!(height <= Self::HPrime::U32);
@@ -70,20 +183,79 @@ pub(crate) trait XmssParams: WotsParams + Sized {
             let mut adrs = adrs.clone();
             adrs.key_pair_adrs.set(node);
             Self::wots_pk_gen(sk_seed, pk_seed, &adrs)
+        } else if height == 1 {
+            let lnode = Self::xmss_node(sk_seed, 2 * node, 0, pk_seed, adrs);
+            let rnode = Self::xmss_node(sk_seed, 2 * node + 1, 0, pk_seed, adrs);
+            Self::xmss_parent(node, height, &lnode, &rnode, pk_seed, adrs)
+        } else {
+            // Compute the four subtrees two levels down together
+            let [a, b, c, d] = Self::xmss_node_x4(
+                sk_seed,
+                [0, 1, 2, 3].map(|k| 4 * node + k),
+                height - 2,
+                pk_seed,
+                adrs,
+            );
+            let lnode = Self::xmss_parent(2 * node, height - 1, &a, &b, pk_seed, adrs);
+            let rnode = Self::xmss_parent(2 * node + 1, height - 1, &c, &d, pk_seed, adrs);
+            Self::xmss_parent(node, height, &lnode, &rnode, pk_seed, adrs)
+        }
+    }
+
+    /// Compute the node at `height` and index `node` from its children
+    #[doc(hidden)]
+    fn xmss_parent(
+        node: u32,
+        height: u32,
+        lnode: &Array<u8, Self::N>,
+        rnode: &Array<u8, Self::N>,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::WotsHash,
+    ) -> Array<u8, Self::N> {
+        let mut adrs = adrs.tree_adrs();
+        adrs.tree_height.set(height);
+        adrs.tree_index.set(node);
+        Self::h(pk_seed, &adrs, lnode, rnode)
+    }
+
+    /// Four independent instances of `xmss_node` at the same height, computed together with
+    /// `wots_pk_gen_x4` and `h_x4`
+    #[doc(hidden)]
+    fn xmss_node_x4(
+        sk_seed: &SkSeed<Self::N>,
+        nodes: [u32; 4],
+        height: u32,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::WotsHash,
+    ) -> [Array<u8, Self::N>; 4] {
+        if height == 0 {
+            let adrs = nodes.map(|node| {
+                let mut adrs = adrs.clone();
+                adrs.key_pair_adrs.set(node);
+                adrs
+            });
+            Self::wots_pk_gen_x4(sk_seed, pk_seed, &adrs)
         } else {
-            let lnode = Self::xmss_node(sk_seed, 2 * node, height - 1, pk_seed, adrs);
-            let rnode = Self::xmss_node(sk_seed, 2 * node + 1, height - 1, pk_seed, adrs);
-            let mut adrs = adrs.tree_adrs();
-            adrs.tree_height.set(height);
-            adrs.tree_index.set(node);
-            Self::h(pk_seed, &adrs, &lnode, &rnode)
+            let lnodes =
+                Self::xmss_node_x4(sk_seed, nodes.map(|n| 2 * n), height - 1, pk_seed, adrs);
+            let rnodes =
+                Self::xmss_node_x4(sk_seed, nodes.map(|n| 2 * n + 1), height - 1, pk_seed, adrs);
+            let adrs = nodes.map(|node| {
+                let mut adrs = adrs.tree_adrs();
+                adrs.tree_height.set(height);
+                adrs.tree_index.set(node);
+                adrs
+            });
+            Self::h_x4(pk_seed, &adrs, &lnodes, &rnodes)
         }
     }
 
+    /// Algorithm 10: sign the `N`-byte message `m` with leaf `idx` of the tree at `adrs`
+    #[cfg_attr(feature = "rayon", allow(dead_code))] // ht_sign builds the trees separately
     fn xmss_sign(
         m: &Array<u8, Self::N>,
         sk_seed: &SkSeed<Self::N>,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         idx: u32,
         adrs: &address::WotsHash,
     ) -> XmssSig<Self> {
@@ -91,31 +263,55 @@ pub(crate) trait XmssParams: WotsParams + Sized {
         adrs.key_pair_adrs.set(idx);
 
         let sig = Self::wots_sign(m, sk_seed, pk_seed, &adrs);
+        let auth = Self::xmss_auth(sk_seed, pk_seed, idx, &adrs);
+
+        XmssSig { sig, auth }
+    }
 
+    /// Compute the authentication path of leaf `idx`, which does not depend on the signed message
+    #[doc(hidden)]
+    fn xmss_auth(
+        sk_seed: &SkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
+        idx: u32,
+        adrs: &address::WotsHash,
+    ) -> Array<Array<u8, Self::N>, Self::HPrime> {
         let mut auth = Array::<Array<u8, Self::N>, Self::HPrime>::default();
         let mut idx = idx;
         for j in 0..Self::HPrime::U32 {
-            let node = Self::xmss_node(sk_seed, idx ^ 1, j, pk_seed, &adrs);
+            let node = Self::xmss_node(sk_seed, idx ^ 1, j, pk_seed, adrs);
             idx >>= 1;
             auth[j as usize] = node;
         }
-
-        XmssSig { sig, auth }
+        auth
     }
 
+    /// Algorithm 11: compute the root of the tree at `adrs` from the signature `sig` of `m` by
+    /// leaf `idx`. The signature is valid if the result is the root of that tree.
     fn xmss_pk_from_sig(
         idx: u32,
-        sig: &XmssSig<Self>,
+        sig: XmssSigRef<'_, Self>,
         m: &Array<u8, Self::N>,
-        pk_seed: &PkSeed<Self::N>,
+        pk_seed: &Self::PkSeedState,
         adrs: &address::WotsHash,
-    ) -> Array<u8, Self::N>
-where {
+    ) -> Array<u8, Self::N> {
         let mut adrs = adrs.clone();
         adrs.key_pair_adrs.set(idx);
 
-        let mut node = Self::wots_pk_from_sig(&sig.sig, m, pk_seed, &adrs);
+        let node = Self::wots_pk_from_sig(sig.sig, m, pk_seed, &adrs);
+        Self::xmss_root(idx, node, sig.auth, pk_seed, &adrs)
+    }
 
+    /// Compute the root of the tree from the node of leaf `idx` and its authentication path
+    #[doc(hidden)]
+    fn xmss_root(
+        idx: u32,
+        leaf: Array<u8, Self::N>,
+        auth: &Array<Array<u8, Self::N>, Self::HPrime>,
+        pk_seed: &Self::PkSeedState,
+        adrs: &address::WotsHash,
+    ) -> Array<u8, Self::N> {
+        let mut node = leaf;
         let mut adrs = adrs.tree_adrs();
 
         let mut idx = idx;
@@ -125,9 +321,9 @@ where {
             (idx, rem) = (idx >> 1, idx & 1);
             adrs.tree_index.set(idx);
             if rem == 0 {
-                node = Self::h(pk_seed, &adrs, &node, &sig.auth[j as usize]);
+                node = Self::h(pk_seed, &adrs, &node, &auth[j as usize]);
             } else {
-                node = Self::h(pk_seed, &adrs, &sig.auth[j as usize], &node);
+                node = Self::h(pk_seed, &adrs, &auth[j as usize], &node);
             }
         }
         node
@@ -210,7 +406,7 @@ mod tests {
 
         let sk_seed = SkSeed::new(&mut rng);
 
-        let pk_seed = PkSeed::new(&mut rng);
+        let pk_seed = PkSeed::new(&mut rng).into();
 
         let mut msg = Array::<u8, _>::default();
         rng.fill_bytes(msg.as_mut_slice());
@@ -222,7 +418,7 @@ mod tests {
         let pk = Xmss::xmss_node(&sk_seed, 0, Xmss::HPrime::U32, &pk_seed, &adrs);
 
         let sig = Xmss::xmss_sign(&msg, &sk_seed, &pk_seed, idx, &adrs);
-        let pk_recovered = Xmss::xmss_pk_from_sig(idx, &sig, &msg, &pk_seed, &adrs);
+        let pk_recovered = Xmss::xmss_pk_from_sig(idx, (&sig).into(), &msg, &pk_seed, &adrs);
 
         assert_eq!(pk, pk_recovered);
     }
@@ -235,7 +431,7 @@ mod tests {
 
         let sk_seed = SkSeed::new(&mut rng);
 
-        let pk_seed = PkSeed::new(&mut rng);
+        let pk_seed = PkSeed::new(&mut rng).into();
 
         let mut msg = Array::<u8, _>::default();
         rng.fill_bytes(msg.as_mut_slice());
@@ -251,7 +447,7 @@ mod tests {
         // Tweak message
         msg[0] ^= 0xff;
 
-        let pk_recovered = Xmss::xmss_pk_from_sig(idx, &sig, &msg, &pk_seed, &adrs);
+        let pk_recovered = Xmss::xmss_pk_from_sig(idx, (&sig).into(), &msg, &pk_seed, &adrs);
 
         assert_ne!(pk, pk_recovered);
     }
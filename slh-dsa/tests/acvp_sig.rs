@@ -1,22 +1,26 @@
+//! `sigGen` test vectors in the ACVP format.
+//!
+//! The NIST ACVP vectors test `slh_sign_internal`. The cross-implementation vectors test the
+//! `external` interface, with pure and pre-hash (HashSLH-DSA) messages. They were generated with
+//! the independent `fips205` crate (version 0.4.1) and are not NIST vectors.
 #![allow(non_snake_case)]
 #![cfg(feature = "alloc")]
 
+mod common;
+
+use common::{with_prehash, HexString};
 use serde::Deserialize;
 use slh_dsa::*;
 
-const KEYGEN_KAT_JSON: &str = include_str!("acvp/SLH-DSA-sigGen-FIPS205/internalProjection.json");
-
-#[derive(Deserialize, Debug)]
-#[serde(transparent)]
-struct HexString {
-    #[serde(with = "hex::serde")]
-    data: Vec<u8>,
-}
+const SIGGEN_KAT_JSON: &str = include_str!("acvp/SLH-DSA-sigGen-FIPS205/internalProjection.json");
+const SIGGEN_CROSS_IMPL_JSON: &str = include_str!("cross_impl/SLH-DSA-sigGen-FIPS205.json");
 
 #[derive(Deserialize, Debug)]
 struct TestCase {
     sk: HexString,
     message: HexString,
+    context: Option<HexString>,
+    hashAlg: Option<String>,
     signature: HexString,
     additionalRandomness: Option<HexString>,
 }
@@ -24,6 +28,8 @@ struct TestCase {
 #[derive(Deserialize, Debug)]
 struct TestGroup {
     parameterSet: String,
+    signatureInterface: Option<String>,
+    preHash: Option<String>,
     tests: Vec<TestCase>,
 }
 
@@ -33,37 +39,61 @@ struct TestFile {
 }
 
 macro_rules! parameter_case {
-    ($param:ident, $test_case:expr) => {{
+    ($param:ident, $test_group:expr, $test_case:expr) => {{
         let sk = SigningKey::<$param>::try_from($test_case.sk.data.as_slice()).unwrap();
         let opt_rand = $test_case
             .additionalRandomness
             .as_ref()
             .map(|x| x.data.as_slice());
-        let sig = sk.slh_sign_internal(&[$test_case.message.data.as_slice()], opt_rand);
+        let msg = $test_case.message.data.as_slice();
+        let ctx = $test_case
+            .context
+            .as_ref()
+            .map_or(&[][..], |x| x.data.as_slice());
+
+        macro_rules! sign_prehash {
+            ($hash:ty, $digest:expr) => {
+                sk.try_sign_prehash_with_context::<$hash>(&$digest, ctx, opt_rand)
+                    .unwrap()
+            };
+        }
+
+        let sig = match (
+            $test_group.signatureInterface.as_deref(),
+            $test_group.preHash.as_deref(),
+        ) {
+            (None | Some("internal"), _) => sk.slh_sign_internal(&[msg], opt_rand),
+            (Some("external"), Some("pure")) => {
+                sk.try_sign_with_context(msg, ctx, opt_rand).unwrap()
+            }
+            (Some("external"), Some("preHash")) => {
+                with_prehash!($test_case.hashAlg.as_deref().unwrap(), msg, sign_prehash)
+            }
+            (interface, pre_hash) => panic!("Unknown test group: {interface:?}, {pre_hash:?}"),
+        };
         assert_eq!(sig.to_vec(), $test_case.signature.data);
     }};
 }
 
-#[test]
-fn test_sign_cvp() {
+fn test_sign_cvp(json: &str) {
     let mut i = 0;
-    let test_file: TestFile = serde_json::from_str(KEYGEN_KAT_JSON).unwrap();
+    let test_file: TestFile = serde_json::from_str(json).unwrap();
     for test_group in test_file.testGroups {
-        let p = test_group.parameterSet;
-        for test_case in test_group.tests {
-            match p.as_str() {
-                Shake128f::NAME => parameter_case!(Shake128f, test_case),
-                Shake128s::NAME => parameter_case!(Shake128s, test_case),
-                Shake192f::NAME => parameter_case!(Shake192f, test_case),
-                Shake192s::NAME => parameter_case!(Shake192s, test_case),
-                Shake256f::NAME => parameter_case!(Shake256f, test_case),
-                Shake256s::NAME => parameter_case!(Shake256s, test_case),
-                Sha2_128f::NAME => parameter_case!(Sha2_128f, test_case),
-                Sha2_128s::NAME => parameter_case!(Sha2_128s, test_case),
-                Sha2_192f::NAME => parameter_case!(Sha2_192f, test_case),
-                Sha2_192s::NAME => parameter_case!(Sha2_192s, test_case),
-                Sha2_256f::NAME => parameter_case!(Sha2_256f, test_case),
-                Sha2_256s::NAME => parameter_case!(Sha2_256s, test_case),
+        let p = test_group.parameterSet.as_str();
+        for test_case in &test_group.tests {
+            match p {
+                Shake128f::NAME => parameter_case!(Shake128f, test_group, test_case),
+                Shake128s::NAME => parameter_case!(Shake128s, test_group, test_case),
+                Shake192f::NAME => parameter_case!(Shake192f, test_group, test_case),
+                Shake192s::NAME => parameter_case!(Shake192s, test_group, test_case),
+                Shake256f::NAME => parameter_case!(Shake256f, test_group, test_case),
+                Shake256s::NAME => parameter_case!(Shake256s, test_group, test_case),
+                Sha2_128f::NAME => parameter_case!(Sha2_128f, test_group, test_case),
+                Sha2_128s::NAME => parameter_case!(Sha2_128s, test_group, test_case),
+                Sha2_192f::NAME => parameter_case!(Sha2_192f, test_group, test_case),
+                Sha2_192s::NAME => parameter_case!(Sha2_192s, test_group, test_case),
+                Sha2_256f::NAME => parameter_case!(Sha2_256f, test_group, test_case),
+                Sha2_256s::NAME => parameter_case!(Sha2_256s, test_group, test_case),
                 _ => panic!("Unknown parameter set: {}", p),
             }
             i += 1;
@@ -71,3 +101,13 @@ fn test_sign_cvp() {
     }
     print!("Number of test cases: {}", i);
 }
+
+#[test]
+fn test_sign_internal_cvp() {
+    test_sign_cvp(SIGGEN_KAT_JSON);
+}
+
+#[test]
+fn test_sign_cross_implementation() {
+    test_sign_cvp(SIGGEN_CROSS_IMPL_JSON);
+}
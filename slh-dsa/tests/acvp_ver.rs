@@ -1,22 +1,26 @@
+//! `sigVer` test vectors in the ACVP format.
+//!
+//! The NIST ACVP vectors test `slh_verify_internal`. The cross-implementation vectors test the
+//! `external` interface with pre-hash (HashSLH-DSA) messages. They were generated with
+//! the independent `fips205` crate (version 0.4.1) and are not NIST vectors.
 #![allow(non_snake_case)]
 #![cfg(feature = "alloc")]
 
+mod common;
+
+use common::{with_prehash, HexString};
 use serde::Deserialize;
 use slh_dsa::*;
 
-const KEYGEN_KAT_JSON: &str = include_str!("acvp/SLH-DSA-sigVer-FIPS205/internalProjection.json");
-
-#[derive(Deserialize, Debug)]
-#[serde(transparent)]
-struct HexString {
-    #[serde(with = "hex::serde")]
-    data: Vec<u8>,
-}
+const SIGVER_KAT_JSON: &str = include_str!("acvp/SLH-DSA-sigVer-FIPS205/internalProjection.json");
+const SIGVER_CROSS_IMPL_JSON: &str = include_str!("cross_impl/SLH-DSA-sigVer-FIPS205.json");
 
 #[derive(Deserialize, Debug)]
 struct TestCase {
     pk: HexString,
     message: HexString,
+    context: Option<HexString>,
+    hashAlg: Option<String>,
     signature: HexString,
     testPassed: bool,
 }
@@ -24,6 +28,8 @@ struct TestCase {
 #[derive(Deserialize, Debug)]
 struct TestGroup {
     parameterSet: String,
+    signatureInterface: Option<String>,
+    preHash: Option<String>,
     tests: Vec<TestCase>,
 }
 
@@ -33,10 +39,34 @@ struct TestFile {
 }
 
 macro_rules! parameter_case {
-    ($param:ident, $test_case:expr) => {{
-        let sk = VerifyingKey::<$param>::try_from($test_case.pk.data.as_slice()).unwrap();
+    ($param:ident, $test_group:expr, $test_case:expr) => {{
+        let vk = VerifyingKey::<$param>::try_from($test_case.pk.data.as_slice()).unwrap();
+        let msg = $test_case.message.data.as_slice();
+        let ctx = $test_case
+            .context
+            .as_ref()
+            .map_or(&[][..], |x| x.data.as_slice());
+
         if let Ok(sig) = $test_case.signature.data.as_slice().try_into() {
-            let success = sk.slh_verify_internal(&[$test_case.message.data.as_slice()], &sig);
+            macro_rules! verify_prehash {
+                ($hash:ty, $digest:expr) => {
+                    vk.try_verify_prehash_with_context::<$hash>(&$digest, ctx, &sig)
+                };
+            }
+
+            let success = match (
+                $test_group.signatureInterface.as_deref(),
+                $test_group.preHash.as_deref(),
+            ) {
+                (None | Some("internal"), _) => vk.slh_verify_internal(&[msg], &sig),
+                (Some("external"), Some("pure")) => vk.try_verify_with_context(msg, ctx, &sig),
+                (Some("external"), Some("preHash")) => {
+                    with_prehash!($test_case.hashAlg.as_deref().unwrap(), msg, verify_prehash)
+                }
+                (interface, pre_hash) => {
+                    panic!("Unknown test group: {interface:?}, {pre_hash:?}")
+                }
+            };
             assert_eq!($test_case.testPassed, success.is_ok());
         } else {
             assert!(!$test_case.testPassed);
@@ -44,26 +74,56 @@ macro_rules! parameter_case {
     }};
 }
 
-#[test]
-fn test_verify_cvp() {
+// Verify the external interface vectors again, borrowing the signatures with `SignatureRef`
+macro_rules! signature_ref_case {
+    ($param:ident, $test_group:expr, $test_case:expr) => {{
+        let vk = VerifyingKey::<$param>::try_from($test_case.pk.data.as_slice()).unwrap();
+        let msg = $test_case.message.data.as_slice();
+        let ctx = $test_case
+            .context
+            .as_ref()
+            .map_or(&[][..], |x| x.data.as_slice());
+
+        if let Ok(sig) = SignatureRef::<$param>::try_from($test_case.signature.data.as_slice()) {
+            macro_rules! verify_prehash {
+                ($hash:ty, $digest:expr) => {
+                    vk.try_verify_prehash_ref_with_context::<$hash>(&$digest, ctx, &sig)
+                };
+            }
+
+            let success = match $test_group.preHash.as_deref() {
+                Some("pure") => vk.try_verify_ref_with_context(msg, ctx, &sig),
+                Some("preHash") => {
+                    with_prehash!($test_case.hashAlg.as_deref().unwrap(), msg, verify_prehash)
+                }
+                pre_hash => panic!("Unknown test group: {pre_hash:?}"),
+            };
+            assert_eq!($test_case.testPassed, success.is_ok());
+        } else {
+            assert!(!$test_case.testPassed);
+        }
+    }};
+}
+
+fn test_verify_cvp(json: &str) {
     let mut i = 0;
-    let test_file: TestFile = serde_json::from_str(KEYGEN_KAT_JSON).unwrap();
+    let test_file: TestFile = serde_json::from_str(json).unwrap();
     for test_group in test_file.testGroups {
-        let p = test_group.parameterSet;
-        for test_case in test_group.tests {
-            match p.as_str() {
-                Shake128f::NAME => parameter_case!(Shake128f, test_case),
-                Shake128s::NAME => parameter_case!(Shake128s, test_case),
-                Shake192f::NAME => parameter_case!(Shake192f, test_case),
-                Shake192s::NAME => parameter_case!(Shake192s, test_case),
-                Shake256f::NAME => parameter_case!(Shake256f, test_case),
-                Shake256s::NAME => parameter_case!(Shake256s, test_case),
-                Sha2_128f::NAME => parameter_case!(Sha2_128f, test_case),
-                Sha2_128s::NAME => parameter_case!(Sha2_128s, test_case),
-                Sha2_192f::NAME => parameter_case!(Sha2_192f, test_case),
-                Sha2_192s::NAME => parameter_case!(Sha2_192s, test_case),
-                Sha2_256f::NAME => parameter_case!(Sha2_256f, test_case),
-                Sha2_256s::NAME => parameter_case!(Sha2_256s, test_case),
+        let p = test_group.parameterSet.as_str();
+        for test_case in &test_group.tests {
+            match p {
+                Shake128f::NAME => parameter_case!(Shake128f, test_group, test_case),
+                Shake128s::NAME => parameter_case!(Shake128s, test_group, test_case),
+                Shake192f::NAME => parameter_case!(Shake192f, test_group, test_case),
+                Shake192s::NAME => parameter_case!(Shake192s, test_group, test_case),
+                Shake256f::NAME => parameter_case!(Shake256f, test_group, test_case),
+                Shake256s::NAME => parameter_case!(Shake256s, test_group, test_case),
+                Sha2_128f::NAME => parameter_case!(Sha2_128f, test_group, test_case),
+                Sha2_128s::NAME => parameter_case!(Sha2_128s, test_group, test_case),
+                Sha2_192f::NAME => parameter_case!(Sha2_192f, test_group, test_case),
+                Sha2_192s::NAME => parameter_case!(Sha2_192s, test_group, test_case),
+                Sha2_256f::NAME => parameter_case!(Sha2_256f, test_group, test_case),
+                Sha2_256s::NAME => parameter_case!(Sha2_256s, test_group, test_case),
                 _ => panic!("Unknown parameter set: {}", p),
             }
             i += 1;
@@ -71,3 +131,39 @@ fn test_verify_cvp() {
     }
     print!("Number of test cases: {}", i);
 }
+
+#[test]
+fn test_verify_internal_cvp() {
+    test_verify_cvp(SIGVER_KAT_JSON);
+}
+
+#[test]
+fn test_verify_cross_implementation() {
+    test_verify_cvp(SIGVER_CROSS_IMPL_JSON);
+}
+
+#[test]
+fn test_verify_signature_ref_cross_implementation() {
+    let test_file: TestFile = serde_json::from_str(SIGVER_CROSS_IMPL_JSON).unwrap();
+    for test_group in test_file.testGroups {
+        assert_eq!(test_group.signatureInterface.as_deref(), Some("external"));
+        let p = test_group.parameterSet.as_str();
+        for test_case in &test_group.tests {
+            match p {
+                Shake128f::NAME => signature_ref_case!(Shake128f, test_group, test_case),
+                Shake128s::NAME => signature_ref_case!(Shake128s, test_group, test_case),
+                Shake192f::NAME => signature_ref_case!(Shake192f, test_group, test_case),
+                Shake192s::NAME => signature_ref_case!(Shake192s, test_group, test_case),
+                Shake256f::NAME => signature_ref_case!(Shake256f, test_group, test_case),
+                Shake256s::NAME => signature_ref_case!(Shake256s, test_group, test_case),
+                Sha2_128f::NAME => signature_ref_case!(Sha2_128f, test_group, test_case),
+                Sha2_128s::NAME => signature_ref_case!(Sha2_128s, test_group, test_case),
+                Sha2_192f::NAME => signature_ref_case!(Sha2_192f, test_group, test_case),
+                Sha2_192s::NAME => signature_ref_case!(Sha2_192s, test_group, test_case),
+                Sha2_256f::NAME => signature_ref_case!(Sha2_256f, test_group, test_case),
+                Sha2_256s::NAME => signature_ref_case!(Sha2_256s, test_group, test_case),
+                _ => panic!("Unknown parameter set: {}", p),
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Signing into a caller-provided buffer on a thread with a small stack, with and without the
+//! `alloc` feature
+
+use slh_dsa::*;
+use typenum::Unsigned;
+
+/// The stack bound documented on [`SigningKey::try_sign_with_context_to`], which holds for the
+/// optimized `dev` profile of the workspace
+const STACK_SIZE: usize = if cfg!(feature = "alloc") {
+    24 * 1024
+} else {
+    32 * 1024
+};
+
+fn test_sign_to<P: ParameterSet + 'static>() {
+    let sk = SigningKey::<P>::new(&mut rand::thread_rng());
+    let msg = b"Hello world";
+    let expected = sk.try_sign_with_context(msg, b"ctx", None).unwrap();
+
+    // The signature is written to the heap, so signing fits in a small stack
+    let mut sig = vec![0u8; P::SigLen::USIZE];
+    let sig = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            sk.try_sign_with_context_to(msg, b"ctx", None, &mut sig)
+                .unwrap();
+            sig
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(sig, expected.to_bytes().as_slice());
+}
+
+macro_rules! parameter_set_tests {
+    ($($name:ident: $param:ty,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                test_sign_to::<$param>();
+            }
+        )*
+    };
+}
+
+parameter_set_tests! {
+    test_sign_to_shake128f: Shake128f,
+    test_sign_to_shake128s: Shake128s,
+    test_sign_to_shake192f: Shake192f,
+    test_sign_to_shake192s: Shake192s,
+    test_sign_to_shake256f: Shake256f,
+    test_sign_to_shake256s: Shake256s,
+    test_sign_to_sha2_128f: Sha2_128f,
+    test_sign_to_sha2_128s: Sha2_128s,
+    test_sign_to_sha2_192f: Sha2_192f,
+    test_sign_to_sha2_192s: Sha2_192s,
+    test_sign_to_sha2_256f: Sha2_256f,
+    test_sign_to_sha2_256s: Sha2_256s,
+}
@@ -0,0 +1,46 @@
+//! Helpers shared by the ACVP tests
+#![allow(dead_code)]
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(transparent)]
+pub struct HexString {
+    #[serde(with = "hex::serde")]
+    pub data: Vec<u8>,
+}
+
+/// Expand to `$f!(H, digest)`, with `H` the HashSLH-DSA pre-hash function called `$hash_alg` in
+/// ACVP vectors and `digest` the hash of `$msg` under `H`
+macro_rules! with_prehash {
+    ($hash_alg:expr, $msg:expr, $f:ident) => {{
+        macro_rules! digest {
+            ($hash:ty) => {
+                <$hash as digest::Digest>::digest($msg)
+            };
+        }
+        macro_rules! xof {
+            ($hash:ty, $len:expr) => {{
+                let mut digest = [0u8; $len];
+                <$hash as digest::ExtendableOutput>::digest_xof($msg, &mut digest);
+                digest
+            }};
+        }
+        match $hash_alg {
+            "SHA2-224" => $f!(sha2::Sha224, digest!(sha2::Sha224)),
+            "SHA2-256" => $f!(sha2::Sha256, digest!(sha2::Sha256)),
+            "SHA2-384" => $f!(sha2::Sha384, digest!(sha2::Sha384)),
+            "SHA2-512" => $f!(sha2::Sha512, digest!(sha2::Sha512)),
+            "SHA2-512/224" => $f!(sha2::Sha512_224, digest!(sha2::Sha512_224)),
+            "SHA2-512/256" => $f!(sha2::Sha512_256, digest!(sha2::Sha512_256)),
+            "SHA3-224" => $f!(sha3::Sha3_224, digest!(sha3::Sha3_224)),
+            "SHA3-256" => $f!(sha3::Sha3_256, digest!(sha3::Sha3_256)),
+            "SHA3-384" => $f!(sha3::Sha3_384, digest!(sha3::Sha3_384)),
+            "SHA3-512" => $f!(sha3::Sha3_512, digest!(sha3::Sha3_512)),
+            "SHAKE-128" => $f!(sha3::Shake128, xof!(sha3::Shake128, 32)),
+            "SHAKE-256" => $f!(sha3::Shake256, xof!(sha3::Shake256, 64)),
+            alg => panic!("Unknown hash algorithm: {}", alg),
+        }
+    }};
+}
+pub(crate) use with_prehash;
@@ -0,0 +1,185 @@
+//! Tests of the low-level components exposed by the `hazmat` feature, through the public API
+#![cfg(feature = "hazmat")]
+
+use hybrid_array::Array;
+use slh_dsa::hazmat::address::{Address, ForsTree, WotsHash};
+use slh_dsa::hazmat::fors::{ForsParams, ForsSignature, ForsSignatureRef};
+use slh_dsa::hazmat::xmss::XmssSigRef;
+use slh_dsa::hazmat::{HashSuite, PkSeed, SkPrf, SkSeed};
+use slh_dsa::{Sha2_128f, Sha2_256f, Shake128f};
+use typenum::Unsigned;
+
+fn seeds<P: HashSuite>() -> (SkSeed<P::N>, P::PkSeedState) {
+    let sk_seed = SkSeed::try_from(&[1u8; 32][..P::N::USIZE]).unwrap();
+    let pk_seed = PkSeed::try_from(&[2u8; 32][..P::N::USIZE]).unwrap().into();
+    (sk_seed, pk_seed)
+}
+
+fn wots_adrs() -> WotsHash {
+    let mut adrs = WotsHash::default();
+    adrs.layer_adrs.set(1);
+    adrs.tree_adrs_low.set(3);
+    adrs.key_pair_adrs.set(2);
+    adrs
+}
+
+fn test_wots<P: ForsParams>() {
+    let (sk_seed, pk_seed) = seeds::<P>();
+    let adrs = wots_adrs();
+    let m = Array::<u8, P::N>::from_fn(|i| i as u8);
+
+    // Chains compose: 3 steps from the start followed by 12 more is the full chain
+    let x = Array::<u8, P::N>::default();
+    let mid = P::wots_chain(&x, 0, 3, &pk_seed, &adrs);
+    assert_eq!(
+        P::wots_chain(&mid, 3, 12, &pk_seed, &adrs),
+        P::wots_chain(&x, 0, 15, &pk_seed, &adrs)
+    );
+
+    let pk = P::wots_pk_gen(&sk_seed, &pk_seed, &adrs);
+    let sig = P::wots_sign(&m, &sk_seed, &pk_seed, &adrs);
+    assert_eq!(P::wots_pk_from_sig(&sig.0, &m, &pk_seed, &adrs), pk);
+
+    let mut m_modified = m.clone();
+    m_modified[0] ^= 1;
+    assert_ne!(
+        P::wots_pk_from_sig(&sig.0, &m_modified, &pk_seed, &adrs),
+        pk
+    );
+}
+
+fn test_xmss<P: ForsParams>() {
+    let (sk_seed, pk_seed) = seeds::<P>();
+    let mut adrs = wots_adrs();
+    adrs.key_pair_adrs.set(0);
+    let m = Array::<u8, P::N>::from_fn(|i| i as u8);
+
+    let root = P::xmss_node(&sk_seed, 0, P::HPrime::U32, &pk_seed, &adrs);
+
+    // The leaves are the WOTS+ public keys of the key pairs of the tree
+    let idx = (1 << P::HPrime::U32) - 1;
+    let mut leaf_adrs = adrs.clone();
+    leaf_adrs.key_pair_adrs.set(idx);
+    assert_eq!(
+        P::xmss_node(&sk_seed, idx, 0, &pk_seed, &adrs),
+        P::wots_pk_gen(&sk_seed, &pk_seed, &leaf_adrs)
+    );
+
+    let sig = P::xmss_sign(&m, &sk_seed, &pk_seed, idx, &adrs);
+    assert_eq!(
+        P::xmss_pk_from_sig(idx, XmssSigRef::from(&sig), &m, &pk_seed, &adrs),
+        root
+    );
+    assert_ne!(
+        P::xmss_pk_from_sig(idx - 1, XmssSigRef::from(&sig), &m, &pk_seed, &adrs),
+        root
+    );
+}
+
+fn test_fors<P: ForsParams>() {
+    let (sk_seed, pk_seed) = seeds::<P>();
+    let adrs = ForsTree::new(3, 2);
+    let md = Array::<u8, P::MD>::from_fn(|i| (i * 37) as u8);
+
+    // The public key compresses the roots of the K trees
+    let roots = Array::<Array<u8, P::N>, P::K>::from_fn(|i| {
+        P::fors_node(&sk_seed, i as u32, P::A::U32, &pk_seed, &adrs)
+    });
+    let pk = P::t(&pk_seed, &adrs.fors_roots(), &roots);
+
+    let sig = P::fors_sign(&md, &sk_seed, &pk_seed, &adrs);
+    assert_eq!(
+        P::fors_pk_from_sig(&ForsSignatureRef::from(&sig), &md, &pk_seed, &adrs),
+        pk
+    );
+
+    // The secret values are those of the leaves selected by the digest
+    let first_leaf = (u32::from(md[0]) << 8 | u32::from(md[1])) >> (16 - P::A::U32);
+    assert_eq!(
+        sig.0[0].sk,
+        P::fors_sk_gen(&sk_seed, &pk_seed, &adrs, first_leaf)
+    );
+
+    let mut bytes = vec![0u8; ForsSignature::<P>::SIZE];
+    sig.write_to(&mut bytes);
+    let sig_ref = ForsSignatureRef::<P>::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(P::fors_pk_from_sig(&sig_ref, &md, &pk_seed, &adrs), pk);
+
+    let mut md_modified = md.clone();
+    md_modified[0] ^= 0x80;
+    assert_ne!(
+        P::fors_pk_from_sig(&sig_ref, &md_modified, &pk_seed, &adrs),
+        pk
+    );
+}
+
+macro_rules! test_hazmat {
+    ($($name:ident: $p:ty),*) => {
+        $(
+            #[test]
+            fn $name() {
+                test_wots::<$p>();
+                test_xmss::<$p>();
+                test_fors::<$p>();
+            }
+        )*
+    };
+}
+
+test_hazmat!(shake_128f: Shake128f, sha2_128f: Sha2_128f, sha2_256f: Sha2_256f);
+
+#[test]
+fn test_address_layout() {
+    let mut adrs = WotsHash::default();
+    adrs.layer_adrs.set(0x01);
+    adrs.tree_adrs_low.set(0x0203_0405_0607_0809);
+    adrs.key_pair_adrs.set(0x0a0b_0c0d);
+    adrs.chain_adrs.set(0x0e);
+    adrs.hash_adrs.set(0x0f);
+
+    let mut expected = [0u8; 32];
+    expected[3] = 0x01;
+    expected[8..16].copy_from_slice(&[2, 3, 4, 5, 6, 7, 8, 9]);
+    expected[20..24].copy_from_slice(&[0x0a, 0x0b, 0x0c, 0x0d]);
+    expected[27] = 0x0e;
+    expected[31] = 0x0f;
+    assert_eq!(adrs.as_ref(), &expected);
+
+    let compressed = adrs.compressed();
+    assert_eq!(compressed[0], 0x01);
+    assert_eq!(&compressed[1..9], &expected[8..16]);
+    assert_eq!(compressed[9], 0);
+    assert_eq!(&compressed[10..], &expected[20..]);
+
+    // Conversions keep the layer, tree and key pair, set the type, and clear the rest
+    let pk_adrs = adrs.pk_adrs();
+    assert_eq!(pk_adrs.as_ref()[..16], expected[..16]);
+    assert_eq!(pk_adrs.as_ref()[19], 1);
+    assert_eq!(pk_adrs.key_pair_adrs.get(), 0x0a0b_0c0d);
+    assert_eq!(pk_adrs.as_ref()[24..], [0; 8]);
+
+    let tree_adrs = adrs.tree_adrs();
+    assert_eq!(tree_adrs.as_ref()[19], 2);
+    assert_eq!(tree_adrs.tree_adrs_low.get(), 0x0203_0405_0607_0809);
+    assert_eq!(tree_adrs.as_ref()[20..], [0; 12]);
+
+    let prf_adrs = adrs.prf_adrs();
+    assert_eq!(prf_adrs.as_ref()[19], 5);
+    assert_eq!(prf_adrs.key_pair_adrs.get(), 0x0a0b_0c0d);
+    assert_eq!(prf_adrs.as_ref()[24..], [0; 8]);
+
+    let mut fors_adrs = ForsTree::new(0x0203_0405_0607_0809, 7);
+    fors_adrs.tree_index.set(9);
+    assert_eq!(fors_adrs.as_ref()[19], 3);
+    assert_eq!(fors_adrs.prf_adrs().as_ref()[19], 6);
+    assert_eq!(fors_adrs.prf_adrs().tree_index.get(), 9);
+    assert_eq!(fors_adrs.fors_roots().as_ref()[19], 4);
+    assert_eq!(fors_adrs.fors_roots().key_pair_adrs.get(), 7);
+}
+
+#[test]
+fn test_seed_wrong_length() {
+    assert!(SkSeed::<typenum::U16>::try_from(&[0u8; 15][..]).is_err());
+    assert!(SkPrf::<typenum::U16>::try_from(&[0u8; 17][..]).is_err());
+    assert!(PkSeed::<typenum::U16>::try_from(&[0u8; 32][..]).is_err());
+}